@@ -1,8 +1,36 @@
+use crate::Track;
+
 #[derive(Debug, Clone)]
 pub struct Album {
     pub id: i64,
     pub name: String,
     pub artist: Option<String>,
     pub year: Option<u32>,
+    pub release_id: Option<String>,
+    pub compilation: bool,
+    pub track_count: i64,
+    pub disc_count: i64,
+    pub duration: f32,
     pub sample_track_path: Option<String>,
 }
+
+pub(crate) fn album_key(track: &Track) -> Option<String> {
+    let name = track.album()?.trim().to_lowercase();
+    if let Some(release_id) = track.release_id() {
+        return Some(format!("mbid:{}", release_id.trim().to_lowercase()));
+    }
+    if track.compilation() {
+        let dir = track.path().parent()?.to_string_lossy();
+        return Some(format!("compilation:{dir}\u{1f}{name}"));
+    }
+    match track.album_artist() {
+        Some(artist) => Some(format!(
+            "artist:{}\u{1f}{name}",
+            artist.trim().to_lowercase()
+        )),
+        None => {
+            let dir = track.path().parent()?.to_string_lossy();
+            Some(format!("dir:{dir}\u{1f}{name}"))
+        }
+    }
+}
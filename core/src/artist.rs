@@ -0,0 +1,55 @@
+#[derive(Debug, Clone)]
+pub struct Artist {
+    pub id: i64,
+    pub name: String,
+    pub sort_name: Option<String>,
+    pub album_count: i64,
+    pub track_count: i64,
+    pub sample_track_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtistRole {
+    Primary,
+    Featured,
+    Remixer,
+    Composer,
+    Conductor,
+    Performer,
+    AlbumArtist,
+}
+
+impl ArtistRole {
+    pub const ALL: [ArtistRole; 7] = [
+        ArtistRole::Primary,
+        ArtistRole::Featured,
+        ArtistRole::Remixer,
+        ArtistRole::Composer,
+        ArtistRole::Conductor,
+        ArtistRole::Performer,
+        ArtistRole::AlbumArtist,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtistRole::Primary => "primary",
+            ArtistRole::Featured => "featured",
+            ArtistRole::Remixer => "remixer",
+            ArtistRole::Composer => "composer",
+            ArtistRole::Conductor => "conductor",
+            ArtistRole::Performer => "performer",
+            ArtistRole::AlbumArtist => "album_artist",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|role| role.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtistCredit {
+    pub name: String,
+    pub sort_name: Option<String>,
+    pub role: ArtistRole,
+}
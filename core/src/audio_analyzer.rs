@@ -109,6 +109,14 @@ impl AudioAnalyzer {
         }
     }
 
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        if (sample_rate - self.sample_rate).abs() > 1.0 {
+            self.sample_rate = sample_rate;
+            self.bin_map = Self::compute_bin_map(sample_rate);
+            self.buffer_pos = 0;
+        }
+    }
+
     fn compute_bin_map(sample_rate: f32) -> [(usize, usize); NUM_BINS] {
         let mut map = [(0usize, 0usize); NUM_BINS];
         let freq_per_bin = sample_rate / FFT_SIZE as f32;
@@ -202,13 +210,17 @@ impl AudioAnalyzer {
 }
 
 impl Effect for AudioAnalyzer {
+    fn init(&mut self, sample_rate: u32, _internal_buffer_size: usize) {
+        self.set_sample_rate(sample_rate as f32);
+    }
+
+    fn on_change_sample_rate(&mut self, sample_rate: u32) {
+        self.set_sample_rate(sample_rate as f32);
+    }
+
     fn process(&mut self, input: &mut [Frame], dt: f64, _info: &Info) {
         if dt > 0.0 {
-            let sr = (1.0 / dt) as f32;
-            if (sr - self.sample_rate).abs() > 1.0 {
-                self.sample_rate = sr;
-                self.bin_map = Self::compute_bin_map(sr);
-            }
+            self.set_sample_rate((1.0 / dt) as f32);
         }
 
         for frame in input.iter() {
@@ -1,18 +1,36 @@
+use cpal::traits::{DeviceTrait, HostTrait};
 use crossbeam::atomic::AtomicCell;
-use std::{path::Path, sync::Arc, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 use kira::{
-    AudioManager, AudioManagerSettings, DefaultBackend, Easing, PlaySoundError, StartTime, Tween,
-    backend::cpal,
+    AudioManager, AudioManagerSettings, Easing, PlaySoundError, StartTime, Tween,
     sound::{
-        FromFileError, PlaybackState,
+        EndPosition, FromFileError, PlaybackPosition, PlaybackState, Region,
         streaming::{StreamingSoundData, StreamingSoundHandle},
     },
     track::MainTrackBuilder,
 };
 
-use crate::audio_analyzer::{AudioAnalyzerBuilder, VisData};
+use crate::{
+    audio_analyzer::{AudioAnalyzerBuilder, VisData},
+    device_watcher::DeviceWatcher,
+    equalizer::{EqParams, EqPreset, EqualizerBuilder},
+    format::AudioFormat,
+    headphone::HeadphoneProfile,
+    loop_region::LoopRegion,
+    output::{
+        AudioSettings, HeadlessOutput, OutputBackend, OutputError, OutputSettings, OutputTarget,
+    },
+    pitch_shifter::PitchShifterBuilder,
+    tempo::Tempo,
+};
+
+const SILENCE_DB: f32 = -60.0;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum BackendState {
@@ -22,47 +40,153 @@ pub(crate) enum BackendState {
     Finished,
 }
 
+struct EffectParams {
+    eq: Arc<AtomicCell<EqParams>>,
+    correction: Arc<AtomicCell<EqParams>>,
+    pitch_ratio: Arc<AtomicCell<f32>>,
+    visualization: Arc<AtomicCell<VisData>>,
+}
+
+impl EffectParams {
+    fn inherit(&self, previous: &EffectParams) {
+        self.eq.store(previous.eq.load());
+        self.correction.store(previous.correction.load());
+        self.pitch_ratio.store(previous.pitch_ratio.load());
+    }
+}
+
 pub struct Backend {
-    manager: AudioManager,
+    manager: AudioManager<OutputBackend>,
+    params: EffectParams,
     sound: Option<StreamingSoundHandle<FromFileError>>,
+    path: Option<PathBuf>,
+    slice: Option<Region>,
     volume: f32,
-    visualization_data: Arc<AtomicCell<VisData>>,
+    fade: Option<(Instant, Duration)>,
+    tempo: Tempo,
+    equalizer: Option<EqPreset>,
+    headphone_profile: Option<HeadphoneProfile>,
+    requested_device: Option<String>,
+    audio: AudioSettings,
+    headless: Option<HeadlessOutput>,
+    device_name: Option<String>,
+    device_watcher: Option<DeviceWatcher>,
+    loop_region: Option<LoopRegion>,
+    loop_position: f64,
 }
 
 impl Backend {
-    pub fn new() -> Result<Self, cpal::Error> {
-        let (analyzer_builder, visualization_data) = AudioAnalyzerBuilder::new();
+    pub fn new(device: Option<&str>, audio: AudioSettings) -> Result<Self, OutputError> {
+        let output = find_output_device(device);
+        let device_name = output.as_ref().and_then(|d| d.name().ok());
+        let (manager, params, audio) = build_manager(OutputTarget::Device(output), audio)?;
+        Ok(Self::with_manager(
+            manager,
+            params,
+            device,
+            audio,
+            None,
+            device_name,
+        ))
+    }
 
-        let settings = AudioManagerSettings {
-            main_track_builder: MainTrackBuilder::new().with_effect(analyzer_builder),
-            ..AudioManagerSettings::default()
-        };
+    pub fn headless(audio: AudioSettings) -> Result<(Self, HeadlessOutput), OutputError> {
+        let output = HeadlessOutput::new(audio.sample_rate);
+        let (manager, params, audio) =
+            build_manager(OutputTarget::Headless(output.clone()), audio)?;
+        let backend = Self::with_manager(manager, params, None, audio, Some(output.clone()), None);
+        Ok((backend, output))
+    }
 
-        Ok(Backend {
-            manager: AudioManager::<DefaultBackend>::new(settings)?,
+    fn with_manager(
+        manager: AudioManager<OutputBackend>,
+        params: EffectParams,
+        device: Option<&str>,
+        audio: AudioSettings,
+        headless: Option<HeadlessOutput>,
+        device_name: Option<String>,
+    ) -> Self {
+        let device_watcher = headless.is_none().then(|| DeviceWatcher::spawn(device));
+        Backend {
+            manager,
+            params,
             sound: None,
+            path: None,
+            slice: None,
             volume: 1.0,
-            visualization_data,
-        })
+            fade: None,
+            tempo: Tempo::default(),
+            equalizer: None,
+            headphone_profile: None,
+            requested_device: device.map(str::to_string),
+            audio,
+            headless,
+            device_name,
+            device_watcher,
+            loop_region: None,
+            loop_position: 0.0,
+        }
     }
 
     pub fn load_and_play(&mut self, path: &Path) -> Result<(), PlaybackError> {
+        self.play_from(path, None, 0.0)
+    }
+
+    pub fn load_and_play_range(
+        &mut self,
+        path: &Path,
+        start: f64,
+        end: Option<f64>,
+    ) -> Result<(), PlaybackError> {
+        let slice = Region {
+            start: PlaybackPosition::Seconds(start),
+            end: end.map_or(EndPosition::EndOfAudio, |end| {
+                EndPosition::Custom(PlaybackPosition::Seconds(end))
+            }),
+        };
+        self.play_from(path, Some(slice), 0.0)
+    }
+
+    fn play_from(
+        &mut self,
+        path: &Path,
+        slice: Option<Region>,
+        position: f64,
+    ) -> Result<(), PlaybackError> {
         self.stop();
 
+        if let Some(format) = AudioFormat::from_path(path).filter(|f| !f.is_decodable()) {
+            return Err(PlaybackError::UnsupportedFormat(format));
+        }
+
         let sound_data = StreamingSoundData::from_file(path)
             .map_err(PlaybackError::LoadError)?
-            .volume(self.volume_db());
+            .volume(self.faded_db())
+            .playback_rate(self.tempo.speed)
+            .slice(slice)
+            .start_position(position);
 
-        let handle = self
+        let mut handle = self
             .manager
             .play(sound_data)
             .map_err(PlaybackError::PlayError)?;
+        if let Some((started, duration)) = self.fade {
+            handle.set_volume(
+                SILENCE_DB,
+                fade_tween(duration.saturating_sub(started.elapsed())),
+            );
+        }
 
         self.sound = Some(handle);
+        self.path = Some(path.to_path_buf());
+        self.slice = slice;
+        self.loop_region = None;
+        self.loop_position = position;
         Ok(())
     }
 
     pub fn play(&mut self) {
+        self.cancel_fade();
         if let Some(sound) = &mut self.sound {
             let state = sound.state();
             if state == PlaybackState::Paused || state == PlaybackState::Pausing {
@@ -87,6 +211,8 @@ impl Backend {
         if let Some(sound) = &mut self.sound {
             sound.stop(Tween::default());
             self.sound = None;
+            self.path = None;
+            self.slice = None;
         }
     }
 
@@ -98,6 +224,169 @@ impl Backend {
 
     pub fn set_volume(&mut self, volume: f32) {
         self.volume = volume.clamp(0.0, 1.0);
+        self.fade = None;
+        let db = self.volume_db();
+        if let Some(sound) = &mut self.sound {
+            sound.set_volume(db, Tween::default());
+        }
+    }
+
+    pub fn set_tempo(&mut self, tempo: Tempo) {
+        self.tempo = tempo.clamped();
+        self.params.pitch_ratio.store(self.tempo.pitch_ratio());
+        if let Some(sound) = &mut self.sound {
+            sound.set_playback_rate(self.tempo.speed, Tween::default());
+        }
+    }
+
+    pub fn tempo(&self) -> Tempo {
+        self.tempo
+    }
+
+    pub fn set_equalizer(&mut self, preset: Option<EqPreset>) {
+        self.params.eq.store(EqParams::from_preset(preset.as_ref()));
+        self.equalizer = preset;
+    }
+
+    pub fn equalizer(&self) -> Option<&EqPreset> {
+        self.equalizer.as_ref()
+    }
+
+    pub fn set_headphone_profile(&mut self, profile: Option<HeadphoneProfile>) {
+        self.params.correction.store(
+            profile
+                .as_ref()
+                .map_or(EqParams::BYPASS, HeadphoneProfile::params),
+        );
+        self.headphone_profile = profile;
+    }
+
+    pub fn headphone_profile(&self) -> Option<&HeadphoneProfile> {
+        self.headphone_profile.as_ref()
+    }
+
+    pub fn output_device(&self) -> Option<&str> {
+        self.device_name.as_deref()
+    }
+
+    pub fn set_output_device(&mut self, device: Option<&str>) -> Result<(), PlaybackError> {
+        self.requested_device = device.map(str::to_string);
+        if let Some(watcher) = &self.device_watcher {
+            watcher.request(device);
+        }
+        self.switch_device(find_output_device(device), self.audio)
+    }
+
+    pub fn set_audio_settings(&mut self, audio: AudioSettings) -> Result<(), PlaybackError> {
+        self.switch_device(find_output_device(self.requested_device.as_deref()), audio)
+    }
+
+    pub fn audio_settings(&self) -> AudioSettings {
+        self.audio
+    }
+
+    pub(crate) fn check_device(&mut self) -> Result<bool, PlaybackError> {
+        let Some(watcher) = &self.device_watcher else {
+            return Ok(false);
+        };
+        let changed = watcher
+            .resolved(self.requested_device.as_deref())
+            .is_some_and(|name| name != self.device_name);
+
+        let lost = self.manager.backend_mut().pop_error().is_some();
+        let on_requested =
+            self.requested_device.is_some() && self.requested_device == self.device_name;
+        if !lost && (on_requested || !changed) {
+            return Ok(false);
+        }
+
+        let output = find_output_device(self.requested_device.as_deref());
+        self.switch_device(output, self.audio)?;
+        Ok(true)
+    }
+
+    fn switch_device(
+        &mut self,
+        output: Option<cpal::Device>,
+        audio: AudioSettings,
+    ) -> Result<(), PlaybackError> {
+        let (target, device_name) = match &self.headless {
+            Some(headless) => (OutputTarget::Headless(headless.clone()), None),
+            None => {
+                let name = output.as_ref().and_then(|d| d.name().ok());
+                (OutputTarget::Device(output), name)
+            }
+        };
+        let (manager, params, audio) =
+            build_manager(target, audio).map_err(PlaybackError::DeviceError)?;
+        params.inherit(&self.params);
+
+        let resume = match (&self.path, &self.sound) {
+            (Some(path), Some(sound)) if sound.state() != PlaybackState::Stopped => {
+                Some((path.clone(), self.slice, sound.position(), self.state()))
+            }
+            _ => None,
+        };
+        let loop_region = self.loop_region;
+
+        self.stop();
+        self.manager = manager;
+        self.params = params;
+        self.audio = audio;
+        self.device_name = device_name;
+
+        if let Some((path, slice, position, state)) = resume {
+            self.play_from(&path, slice, position)?;
+            self.set_loop_region(loop_region);
+            if state == BackendState::Paused
+                && let Some(sound) = &mut self.sound
+            {
+                sound.pause(Tween::default());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_loop_region(&mut self, region: Option<LoopRegion>) {
+        self.loop_region = region;
+        self.loop_position = self.position();
+        if let Some(sound) = &mut self.sound {
+            sound.set_loop_region(region.map(|r| Region::from(r.start..r.end)));
+        }
+    }
+
+    pub fn loop_region(&self) -> Option<LoopRegion> {
+        self.loop_region
+    }
+
+    pub(crate) fn update_loop(&mut self) {
+        let Some(region) = self.loop_region else {
+            return;
+        };
+
+        let position = self.position();
+        let wrapped = region.wrapped(self.loop_position, position);
+        self.loop_position = position;
+
+        if wrapped && region.speed_step > 0.0 {
+            self.set_tempo(Tempo {
+                speed: self.tempo.speed + region.speed_step,
+                ..self.tempo
+            });
+        }
+    }
+
+    pub fn fade_out(&mut self, duration: Duration) {
+        self.fade = Some((Instant::now(), duration));
+        if let Some(sound) = &mut self.sound {
+            sound.set_volume(SILENCE_DB, fade_tween(duration));
+        }
+    }
+
+    pub fn cancel_fade(&mut self) {
+        if self.fade.take().is_none() {
+            return;
+        }
         let db = self.volume_db();
         if let Some(sound) = &mut self.sound {
             sound.set_volume(db, Tween::default());
@@ -106,12 +395,24 @@ impl Backend {
 
     fn volume_db(&self) -> f32 {
         if self.volume <= 0.0 {
-            -60.0
+            SILENCE_DB
         } else {
             28.0 * self.volume.log10()
         }
     }
 
+    fn faded_db(&self) -> f32 {
+        let db = self.volume_db();
+        match self.fade {
+            Some((started, duration)) if !duration.is_zero() => {
+                let progress = (started.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.0);
+                db + (SILENCE_DB - db) * progress
+            }
+            Some(_) => SILENCE_DB,
+            None => db,
+        }
+    }
+
     pub(crate) fn state(&self) -> BackendState {
         match &self.sound {
             None => BackendState::Idle,
@@ -128,6 +429,10 @@ impl Backend {
         self.state() == BackendState::Playing
     }
 
+    pub fn is_finished(&self) -> bool {
+        self.state() == BackendState::Finished
+    }
+
     pub fn position(&self) -> f64 {
         if let Some(sound) = &self.sound {
             sound.position()
@@ -137,14 +442,91 @@ impl Backend {
     }
 
     pub fn vis_data(&self) -> VisData {
-        self.visualization_data.load()
+        self.params.visualization.load()
     }
 }
 
+fn fade_tween(duration: Duration) -> Tween {
+    Tween {
+        start_time: StartTime::Immediate,
+        duration,
+        easing: Easing::Linear,
+    }
+}
+
+fn build_manager(
+    target: OutputTarget,
+    audio: AudioSettings,
+) -> Result<(AudioManager<OutputBackend>, EffectParams, AudioSettings), OutputError> {
+    match start_manager(target.clone(), audio) {
+        Err(_) if audio.buffer_size.is_some() => {
+            let audio = AudioSettings {
+                buffer_size: None,
+                ..audio
+            };
+            start_manager(target, audio)
+        }
+        result => result,
+    }
+}
+
+fn start_manager(
+    target: OutputTarget,
+    audio: AudioSettings,
+) -> Result<(AudioManager<OutputBackend>, EffectParams, AudioSettings), OutputError> {
+    let (eq_builder, eq) = EqualizerBuilder::new();
+    let (correction_builder, correction) = EqualizerBuilder::new();
+    let (pitch_builder, pitch_ratio) = PitchShifterBuilder::new();
+    let (analyzer_builder, visualization) = AudioAnalyzerBuilder::new();
+
+    let settings = AudioManagerSettings {
+        main_track_builder: MainTrackBuilder::new()
+            .with_effect(eq_builder)
+            .with_effect(correction_builder)
+            .with_effect(pitch_builder)
+            .with_effect(analyzer_builder),
+        backend_settings: OutputSettings { target, audio },
+        ..AudioManagerSettings::default()
+    };
+
+    let manager = AudioManager::<OutputBackend>::new(settings)?;
+    Ok((
+        manager,
+        EffectParams {
+            eq,
+            correction,
+            pitch_ratio,
+            visualization,
+        },
+        audio,
+    ))
+}
+
+pub(crate) fn find_output_device(name: Option<&str>) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    name.and_then(|name| {
+        host.output_devices()
+            .ok()?
+            .find(|d| d.name().is_ok_and(|n| n == name))
+    })
+    .or_else(|| host.default_output_device())
+}
+
+pub fn output_devices() -> Vec<String> {
+    cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Error)]
 pub enum PlaybackError {
     #[error("Failed to load audio file: {0}")]
     LoadError(FromFileError),
     #[error("Failed to play audio: {0}")]
     PlayError(PlaySoundError<FromFileError>),
+    #[error("{0} files can be tagged but not played")]
+    UnsupportedFormat(AudioFormat),
+    #[error("Failed to open output device: {0}")]
+    DeviceError(OutputError),
 }
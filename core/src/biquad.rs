@@ -0,0 +1,155 @@
+use kira::Frame;
+use std::f32::consts::PI;
+
+use crate::equalizer::{EqBand, FilterKind};
+
+const SMOOTHING: f32 = 0.002;
+const SETTLED: f32 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Coefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Coefficients {
+    pub(crate) const IDENTITY: Coefficients = Coefficients {
+        b0: 1.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+
+    pub(crate) fn new(band: &EqBand, sample_rate: f32) -> Self {
+        let frequency = band.frequency.clamp(10.0, sample_rate * 0.45);
+        let q = band.q.max(0.05);
+        let a = 10f32.powf(band.gain_db / 40.0);
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let shelf = 2.0 * a.sqrt() * alpha;
+
+        let (b0, b1, b2, a0, a1, a2) = match band.kind {
+            FilterKind::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            FilterKind::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos + shelf),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - shelf),
+                (a + 1.0) + (a - 1.0) * cos + shelf,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - shelf,
+            ),
+            FilterKind::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos + shelf),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - shelf),
+                (a + 1.0) - (a - 1.0) * cos + shelf,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - shelf,
+            ),
+            FilterKind::LowPass => (
+                (1.0 - cos) / 2.0,
+                1.0 - cos,
+                (1.0 - cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            FilterKind::HighPass => (
+                (1.0 + cos) / 2.0,
+                -(1.0 + cos),
+                (1.0 + cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+        };
+
+        Coefficients {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+
+    fn approach(&mut self, target: &Coefficients) -> bool {
+        self.b0 += (target.b0 - self.b0) * SMOOTHING;
+        self.b1 += (target.b1 - self.b1) * SMOOTHING;
+        self.b2 += (target.b2 - self.b2) * SMOOTHING;
+        self.a1 += (target.a1 - self.a1) * SMOOTHING;
+        self.a2 += (target.a2 - self.a2) * SMOOTHING;
+
+        let settled = (target.b0 - self.b0).abs() < SETTLED
+            && (target.b1 - self.b1).abs() < SETTLED
+            && (target.b2 - self.b2).abs() < SETTLED
+            && (target.a1 - self.a1).abs() < SETTLED
+            && (target.a2 - self.a2).abs() < SETTLED;
+
+        if settled {
+            *self = *target;
+        }
+        settled
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Biquad {
+    current: Coefficients,
+    target: Coefficients,
+    settled: bool,
+    z1: Frame,
+    z2: Frame,
+}
+
+impl Biquad {
+    pub(crate) fn new() -> Self {
+        Biquad {
+            current: Coefficients::IDENTITY,
+            target: Coefficients::IDENTITY,
+            settled: true,
+            z1: Frame::ZERO,
+            z2: Frame::ZERO,
+        }
+    }
+
+    pub(crate) fn set_target(&mut self, target: Coefficients) {
+        if target != self.target {
+            self.target = target;
+            self.settled = false;
+        }
+    }
+
+    pub(crate) fn is_bypassed(&self) -> bool {
+        self.settled && self.current == Coefficients::IDENTITY
+    }
+
+    pub(crate) fn process(&mut self, input: Frame) -> Frame {
+        if !self.settled {
+            self.settled = self.current.approach(&self.target);
+            if self.is_bypassed() {
+                self.z1 = Frame::ZERO;
+                self.z2 = Frame::ZERO;
+                return input;
+            }
+        }
+
+        let c = &self.current;
+        let output = input * c.b0 + self.z1;
+        self.z1 = input * c.b1 - output * c.a1 + self.z2;
+        self.z2 = input * c.b2 - output * c.a2;
+        output
+    }
+}
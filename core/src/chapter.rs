@@ -0,0 +1,383 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use lofty::id3::v2::{Frame, Id3v2Tag, Id3v2Version};
+
+use crate::AudioFormat;
+
+const NERO_TICKS_PER_SECOND: f64 = 10_000_000.0;
+const MAX_CHAPTERS: usize = 4096;
+const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+const BOUNDARY_TOLERANCE: f32 = 0.05;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Chapter {
+    pub fn index_at(chapters: &[Chapter], position: f32) -> Option<usize> {
+        chapters
+            .iter()
+            .rposition(|c| c.start <= position + BOUNDARY_TOLERANCE)
+    }
+}
+
+pub(crate) fn read(path: &Path, id3: Option<&Id3v2Tag>, duration: f32) -> Vec<Chapter> {
+    let chapters = match AudioFormat::from_path(path) {
+        Some(AudioFormat::Mp4) => mp4_chapters(path),
+        _ => id3.map(id3_chapters),
+    };
+    complete(chapters.unwrap_or_default(), duration)
+}
+
+fn complete(mut chapters: Vec<Chapter>, duration: f32) -> Vec<Chapter> {
+    chapters.retain(|c| c.start.is_finite() && c.start >= 0.0);
+    if duration > 0.0 {
+        chapters.retain(|c| c.start < duration);
+    }
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    chapters.dedup_by(|later, earlier| later.start == earlier.start);
+
+    let nexts: Vec<f32> = chapters
+        .iter()
+        .skip(1)
+        .map(|c| c.start)
+        .chain([duration])
+        .collect();
+    for (i, (chapter, next)) in chapters.iter_mut().zip(nexts).enumerate() {
+        chapter.end = match (chapter.end > chapter.start, next > chapter.start) {
+            (true, true) => chapter.end.min(next),
+            (true, false) => chapter.end,
+            (false, _) => next.max(chapter.start),
+        };
+        chapter.title = chapter.title.trim().to_string();
+        if chapter.title.is_empty() {
+            chapter.title = format!("Chapter {}", i + 1);
+        }
+    }
+    chapters
+}
+
+fn id3_chapters(tag: &Id3v2Tag) -> Vec<Chapter> {
+    let version = tag.original_version();
+    tag.into_iter()
+        .filter_map(|frame| match frame {
+            Frame::Binary(frame) if frame.id().as_str() == "CHAP" => {
+                chap_frame(&frame.data, version)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn chap_frame(data: &[u8], version: Id3v2Version) -> Option<Chapter> {
+    let times = data.iter().position(|&b| b == 0)? + 1;
+    let start = be_u32(data.get(times..)?)?;
+    let end = be_u32(data.get(times + 4..)?)?;
+
+    let mut title = String::new();
+    let mut frames = data.get(times + 16..)?;
+    while let (Some(id), Some(size)) = (frames.get(..4), frames.get(4..).and_then(be_u32)) {
+        let size = match version {
+            Id3v2Version::V4 => synchsafe(size),
+            _ => size,
+        } as usize;
+        let Some(body) = frames.get(10..10 + size) else {
+            break;
+        };
+        if id == b"TIT2" {
+            title = id3_text(body);
+        }
+        frames = &frames[10 + size..];
+    }
+
+    Some(Chapter {
+        title,
+        start: start as f32 / 1000.0,
+        end: end as f32 / 1000.0,
+    })
+}
+
+fn id3_text(body: &[u8]) -> String {
+    let Some((&encoding, text)) = body.split_first() else {
+        return String::new();
+    };
+    let text = match encoding {
+        1 => match text.get(..2) {
+            Some([0xFF, 0xFE]) => utf16(&text[2..], false),
+            Some([0xFE, 0xFF]) => utf16(&text[2..], true),
+            _ => utf16(text, false),
+        },
+        2 => utf16(text, true),
+        3 => String::from_utf8_lossy(text).into_owned(),
+        _ => text.iter().map(|&b| b as char).collect(),
+    };
+    text.split('\0').next().unwrap_or_default().to_string()
+}
+
+fn mp4_chapters(path: &Path) -> Option<Vec<Chapter>> {
+    let mut file = File::open(path).ok()?;
+    let moov = read_moov(&mut file)?;
+    nero_chapters(&moov).or_else(|| quicktime_chapters(&moov, &mut file))
+}
+
+fn read_moov(file: &mut File) -> Option<Vec<u8>> {
+    let len = file.metadata().ok()?.len();
+    let mut offset = 0;
+    while offset + 8 <= len {
+        let mut header = [0; 16];
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut header[..8]).ok()?;
+        let (header_len, size) = match be_u32(&header)? {
+            0 => (8, len - offset),
+            1 => {
+                file.read_exact(&mut header[8..]).ok()?;
+                (16, be_u64(&header[8..])?)
+            }
+            size => (8, size as u64),
+        };
+        if size < header_len || size > len - offset {
+            return None;
+        }
+        if &header[4..8] == b"moov" {
+            if size - header_len > MAX_MOOV_SIZE {
+                return None;
+            }
+            let mut moov = vec![0; usize::try_from(size - header_len).ok()?];
+            file.read_exact(&mut moov).ok()?;
+            return Some(moov);
+        }
+        offset = offset.checked_add(size)?;
+    }
+    None
+}
+
+fn nero_chapters(moov: &[u8]) -> Option<Vec<Chapter>> {
+    let chpl = Atoms(moov)
+        .filter(|(kind, _)| kind == b"udta")
+        .find_map(|(_, udta)| child(udta, b"chpl"))?;
+    let header = if *chpl.first()? == 0 { 4 } else { 8 };
+    let count = *chpl.get(header)?;
+
+    let mut entries = chpl.get(header + 1..)?;
+    let mut chapters = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let start = be_u64(entries)?;
+        let len = *entries.get(8)? as usize;
+        let title = entries.get(9..9 + len)?;
+        chapters.push(Chapter {
+            title: String::from_utf8_lossy(title).into_owned(),
+            start: (start as f64 / NERO_TICKS_PER_SECOND) as f32,
+            end: 0.0,
+        });
+        entries = &entries[9 + len..];
+    }
+    Some(chapters)
+}
+
+fn quicktime_chapters(moov: &[u8], file: &mut File) -> Option<Vec<Chapter>> {
+    let traks: Vec<&[u8]> = Atoms(moov)
+        .filter(|(kind, _)| kind == b"trak")
+        .map(|(_, trak)| trak)
+        .collect();
+    let chapter_track = traks
+        .iter()
+        .find_map(|trak| be_u32(child(child(trak, b"tref")?, b"chap")?))?;
+    let trak = traks
+        .iter()
+        .find(|trak| child(trak, b"tkhd").and_then(after_timestamps) == Some(chapter_track))?;
+
+    let mdia = child(trak, b"mdia")?;
+    let timescale = after_timestamps(child(mdia, b"mdhd")?)? as f64;
+    if timescale == 0.0 {
+        return None;
+    }
+    let stbl = child(child(mdia, b"minf")?, b"stbl")?;
+    let durations = table(child(stbl, b"stts")?, 8).flat_map(|entry| {
+        std::iter::repeat_n(be_u32(&entry[4..]), be_u32(entry).unwrap_or(0) as usize)
+    });
+
+    let len = file.metadata().ok()?.len();
+    let mut chapters = Vec::new();
+    let mut time = 0u64;
+    for ((offset, size), duration) in samples(stbl, len)?.into_iter().zip(durations) {
+        let duration = duration? as u64;
+        chapters.push(Chapter {
+            title: text_sample(file, offset, size).unwrap_or_default(),
+            start: (time as f64 / timescale) as f32,
+            end: ((time + duration) as f64 / timescale) as f32,
+        });
+        time += duration;
+    }
+    Some(chapters)
+}
+
+fn after_timestamps(atom: &[u8]) -> Option<u32> {
+    match atom.first()? {
+        0 => be_u32(atom.get(12..)?),
+        _ => be_u32(atom.get(20..)?),
+    }
+}
+
+fn samples(stbl: &[u8], len: u64) -> Option<Vec<(u64, u32)>> {
+    let stsz = child(stbl, b"stsz")?;
+    let fixed = be_u32(stsz.get(4..)?)?;
+    let count = (be_u32(stsz.get(8..)?)? as usize).min(MAX_CHAPTERS);
+    let sizes: Vec<u32> = match fixed {
+        0 => table(stsz.get(4..)?, 4)
+            .take(count)
+            .filter_map(be_u32)
+            .collect(),
+        size => vec![size; count],
+    };
+
+    let chunks: Vec<u64> = match child(stbl, b"stco") {
+        Some(stco) => table(stco, 4).filter_map(be_u32).map(u64::from).collect(),
+        None => table(child(stbl, b"co64")?, 8).filter_map(be_u64).collect(),
+    };
+    let runs: Vec<(usize, usize)> = table(child(stbl, b"stsc")?, 12)
+        .filter_map(|entry| Some((be_u32(entry)? as usize, be_u32(&entry[4..])? as usize)))
+        .collect();
+
+    let mut sizes = sizes.into_iter();
+    let mut located = Vec::new();
+    for (index, &chunk) in chunks.iter().enumerate() {
+        let per_chunk = runs
+            .iter()
+            .rev()
+            .find(|(first, _)| *first <= index + 1)
+            .map_or(0, |(_, samples)| *samples);
+        let mut offset = chunk;
+        for size in sizes.by_ref().take(per_chunk) {
+            let Some(end) = offset.checked_add(size as u64).filter(|&end| end <= len) else {
+                return Some(located);
+            };
+            located.push((offset, size));
+            offset = end;
+        }
+    }
+    Some(located)
+}
+
+fn text_sample(file: &mut File, offset: u64, size: u32) -> Option<String> {
+    let mut sample = vec![0; (size as usize).min(u16::MAX as usize + 2)];
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(&mut sample).ok()?;
+
+    let len = u16::from_be_bytes([*sample.first()?, *sample.get(1)?]) as usize;
+    let text = sample.get(2..2 + len)?;
+    Some(match text.strip_prefix(&[0xFE, 0xFF]) {
+        Some(text) => utf16(text, true),
+        None => String::from_utf8_lossy(text).into_owned(),
+    })
+}
+
+struct Atoms<'a>(&'a [u8]);
+
+impl<'a> Iterator for Atoms<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kind = self.0.get(4..8)?;
+        let (header, size) = match be_u32(self.0)? {
+            0 => (8, self.0.len()),
+            1 => (16, usize::try_from(be_u64(self.0.get(8..)?)?).ok()?),
+            size => (8, size as usize),
+        };
+        let body = self.0.get(header..size)?;
+        self.0 = &self.0[size..];
+        Some((kind, body))
+    }
+}
+
+fn child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    Atoms(data).find(|(k, _)| k == kind).map(|(_, body)| body)
+}
+
+fn table(atom: &[u8], width: usize) -> impl Iterator<Item = &[u8]> {
+    let count = atom.get(4..).and_then(be_u32).unwrap_or(0) as usize;
+    atom.get(8..)
+        .unwrap_or_default()
+        .chunks_exact(width)
+        .take(count.min(MAX_CHAPTERS))
+}
+
+fn utf16(bytes: &[u8], big_endian: bool) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| {
+            if big_endian {
+                u16::from_be_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_le_bytes([pair[0], pair[1]])
+            }
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn synchsafe(value: u32) -> u32 {
+    (value & 0x7F)
+        | ((value & 0x7F00) >> 1)
+        | ((value & 0x7F_0000) >> 2)
+        | ((value & 0x7F00_0000) >> 3)
+}
+
+fn be_u32(bytes: &[u8]) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(..4)?.try_into().ok()?))
+}
+
+fn be_u64(bytes: &[u8]) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.get(..8)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn moov_from(bytes: &[u8]) -> Option<Vec<u8>> {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(bytes).unwrap();
+        read_moov(&mut file)
+    }
+
+    #[test]
+    fn reads_moov_after_other_atoms() {
+        let mut bytes = [&16u32.to_be_bytes()[..], b"ftypM4A ", &[0; 4]].concat();
+        bytes.extend_from_slice(&12u32.to_be_bytes());
+        bytes.extend_from_slice(b"moov");
+        bytes.extend_from_slice(b"data");
+        assert_eq!(moov_from(&bytes).as_deref(), Some(&b"data"[..]));
+    }
+
+    #[test]
+    fn rejects_moov_larger_than_file() {
+        let bytes = [&u32::MAX.to_be_bytes()[..], b"moov", &[0; 8]].concat();
+        assert!(moov_from(&bytes).is_none());
+    }
+
+    #[test]
+    fn rejects_oversized_64_bit_atoms() {
+        let bytes = [&1u32.to_be_bytes()[..], b"free", &u64::MAX.to_be_bytes()].concat();
+        assert!(moov_from(&bytes).is_none());
+
+        let bytes = [
+            &1u32.to_be_bytes()[..],
+            b"moov",
+            &(1u64 << 40).to_be_bytes(),
+        ]
+        .concat();
+        assert!(moov_from(&bytes).is_none());
+    }
+
+    #[test]
+    fn stops_at_truncated_header() {
+        let bytes = [&16u32.to_be_bytes()[..], b"free", &[0; 8], &[0, 0, 0]].concat();
+        assert!(moov_from(&bytes).is_none());
+    }
+}
@@ -1,39 +1,154 @@
 use serde::{Deserialize, Serialize};
 use std::{
-    fs,
-    path::PathBuf,
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
     sync::{OnceLock, RwLock},
 };
 use thiserror::Error;
 
+use crate::{
+    equalizer::EqPreset, headphone::HeadphoneProfile, output::AudioSettings, tags::TagSeparators,
+};
+
+pub const HOME_VAR: &str = "VERSE_HOME";
+const PORTABLE_DIR: &str = "verse-data";
+const PROFILES_DIR: &str = "profiles";
+pub const VARIOUS_ARTISTS: &str = "Various Artists";
+
 static CONFIG: OnceLock<RwLock<Config>> = OnceLock::new();
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct Config {
-    pub(crate) root: Option<PathBuf>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
 }
 
-fn project_dirs() -> Result<directories::ProjectDirs, ConfigError> {
-    directories::ProjectDirs::from("", "", "verse").ok_or(ConfigError::DirectoryNotFound)
+impl Paths {
+    pub fn new(config_dir: impl Into<PathBuf>, data_dir: impl Into<PathBuf>) -> Self {
+        Paths {
+            config_dir: config_dir.into(),
+            data_dir: data_dir.into(),
+        }
+    }
+
+    pub fn home(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self::new(dir.clone(), dir)
+    }
+
+    pub fn resolve() -> Result<Self, ConfigError> {
+        if let Some(home) = env::var_os(HOME_VAR).filter(|v| !v.is_empty()) {
+            return Ok(Self::home(home));
+        }
+
+        let proj_dirs = directories::ProjectDirs::from("", "", "verse")
+            .ok_or(ConfigError::DirectoryNotFound)?;
+        Ok(Self::new(proj_dirs.config_dir(), proj_dirs.data_dir()))
+    }
+
+    pub fn portable() -> Result<Self, ConfigError> {
+        let exe = env::current_exe()?;
+        let dir = exe.parent().ok_or(ConfigError::DirectoryNotFound)?;
+        Ok(Self::home(dir.join(PORTABLE_DIR)))
+    }
+
+    pub fn with_profile(self, name: &str) -> Self {
+        Self::new(
+            self.config_dir.join(PROFILES_DIR).join(name),
+            self.data_dir.join(PROFILES_DIR).join(name),
+        )
+    }
+
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir.join("config.toml")
+    }
+
+    pub fn database(&self) -> PathBuf {
+        self.data_dir.join("library.db")
+    }
 }
 
-impl Config {
-    pub(crate) fn path() -> Result<PathBuf, ConfigError> {
-        let proj_dirs = project_dirs()?;
-        Ok(proj_dirs.config_dir().join("config.toml"))
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    pub profile: Option<String>,
+    pub portable: bool,
+}
+
+impl LaunchOptions {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, ConfigError> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let profile = match arg.split_once('=') {
+                Some(("--profile", name)) => name.to_string(),
+                _ if arg == "--profile" => args
+                    .next()
+                    .ok_or_else(|| ConfigError::InvalidArgument(arg.clone()))?,
+                _ if arg == "--portable" => {
+                    options.portable = true;
+                    continue;
+                }
+                _ => return Err(ConfigError::InvalidArgument(arg)),
+            };
+
+            if profile.is_empty()
+                || profile == "."
+                || profile == ".."
+                || profile.contains(['/', '\\'])
+            {
+                return Err(ConfigError::InvalidProfile(profile));
+            }
+            options.profile = Some(profile);
+        }
+
+        Ok(options)
     }
 
-    pub(crate) fn database_path() -> Result<PathBuf, ConfigError> {
-        let proj_dirs = project_dirs()?;
-        Ok(proj_dirs.data_dir().join("library.db"))
+    pub fn paths(&self) -> Result<Paths, ConfigError> {
+        let paths = if self.portable {
+            Paths::portable()?
+        } else {
+            Paths::resolve()?
+        };
+
+        Ok(match &self.profile {
+            Some(name) => paths.with_profile(name),
+            None => paths,
+        })
     }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(skip)]
+    path: PathBuf,
+    pub(crate) root: Option<PathBuf>,
+    #[serde(default)]
+    pub(crate) eq_presets: Vec<EqPreset>,
+    #[serde(default)]
+    pub(crate) device_eq_presets: HashMap<String, String>,
+    #[serde(default)]
+    pub(crate) headphone_profiles: Vec<HeadphoneProfile>,
+    #[serde(default)]
+    pub(crate) headphone_profile: Option<String>,
+    #[serde(default)]
+    pub(crate) output_device: Option<String>,
+    #[serde(default)]
+    pub(crate) audio: AudioSettings,
+    #[serde(default)]
+    pub(crate) tag_separators: TagSeparators,
+    #[serde(default)]
+    pub(crate) various_artists: Option<String>,
+}
 
+impl Config {
     pub fn init() -> Result<(), ConfigError> {
-        let config = Self::load_from_disk()?;
+        let config = Self::load(&Paths::resolve()?.config_file())?;
         CONFIG
             .set(RwLock::new(config))
-            .map_err(|_| ConfigError::AlreadyInitialized)?;
-        Ok(())
+            .map_err(|_| ConfigError::AlreadyInitialized)
     }
 
     pub fn get() -> &'static RwLock<Config> {
@@ -42,50 +157,155 @@ impl Config {
             .expect("Config not initialized. Call Config::init() first.")
     }
 
-    pub fn root() -> Option<PathBuf> {
-        Self::get().read().unwrap().root.clone()
+    pub fn save_current() -> Result<(), ConfigError> {
+        Self::get().read().unwrap().save()
     }
 
-    pub fn set_root(path: PathBuf) -> Result<(), ConfigError> {
-        let mut config = Self::get().write().unwrap();
-        config.root = Some(path);
-        config.save()?;
-        Ok(())
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let mut config = if path.exists() {
+            let contents = fs::read_to_string(path)?;
+            toml::from_str(&contents)?
+        } else {
+            Self::default()
+        };
+        config.path = path.to_path_buf();
+        Ok(config)
     }
 
-    pub fn clear_root() -> Result<(), ConfigError> {
-        let mut config = Self::get().write().unwrap();
-        config.root = None;
-        config.save()?;
-        Ok(())
+    pub fn path(&self) -> &Path {
+        &self.path
     }
 
-    pub fn save_current() -> Result<(), ConfigError> {
-        let config = Self::get().read().unwrap();
-        config.save()
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    pub fn set_root(&mut self, path: PathBuf) -> Result<(), ConfigError> {
+        self.root = Some(path);
+        self.save()
+    }
+
+    pub fn clear_root(&mut self) -> Result<(), ConfigError> {
+        self.root = None;
+        self.save()
     }
 
-    fn load_from_disk() -> Result<Self, ConfigError> {
-        let config_path = Self::path()?;
+    pub fn eq_presets(&self) -> &[EqPreset] {
+        &self.eq_presets
+    }
 
-        if !config_path.exists() {
-            return Ok(Self::default());
+    pub fn save_eq_preset(&mut self, preset: EqPreset) -> Result<(), ConfigError> {
+        match self.eq_presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.eq_presets.push(preset),
         }
+        self.save()
+    }
 
-        let contents = fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&contents)?;
-        Ok(config)
+    pub fn delete_eq_preset(&mut self, name: &str) -> Result<(), ConfigError> {
+        self.eq_presets.retain(|p| p.name != name);
+        self.device_eq_presets.retain(|_, preset| preset != name);
+        self.save()
+    }
+
+    pub fn device_eq_preset(&self, device: &str) -> Option<&str> {
+        self.device_eq_presets.get(device).map(String::as_str)
+    }
+
+    pub fn set_device_eq_preset(
+        &mut self,
+        device: &str,
+        preset: Option<&str>,
+    ) -> Result<(), ConfigError> {
+        match preset {
+            Some(name) => {
+                self.device_eq_presets
+                    .insert(device.to_string(), name.to_string());
+            }
+            None => {
+                self.device_eq_presets.remove(device);
+            }
+        }
+        self.save()
+    }
+
+    pub fn headphone_profiles(&self) -> &[HeadphoneProfile] {
+        &self.headphone_profiles
+    }
+
+    pub fn headphone_profile(&self) -> Option<&HeadphoneProfile> {
+        let name = self.headphone_profile.as_deref()?;
+        self.headphone_profiles.iter().find(|p| p.name == name)
     }
 
-    pub(crate) fn save(&self) -> Result<(), ConfigError> {
-        let config_path = Self::path()?;
+    pub fn save_headphone_profile(&mut self, profile: HeadphoneProfile) -> Result<(), ConfigError> {
+        match self
+            .headphone_profiles
+            .iter_mut()
+            .find(|p| p.name == profile.name)
+        {
+            Some(existing) => *existing = profile,
+            None => self.headphone_profiles.push(profile),
+        }
+        self.save()
+    }
+
+    pub fn remove_headphone_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        self.headphone_profiles.retain(|p| p.name != name);
+        if self.headphone_profile.as_deref() == Some(name) {
+            self.headphone_profile = None;
+        }
+        self.save()
+    }
+
+    pub fn set_headphone_profile(&mut self, name: Option<&str>) -> Result<(), ConfigError> {
+        self.headphone_profile = name.map(str::to_string);
+        self.save()
+    }
+
+    pub fn output_device(&self) -> Option<&str> {
+        self.output_device.as_deref()
+    }
 
-        if let Some(parent) = config_path.parent() {
+    pub fn set_output_device(&mut self, name: Option<&str>) -> Result<(), ConfigError> {
+        self.output_device = name.map(str::to_string);
+        self.save()
+    }
+
+    pub fn audio_settings(&self) -> AudioSettings {
+        self.audio
+    }
+
+    pub fn set_audio_settings(&mut self, settings: AudioSettings) -> Result<(), ConfigError> {
+        self.audio = settings;
+        self.save()
+    }
+
+    pub fn tag_separators(&self) -> &TagSeparators {
+        &self.tag_separators
+    }
+
+    pub fn set_tag_separators(&mut self, separators: TagSeparators) -> Result<(), ConfigError> {
+        self.tag_separators = separators;
+        self.save()
+    }
+
+    pub fn various_artists(&self) -> &str {
+        self.various_artists.as_deref().unwrap_or(VARIOUS_ARTISTS)
+    }
+
+    pub fn set_various_artists(&mut self, label: Option<&str>) -> Result<(), ConfigError> {
+        self.various_artists = label.map(str::to_string);
+        self.save()
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        if let Some(parent) = self.path.parent() {
             fs::create_dir_all(parent)?;
         }
 
         let toml_string = toml::to_string_pretty(self)?;
-        fs::write(&config_path, toml_string)?;
+        fs::write(&self.path, toml_string)?;
         Ok(())
     }
 }
@@ -102,4 +322,65 @@ pub enum ConfigError {
     DirectoryNotFound,
     #[error("Config already initialized")]
     AlreadyInitialized,
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Invalid profile name: {0}")]
+    InvalidProfile(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, ConfigError> {
+        LaunchOptions::from_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parses_profile_and_portable() {
+        assert_eq!(parse(&[]).unwrap(), LaunchOptions::default());
+        assert_eq!(
+            parse(&["--profile", "work", "--portable"]).unwrap(),
+            LaunchOptions {
+                profile: Some("work".to_string()),
+                portable: true,
+            }
+        );
+        assert_eq!(
+            parse(&["--profile=home"]).unwrap().profile.as_deref(),
+            Some("home")
+        );
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(matches!(
+            parse(&["--profile"]),
+            Err(ConfigError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            parse(&["--verbose"]),
+            Err(ConfigError::InvalidArgument(_))
+        ));
+        for name in ["", "..", "a/b", "a\\b"] {
+            assert!(matches!(
+                parse(&["--profile", name]),
+                Err(ConfigError::InvalidProfile(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn profiles_are_nested_under_base_dirs() {
+        let paths = Paths::new("/config", "/data").with_profile("work");
+
+        assert_eq!(
+            paths.config_file(),
+            Path::new("/config/profiles/work/config.toml")
+        );
+        assert_eq!(
+            paths.database(),
+            Path::new("/data/profiles/work/library.db")
+        );
+    }
 }
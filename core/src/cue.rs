@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::Path;
+
+const FRAMES_PER_SECOND: f32 = 75.0;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CueSheet {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub genre: Option<String>,
+    pub year: Option<u32>,
+    pub tracks: Vec<CueTrack>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CueTrack {
+    pub file: Option<String>,
+    pub number: u32,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub start: f32,
+}
+
+impl CueSheet {
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut sheet = CueSheet::default();
+        let mut file: Option<String> = None;
+        let mut current: Option<CueTrack> = None;
+        let mut start: Option<f32> = None;
+        let mut pregap: Option<f32> = None;
+
+        for line in text.trim_start_matches('\u{feff}').lines() {
+            let line = line.trim();
+            let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+
+            match command.to_ascii_uppercase().as_str() {
+                "FILE" => file = Some(file_name(rest)),
+                "TRACK" => {
+                    sheet.finish(current.take(), start.take().or(pregap.take()));
+                    let mut parts = rest.split_whitespace();
+                    let number = parts.next().and_then(|n| n.parse().ok());
+                    let audio = parts
+                        .next()
+                        .is_some_and(|kind| kind.eq_ignore_ascii_case("AUDIO"));
+                    current = number.filter(|_| audio).map(|number| CueTrack {
+                        file: file.clone(),
+                        number,
+                        ..CueTrack::default()
+                    });
+                }
+                "TITLE" => match &mut current {
+                    Some(track) => track.title = Some(unquote(rest)),
+                    None => sheet.title = Some(unquote(rest)),
+                },
+                "PERFORMER" => match &mut current {
+                    Some(track) => track.performer = Some(unquote(rest)),
+                    None => sheet.performer = Some(unquote(rest)),
+                },
+                "INDEX" => {
+                    let mut parts = rest.split_whitespace();
+                    let index = parts.next().and_then(|n| n.parse::<u32>().ok());
+                    let time = parts.next().and_then(parse_time);
+                    if current.is_some() {
+                        match index {
+                            Some(0) => pregap = time,
+                            Some(1) => start = time,
+                            _ => {}
+                        }
+                    }
+                }
+                "REM" if current.is_none() => {
+                    let (key, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    match key.to_ascii_uppercase().as_str() {
+                        "GENRE" => sheet.genre = Some(unquote(value.trim())),
+                        "DATE" => sheet.year = unquote(value).get(..4).and_then(|y| y.parse().ok()),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        sheet.finish(current, start.or(pregap));
+
+        (!sheet.tracks.is_empty()).then_some(sheet)
+    }
+
+    pub fn sidecar(audio: &Path) -> Option<Self> {
+        let mut with_suffix = audio.as_os_str().to_owned();
+        with_suffix.push(".cue");
+        [audio.with_extension("cue"), with_suffix.into()]
+            .iter()
+            .find_map(|path| fs::read(path).ok())
+            .and_then(|bytes| Self::parse(&decode(bytes)))
+    }
+
+    pub fn tracks_for(&self, audio: &Path) -> Vec<&CueTrack> {
+        let first = self.tracks[0].file.as_deref();
+        if self
+            .tracks
+            .iter()
+            .all(|track| track.file.as_deref() == first)
+        {
+            return self.tracks.iter().collect();
+        }
+
+        let name = audio
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let stem = audio
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        self.tracks
+            .iter()
+            .filter(|track| {
+                track.file.as_deref().is_some_and(|file| {
+                    let file = Path::new(file);
+                    file.file_name()
+                        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+                        || file
+                            .file_stem()
+                            .is_some_and(|s| s.eq_ignore_ascii_case(stem))
+                })
+            })
+            .collect()
+    }
+
+    fn finish(&mut self, track: Option<CueTrack>, start: Option<f32>) {
+        if let (Some(track), Some(start)) = (track, start) {
+            self.tracks.push(CueTrack { start, ..track });
+        }
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+fn file_name(value: &str) -> String {
+    match value.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or_default().to_string(),
+        None => value
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+fn parse_time(value: &str) -> Option<f32> {
+    let mut parts = value.split(':').map(|p| p.parse::<u32>().ok());
+    let (minutes, seconds, frames) = (parts.next()??, parts.next()??, parts.next()??);
+    Some((minutes * 60 + seconds) as f32 + frames as f32 / FRAMES_PER_SECOND)
+}
+
+fn decode(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|err| err.into_bytes().iter().map(|&b| b as char).collect())
+}
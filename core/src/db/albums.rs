@@ -1,10 +1,25 @@
-use crate::{Album, Track};
-use rusqlite::{Result as SqliteResult, params};
+use crate::{Album, Track, album::album_key};
+use rusqlite::{Result as SqliteResult, Row, params};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::Database;
 
+fn row_to_album(row: &Row) -> SqliteResult<Album> {
+    Ok(Album {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        artist: row.get("artist")?,
+        year: row.get("year")?,
+        release_id: row.get("release_id")?,
+        compilation: row.get::<_, i64>("compilation")? != 0,
+        track_count: row.get("track_count")?,
+        disc_count: row.get("disc_count")?,
+        duration: row.get::<_, f64>("duration")? as f32,
+        sample_track_path: row.get("sample_track_path")?,
+    })
+}
+
 fn now() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -18,11 +33,10 @@ impl Database {
             return Ok(());
         }
 
-        let mut albums: HashMap<(String, Option<String>), Option<u32>> = HashMap::new();
+        let mut albums: HashMap<String, &Track> = HashMap::new();
         for track in tracks {
-            if let Some(album_name) = track.album() {
-                let key = (album_name.into(), track.album_artist().map(|s| s.into()));
-                albums.entry(key).or_insert_with(|| track.year());
+            if let Some(key) = album_key(track) {
+                albums.entry(key).or_insert(track);
             }
         }
 
@@ -30,14 +44,39 @@ impl Database {
 
         self.conn.execute_batch("BEGIN")?;
         let result: SqliteResult<()> = (|| {
-            for ((name, artist), year) in &albums {
+            let mut ids: HashMap<&str, i64> = HashMap::new();
+            for (key, track) in &albums {
+                let id = self.conn.query_row(
+                    "INSERT INTO albums (album_key, name, artist, year, release_id,
+                                         compilation, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+                     ON CONFLICT(album_key) DO UPDATE SET
+                        name        = excluded.name,
+                        artist      = excluded.artist,
+                        year        = excluded.year,
+                        release_id  = excluded.release_id,
+                        compilation = excluded.compilation,
+                        updated_at  = excluded.updated_at
+                     RETURNING id",
+                    params![
+                        key,
+                        track.album(),
+                        track.album_artist().filter(|_| !track.compilation()),
+                        track.year(),
+                        track.release_id(),
+                        track.compilation(),
+                        ts,
+                    ],
+                    |row| row.get(0),
+                )?;
+                ids.insert(key.as_str(), id);
+            }
+
+            for track in tracks {
+                let album_id = album_key(track).and_then(|key| ids.get(key.as_str()).copied());
                 self.conn.execute(
-                    "INSERT INTO albums (name, artist, year, created_at, updated_at)
-                     VALUES (?1, ?2, ?3, ?4, ?4)
-                     ON CONFLICT(name, artist) DO UPDATE SET
-                         year       = excluded.year,
-                         updated_at = excluded.updated_at",
-                    params![name, artist, year, ts],
+                    "UPDATE tracks SET album_id = ?1 WHERE path = ?2 AND cue_index = ?3",
+                    params![album_id, track.path().to_str(), track.cue_index()],
                 )?;
             }
             Ok(())
@@ -51,24 +90,94 @@ impl Database {
         result
     }
 
+    pub fn delete_orphan_albums(&self) -> SqliteResult<()> {
+        self.conn.execute_batch(
+            "DELETE FROM albums
+             WHERE NOT EXISTS (
+                SELECT 1 FROM tracks t WHERE t.album_id = albums.id AND t.missing = 0
+             );
+             UPDATE tracks SET album_id = NULL
+             WHERE album_id IS NOT NULL AND album_id NOT IN (SELECT id FROM albums);",
+        )
+    }
+
     pub fn get_all_albums(&self) -> SqliteResult<Vec<Album>> {
         let mut stmt = self.conn.prepare(
-            "SELECT a.id, a.name, a.artist, a.year, MIN(t.path) AS sample_track_path
+            "SELECT a.id, a.name, a.artist, a.year, a.release_id, a.compilation,
+                    COUNT(t.id) AS track_count,
+                    COUNT(DISTINCT COALESCE(t.disc_number, 1)) AS disc_count,
+                    TOTAL(t.duration) AS duration,
+                    MIN(t.path) AS sample_track_path
+             FROM albums a
+             JOIN tracks t ON t.album_id = a.id AND t.missing = 0
+             GROUP BY a.id
+             ORDER BY a.compilation, LOWER(COALESCE(a.artist, '')), a.year NULLS LAST,
+                      LOWER(a.name)",
+        )?;
+
+        stmt.query_map([], row_to_album)?
+            .collect::<SqliteResult<Vec<_>>>()
+    }
+
+    pub fn get_albums_by_genre(&self, genre_id: i64) -> SqliteResult<Vec<Album>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id, a.name, a.artist, a.year, a.release_id, a.compilation,
+                    COUNT(t.id) AS track_count,
+                    COUNT(DISTINCT COALESCE(t.disc_number, 1)) AS disc_count,
+                    TOTAL(t.duration) AS duration,
+                    MIN(t.path) AS sample_track_path
              FROM albums a
-             LEFT JOIN tracks t ON t.album = a.name
-             GROUP BY a.id, a.name, a.artist, a.year
+             JOIN tracks t ON t.album_id = a.id AND t.missing = 0
+             WHERE a.id IN (
+                SELECT gt.album_id FROM tracks gt
+                JOIN track_genres tg ON tg.track_id = gt.id
+                WHERE tg.genre_id = ?1 AND gt.missing = 0
+             )
+             GROUP BY a.id
              ORDER BY LOWER(COALESCE(a.artist, '')), a.year NULLS LAST, LOWER(a.name)",
         )?;
+        stmt.query_map(params![genre_id], row_to_album)?
+            .collect::<SqliteResult<Vec<_>>>()
+    }
 
-        stmt.query_map([], |row| {
-            Ok(Album {
-                id: row.get("id")?,
-                name: row.get("name")?,
-                artist: row.get("artist")?,
-                year: row.get("year")?,
-                sample_track_path: row.get("sample_track_path")?,
-            })
-        })?
-        .collect::<SqliteResult<Vec<_>>>()
+    pub fn get_albums_by_decade(&self, start: u32) -> SqliteResult<Vec<Album>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id, a.name, a.artist, a.year, a.release_id, a.compilation,
+                    COUNT(t.id) AS track_count,
+                    COUNT(DISTINCT COALESCE(t.disc_number, 1)) AS disc_count,
+                    TOTAL(t.duration) AS duration,
+                    MIN(t.path) AS sample_track_path
+             FROM albums a
+             JOIN tracks t ON t.album_id = a.id AND t.missing = 0
+             WHERE a.id IN (
+                SELECT album_id FROM tracks
+                WHERE year >= ?1 AND year < ?1 + 10 AND missing = 0
+             )
+             GROUP BY a.id
+             ORDER BY a.year NULLS LAST, LOWER(COALESCE(a.artist, '')), LOWER(a.name)",
+        )?;
+        stmt.query_map(params![start], row_to_album)?
+            .collect::<SqliteResult<Vec<_>>>()
+    }
+
+    pub fn get_albums_by_artist(&self, artist_id: i64) -> SqliteResult<Vec<Album>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id, a.name, a.artist, a.year, a.release_id, a.compilation,
+                    COUNT(t.id) AS track_count,
+                    COUNT(DISTINCT COALESCE(t.disc_number, 1)) AS disc_count,
+                    TOTAL(t.duration) AS duration,
+                    MIN(t.path) AS sample_track_path
+             FROM albums a
+             JOIN tracks t ON t.album_id = a.id AND t.missing = 0
+             WHERE a.id IN (
+                SELECT at.album_id FROM tracks at
+                JOIN track_artists ta ON ta.track_id = at.id
+                WHERE ta.artist_id = ?1 AND at.missing = 0
+             )
+             GROUP BY a.id
+             ORDER BY a.year NULLS LAST, LOWER(a.name)",
+        )?;
+        stmt.query_map(params![artist_id], row_to_album)?
+            .collect::<SqliteResult<Vec<_>>>()
     }
 }
@@ -0,0 +1,210 @@
+use crate::{Artist, ArtistCredit, ArtistRole, Track};
+use rusqlite::{Result as SqliteResult, Row, params};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Database;
+
+const TOP_ARTISTS: usize = 3;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+fn row_to_artist(row: &Row) -> SqliteResult<Artist> {
+    Ok(Artist {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        sort_name: row.get("sort_name")?,
+        album_count: row.get("album_count")?,
+        track_count: row.get("track_count")?,
+        sample_track_path: row.get("sample_track_path")?,
+    })
+}
+
+impl Database {
+    pub fn batch_upsert_artists_from_tracks(&self, tracks: &[Track]) -> SqliteResult<()> {
+        if tracks.is_empty() {
+            return Ok(());
+        }
+
+        let credits = || tracks.iter().flat_map(Track::credits);
+        let album_artists = credits().filter(|c| c.role == ArtistRole::AlbumArtist);
+        let others = credits().filter(|c| c.role != ArtistRole::AlbumArtist);
+
+        let mut artists: HashMap<String, (&str, Option<&str>)> = HashMap::new();
+        for credit in album_artists.chain(others) {
+            let entry = artists
+                .entry(credit.name.to_lowercase())
+                .or_insert((credit.name.as_str(), None));
+            if entry.1.is_none() {
+                entry.1 = credit.sort_name.as_deref();
+            }
+        }
+
+        let ts = now();
+
+        self.conn.execute_batch("BEGIN")?;
+        let result: SqliteResult<()> = (|| {
+            let mut ids: HashMap<&str, i64> = HashMap::new();
+            for (key, (name, sort_name)) in &artists {
+                let id = self.conn.query_row(
+                    "INSERT INTO artists (name, sort_name, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?3)
+                     ON CONFLICT(name) DO UPDATE SET
+                        sort_name  = COALESCE(excluded.sort_name, artists.sort_name),
+                        updated_at = excluded.updated_at
+                     RETURNING id",
+                    params![name, sort_name, ts],
+                    |row| row.get(0),
+                )?;
+                ids.insert(key.as_str(), id);
+            }
+
+            for track in tracks {
+                let path = track.path().to_str();
+                self.conn.execute(
+                    "DELETE FROM track_artists
+                     WHERE track_id = (SELECT id FROM tracks WHERE path = ?1 AND cue_index = ?2)",
+                    params![path, track.cue_index()],
+                )?;
+                for (position, credit) in track.credits().iter().enumerate() {
+                    let Some(artist_id) = ids.get(credit.name.to_lowercase().as_str()) else {
+                        continue;
+                    };
+                    self.conn.execute(
+                        "INSERT OR IGNORE INTO track_artists (track_id, artist_id, role, position)
+                         SELECT id, ?2, ?3, ?4 FROM tracks WHERE path = ?1 AND cue_index = ?5",
+                        params![
+                            path,
+                            artist_id,
+                            credit.role.as_str(),
+                            position as i64,
+                            track.cue_index()
+                        ],
+                    )?;
+                }
+            }
+            Ok(())
+        })();
+
+        if result.is_ok() {
+            self.conn.execute_batch("COMMIT")?;
+        } else {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+        result
+    }
+
+    pub fn delete_orphan_artists(&self) -> SqliteResult<()> {
+        self.conn.execute_batch(
+            "DELETE FROM track_artists
+             WHERE track_id NOT IN (SELECT id FROM tracks);
+             DELETE FROM artists
+             WHERE NOT EXISTS (
+                SELECT 1 FROM track_artists ta
+                JOIN tracks t ON t.id = ta.track_id
+                WHERE ta.artist_id = artists.id AND t.missing = 0
+             );
+             DELETE FROM track_artists
+             WHERE artist_id NOT IN (SELECT id FROM artists);",
+        )
+    }
+
+    pub fn get_credits_for_track(&self, track_id: i64) -> SqliteResult<Vec<ArtistCredit>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT ar.name, ar.sort_name, ta.role
+             FROM track_artists ta
+             JOIN artists ar ON ar.id = ta.artist_id
+             WHERE ta.track_id = ?1
+             ORDER BY ta.position",
+        )?;
+        let rows = stmt.query_map(params![track_id], |row| {
+            Ok((
+                row.get::<_, String>("name")?,
+                row.get::<_, Option<String>>("sort_name")?,
+                row.get::<_, String>("role")?,
+            ))
+        })?;
+
+        let mut credits = Vec::new();
+        for row in rows {
+            let (name, sort_name, role) = row?;
+            if let Some(role) = ArtistRole::parse(&role) {
+                credits.push(ArtistCredit {
+                    name,
+                    sort_name,
+                    role,
+                });
+            }
+        }
+        Ok(credits)
+    }
+
+    pub fn get_all_artists(&self) -> SqliteResult<Vec<Artist>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ar.id, ar.name, ar.sort_name,
+                    COUNT(DISTINCT t.album_id) AS album_count,
+                    COUNT(DISTINCT t.id) AS track_count,
+                    MIN(t.path) AS sample_track_path
+             FROM artists ar
+             JOIN track_artists ta ON ta.artist_id = ar.id
+             JOIN tracks t ON t.id = ta.track_id AND t.missing = 0
+             GROUP BY ar.id
+             ORDER BY LOWER(COALESCE(ar.sort_name, ar.name))",
+        )?;
+
+        stmt.query_map([], row_to_artist)?
+            .collect::<SqliteResult<Vec<_>>>()
+    }
+
+    pub(super) fn top_artists_by(
+        &self,
+        join: &str,
+        group: &str,
+    ) -> SqliteResult<HashMap<i64, Vec<String>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {group} AS grp, ar.name, COUNT(DISTINCT t.id) AS n
+             FROM tracks t
+             JOIN track_artists ta ON ta.track_id = t.id AND ta.role = 'primary'
+             JOIN artists ar ON ar.id = ta.artist_id
+             {join}
+             WHERE t.missing = 0 AND {group} IS NOT NULL
+             GROUP BY grp, ar.id
+             ORDER BY grp, n DESC, LOWER(ar.name)"
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>("grp")?, row.get::<_, String>("name")?))
+        })?;
+
+        let mut top: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (group, name) = row?;
+            let names = top.entry(group).or_default();
+            if names.len() < TOP_ARTISTS {
+                names.push(name);
+            }
+        }
+        Ok(top)
+    }
+
+    pub fn get_artists_by_role(&self, role: ArtistRole) -> SqliteResult<Vec<Artist>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ar.id, ar.name, ar.sort_name,
+                    COUNT(DISTINCT t.album_id) AS album_count,
+                    COUNT(DISTINCT t.id) AS track_count,
+                    MIN(t.path) AS sample_track_path
+             FROM artists ar
+             JOIN track_artists ta ON ta.artist_id = ar.id AND ta.role = ?1
+             JOIN tracks t ON t.id = ta.track_id AND t.missing = 0
+             GROUP BY ar.id
+             ORDER BY LOWER(COALESCE(ar.sort_name, ar.name))",
+        )?;
+
+        stmt.query_map(params![role.as_str()], row_to_artist)?
+            .collect::<SqliteResult<Vec<_>>>()
+    }
+}
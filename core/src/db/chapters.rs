@@ -0,0 +1,63 @@
+use crate::{Chapter, Track};
+use rusqlite::{Result as SqliteResult, params};
+
+use super::Database;
+
+impl Database {
+    pub fn batch_upsert_chapters_from_tracks(&self, tracks: &[Track]) -> SqliteResult<()> {
+        if tracks.is_empty() {
+            return Ok(());
+        }
+
+        self.conn.execute_batch("BEGIN")?;
+        let result: SqliteResult<()> = (|| {
+            for track in tracks {
+                let path = track.path().to_str();
+                self.conn.execute(
+                    "DELETE FROM track_chapters
+                     WHERE track_id = (SELECT id FROM tracks WHERE path = ?1 AND cue_index = ?2)",
+                    params![path, track.cue_index()],
+                )?;
+                for (position, chapter) in track.chapters().iter().enumerate() {
+                    self.conn.execute(
+                        "INSERT INTO track_chapters (track_id, position, title, start_time, end_time)
+                         SELECT id, ?3, ?4, ?5, ?6 FROM tracks WHERE path = ?1 AND cue_index = ?2",
+                        params![
+                            path,
+                            track.cue_index(),
+                            position as i64,
+                            chapter.title,
+                            chapter.start,
+                            chapter.end
+                        ],
+                    )?;
+                }
+            }
+            Ok(())
+        })();
+
+        if result.is_ok() {
+            self.conn.execute_batch("COMMIT")?;
+        } else {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+        result
+    }
+
+    pub fn get_chapters_for_track(&self, track_id: i64) -> SqliteResult<Vec<Chapter>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT title, start_time, end_time
+             FROM track_chapters
+             WHERE track_id = ?1
+             ORDER BY position",
+        )?;
+        stmt.query_map(params![track_id], |row| {
+            Ok(Chapter {
+                title: row.get("title")?,
+                start: row.get("start_time")?,
+                end: row.get("end_time")?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()
+    }
+}
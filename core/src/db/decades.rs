@@ -0,0 +1,32 @@
+use crate::Decade;
+use rusqlite::Result as SqliteResult;
+
+use super::Database;
+
+impl Database {
+    pub fn get_decades(&self) -> SqliteResult<Vec<Decade>> {
+        let mut top_artists = self.top_artists_by("", "(t.year / 10) * 10")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT (t.year / 10) * 10 AS start,
+                    COUNT(t.id) AS track_count,
+                    COUNT(DISTINCT t.album_id) AS album_count,
+                    TOTAL(t.duration) AS duration
+             FROM tracks t
+             WHERE t.missing = 0 AND t.year > 0
+             GROUP BY start
+             ORDER BY start",
+        )?;
+
+        stmt.query_map([], |row| {
+            let start: i64 = row.get("start")?;
+            Ok(Decade {
+                start: start as u32,
+                track_count: row.get("track_count")?,
+                album_count: row.get("album_count")?,
+                duration: row.get::<_, f64>("duration")? as f32,
+                top_artists: top_artists.remove(&start).unwrap_or_default(),
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()
+    }
+}
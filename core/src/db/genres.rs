@@ -0,0 +1,127 @@
+use crate::{Genre, Track};
+use rusqlite::{Result as SqliteResult, params};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Database;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+impl Database {
+    pub fn batch_upsert_genres_from_tracks(&self, tracks: &[Track]) -> SqliteResult<()> {
+        if tracks.is_empty() {
+            return Ok(());
+        }
+
+        let mut genres: HashMap<String, &str> = HashMap::new();
+        for genre in tracks.iter().flat_map(Track::genres) {
+            genres.entry(genre.to_lowercase()).or_insert(genre);
+        }
+
+        let ts = now();
+
+        self.conn.execute_batch("BEGIN")?;
+        let result: SqliteResult<()> = (|| {
+            let mut ids: HashMap<&str, i64> = HashMap::new();
+            for (key, name) in &genres {
+                let id = self.conn.query_row(
+                    "INSERT INTO genres (name, created_at, updated_at)
+                     VALUES (?1, ?2, ?2)
+                     ON CONFLICT(name) DO UPDATE SET updated_at = excluded.updated_at
+                     RETURNING id",
+                    params![name, ts],
+                    |row| row.get(0),
+                )?;
+                ids.insert(key.as_str(), id);
+            }
+
+            for track in tracks {
+                let path = track.path().to_str();
+                self.conn.execute(
+                    "DELETE FROM track_genres
+                     WHERE track_id = (SELECT id FROM tracks WHERE path = ?1 AND cue_index = ?2)",
+                    params![path, track.cue_index()],
+                )?;
+                for (position, genre) in track.genres().iter().enumerate() {
+                    let Some(genre_id) = ids.get(genre.to_lowercase().as_str()) else {
+                        continue;
+                    };
+                    self.conn.execute(
+                        "INSERT OR IGNORE INTO track_genres (track_id, genre_id, position)
+                         SELECT id, ?2, ?3 FROM tracks WHERE path = ?1 AND cue_index = ?4",
+                        params![path, genre_id, position as i64, track.cue_index()],
+                    )?;
+                }
+            }
+            Ok(())
+        })();
+
+        if result.is_ok() {
+            self.conn.execute_batch("COMMIT")?;
+        } else {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+        result
+    }
+
+    pub fn delete_orphan_genres(&self) -> SqliteResult<()> {
+        self.conn.execute_batch(
+            "DELETE FROM track_genres
+             WHERE track_id NOT IN (SELECT id FROM tracks);
+             DELETE FROM genres
+             WHERE NOT EXISTS (
+                SELECT 1 FROM track_genres tg
+                JOIN tracks t ON t.id = tg.track_id
+                WHERE tg.genre_id = genres.id AND t.missing = 0
+             );
+             DELETE FROM track_genres
+             WHERE genre_id NOT IN (SELECT id FROM genres);",
+        )
+    }
+
+    pub fn get_all_genres(&self) -> SqliteResult<Vec<Genre>> {
+        let mut top_artists =
+            self.top_artists_by("JOIN track_genres tg ON tg.track_id = t.id", "tg.genre_id")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT g.id, g.name,
+                    COUNT(t.id) AS track_count,
+                    COUNT(DISTINCT t.album_id) AS album_count,
+                    TOTAL(t.duration) AS duration
+             FROM genres g
+             JOIN track_genres tg ON tg.genre_id = g.id
+             JOIN tracks t ON t.id = tg.track_id AND t.missing = 0
+             GROUP BY g.id
+             ORDER BY LOWER(g.name)",
+        )?;
+
+        stmt.query_map([], |row| {
+            let id = row.get("id")?;
+            Ok(Genre {
+                id,
+                name: row.get("name")?,
+                track_count: row.get("track_count")?,
+                album_count: row.get("album_count")?,
+                duration: row.get::<_, f64>("duration")? as f32,
+                top_artists: top_artists.remove(&id).unwrap_or_default(),
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()
+    }
+
+    pub fn get_genres_for_track(&self, track_id: i64) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT g.name
+             FROM track_genres tg
+             JOIN genres g ON g.id = tg.genre_id
+             WHERE tg.track_id = ?1
+             ORDER BY tg.position",
+        )?;
+        stmt.query_map(params![track_id], |row| row.get(0))?
+            .collect::<SqliteResult<Vec<_>>>()
+    }
+}
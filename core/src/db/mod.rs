@@ -1,31 +1,55 @@
 mod albums;
+mod artists;
+mod chapters;
+mod decades;
+mod genres;
 mod playlists;
 mod schema;
+mod tempo;
 mod tracks;
+mod works;
 
+use crate::TagSeparators;
 use rusqlite::{Connection, Result as SqliteResult};
-use std::path::Path;
+use std::{path::Path, time::Duration};
 
 pub struct Database {
     conn: Connection,
 }
 
 impl Database {
-    pub fn new(path: &Path) -> SqliteResult<Self> {
+    pub fn new(path: &Path, separators: &TagSeparators) -> SqliteResult<Self> {
         let conn = Connection::open(path)?;
+        conn.busy_timeout(Duration::from_secs(5))?;
         conn.execute_batch(
             "PRAGMA journal_mode=WAL;
              PRAGMA synchronous=NORMAL;",
         )?;
-        schema::create_tables(&conn)?;
-        Ok(Database { conn })
+        let rebuild = schema::create_tables(&conn)?;
+        let db = Database { conn };
+        if rebuild {
+            let mut tracks = db.get_all_tracks()?;
+            for track in &mut tracks {
+                track.split_tags(separators);
+            }
+            db.batch_upsert_albums_from_tracks(&tracks)?;
+            db.batch_upsert_artists_from_tracks(&tracks)?;
+            db.batch_upsert_genres_from_tracks(&tracks)?;
+        }
+        Ok(db)
     }
 
     pub fn clear_library(&self) -> SqliteResult<()> {
         self.conn.execute_batch(
             "DELETE FROM playlist_tracks;
              DELETE FROM playlists;
+             DELETE FROM track_tempo;
              DELETE FROM albums;
+             DELETE FROM track_artists;
+             DELETE FROM track_genres;
+             DELETE FROM track_chapters;
+             DELETE FROM artists;
+             DELETE FROM genres;
              DELETE FROM tracks;",
         )
     }
@@ -1,9 +1,9 @@
 use crate::{Playlist, Track};
 use rusqlite::{Result as SqliteResult, params};
-use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::Database;
+use super::tracks::{TRACK_COLUMNS, row_to_track};
 
 fn now() -> i64 {
     SystemTime::now()
@@ -120,36 +120,15 @@ impl Database {
     }
 
     pub fn get_tracks_in_playlist(&self, playlist_id: i64) -> SqliteResult<Vec<Track>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT t.id, t.path, t.title, t.track_artist, t.album, t.album_artist,
-                    t.genre, t.year, t.track_number, t.disc_number, t.comment,
-                    t.duration, t.bit_rate, t.sample_rate, t.bit_depth, t.channels, t.missing
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
              FROM tracks t
              JOIN playlist_tracks pt ON pt.track_id = t.id
              WHERE pt.playlist_id = ?1
-             ORDER BY pt.position",
-        )?;
-        stmt.query_map(params![playlist_id], |row| {
-            Ok(Track {
-                id: Some(row.get("id")?),
-                path: PathBuf::from(row.get::<_, String>("path")?),
-                missing: row.get::<_, i64>("missing")? != 0,
-                title: row.get("title")?,
-                track_artist: row.get("track_artist")?,
-                album: row.get("album")?,
-                album_artist: row.get("album_artist")?,
-                genre: row.get("genre")?,
-                year: row.get("year")?,
-                track_number: row.get("track_number")?,
-                disc_number: row.get("disc_number")?,
-                comment: row.get("comment")?,
-                duration: row.get("duration")?,
-                bit_rate: row.get("bit_rate")?,
-                sample_rate: row.get("sample_rate")?,
-                bit_depth: row.get("bit_depth")?,
-                channels: row.get("channels")?,
-            })
-        })?
-        .collect::<SqliteResult<Vec<_>>>()
+             ORDER BY pt.position"
+        ))?;
+        stmt.query_map(params![playlist_id], row_to_track)?
+            .map(|track| self.with_tags(track?))
+            .collect()
     }
 }
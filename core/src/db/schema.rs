@@ -1,37 +1,124 @@
 use rusqlite::{Connection, Result as SqliteResult};
 
-pub fn create_tables(conn: &Connection) -> SqliteResult<()> {
+const TRACKS_TABLE: &str = "(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    path TEXT NOT NULL,
+    cue_index INTEGER NOT NULL DEFAULT 0,
+    title TEXT,
+    track_artist TEXT,
+    album TEXT,
+    album_artist TEXT,
+    artist_sort TEXT,
+    album_artist_sort TEXT,
+    genre TEXT,
+    year INTEGER,
+    track_number INTEGER,
+    disc_number INTEGER,
+    comment TEXT,
+    release_id TEXT,
+    compilation INTEGER NOT NULL DEFAULT 0,
+    composer TEXT,
+    conductor TEXT,
+    performer TEXT,
+    work TEXT,
+    movement TEXT,
+    movement_number INTEGER,
+    grouping TEXT,
+    album_id INTEGER REFERENCES albums(id) ON DELETE SET NULL,
+    duration REAL NOT NULL,
+    bit_rate INTEGER,
+    sample_rate INTEGER,
+    bit_depth INTEGER,
+    channels INTEGER,
+    cue_start REAL,
+    cue_end REAL,
+    missing INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (path, cue_index)
+)";
+
+const LEGACY_TRACK_COLUMNS: &str =
+    "id, path, title, track_artist, album, album_artist, artist_sort,
+    album_artist_sort, genre, year, track_number, disc_number, comment, release_id, compilation,
+    composer, conductor, performer, work, movement, movement_number, grouping, album_id, duration,
+    bit_rate, sample_rate, bit_depth, channels, missing, created_at, updated_at";
+
+pub fn create_tables(conn: &Connection) -> SqliteResult<bool> {
+    let relink_albums = conn
+        .prepare("SELECT album_key FROM albums LIMIT 0")
+        .is_err();
+    if relink_albums {
+        conn.execute_batch("DROP TABLE IF EXISTS albums")?;
+    }
+    let rebuild_credits = conn
+        .prepare("SELECT role FROM track_artists LIMIT 0")
+        .is_err();
+    let rekey_tracks = conn.prepare("SELECT id FROM tracks LIMIT 0").is_ok()
+        && conn
+            .prepare("SELECT cue_index FROM tracks LIMIT 0")
+            .is_err();
+
+    conn.execute(
+        &format!("CREATE TABLE IF NOT EXISTS tracks {TRACKS_TABLE}"),
+        [],
+    )?;
+
     conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS tracks (
+        "CREATE TABLE IF NOT EXISTS albums (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
-            path TEXT NOT NULL UNIQUE,
-            title TEXT,
-            track_artist TEXT,
-            album TEXT,
-            album_artist TEXT,
-            genre TEXT,
+            album_key TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            artist TEXT,
             year INTEGER,
-            track_number INTEGER,
-            disc_number INTEGER,
-            comment TEXT,
-            duration REAL NOT NULL,
-            bit_rate INTEGER,
-            sample_rate INTEGER,
-            bit_depth INTEGER,
-            channels INTEGER,
-            missing INTEGER NOT NULL DEFAULT 0,
+            release_id TEXT,
+            compilation INTEGER NOT NULL DEFAULT 0,
             created_at INTEGER NOT NULL,
             updated_at INTEGER NOT NULL
         );
 
-        CREATE TABLE IF NOT EXISTS albums (
+        CREATE TABLE IF NOT EXISTS artists (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            artist TEXT,
-            year INTEGER,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            sort_name TEXT,
             created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL,
-            UNIQUE(name, artist)
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS track_artists (
+            track_id INTEGER NOT NULL,
+            artist_id INTEGER NOT NULL,
+            role TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            PRIMARY KEY (track_id, artist_id, role),
+            FOREIGN KEY (track_id) REFERENCES tracks(id) ON DELETE CASCADE,
+            FOREIGN KEY (artist_id) REFERENCES artists(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS genres (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS track_genres (
+            track_id INTEGER NOT NULL,
+            genre_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            PRIMARY KEY (track_id, genre_id),
+            FOREIGN KEY (track_id) REFERENCES tracks(id) ON DELETE CASCADE,
+            FOREIGN KEY (genre_id) REFERENCES genres(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS track_chapters (
+            track_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            start_time REAL NOT NULL,
+            end_time REAL NOT NULL,
+            PRIMARY KEY (track_id, position),
+            FOREIGN KEY (track_id) REFERENCES tracks(id) ON DELETE CASCADE
         );
 
         CREATE TABLE IF NOT EXISTS playlists (
@@ -51,10 +138,23 @@ pub fn create_tables(conn: &Connection) -> SqliteResult<()> {
             FOREIGN KEY (track_id) REFERENCES tracks(id) ON DELETE CASCADE
         );
 
-        CREATE INDEX IF NOT EXISTS idx_tracks_path ON tracks(path);
-        CREATE INDEX IF NOT EXISTS idx_tracks_album ON tracks(album);
-        CREATE INDEX IF NOT EXISTS idx_tracks_artist ON tracks(track_artist);
-        CREATE INDEX IF NOT EXISTS idx_tracks_missing ON tracks(missing);
+        CREATE TABLE IF NOT EXISTS track_tempo (
+            track_id INTEGER PRIMARY KEY,
+            speed REAL NOT NULL,
+            semitones REAL NOT NULL,
+            preserve_pitch INTEGER NOT NULL,
+            FOREIGN KEY (track_id) REFERENCES tracks(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS genre_tempo (
+            genre TEXT PRIMARY KEY COLLATE NOCASE,
+            speed REAL NOT NULL,
+            semitones REAL NOT NULL,
+            preserve_pitch INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_track_artists_artist ON track_artists(artist_id);
+        CREATE INDEX IF NOT EXISTS idx_track_genres_genre ON track_genres(genre_id);
         CREATE INDEX IF NOT EXISTS idx_playlist_tracks_position ON playlist_tracks(playlist_id, position);"
     )?;
 
@@ -62,6 +162,58 @@ pub fn create_tables(conn: &Connection) -> SqliteResult<()> {
         "ALTER TABLE playlists ADD COLUMN cover_track_id INTEGER REFERENCES tracks(id) ON DELETE SET NULL",
         [],
     );
+    let _ = conn.execute("ALTER TABLE tracks ADD COLUMN release_id TEXT", []);
+    let _ = conn.execute(
+        "ALTER TABLE tracks ADD COLUMN compilation INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+    let _ = conn.execute(
+        "ALTER TABLE albums ADD COLUMN compilation INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+    for column in [
+        "composer TEXT",
+        "conductor TEXT",
+        "performer TEXT",
+        "work TEXT",
+        "movement TEXT",
+        "movement_number INTEGER",
+        "grouping TEXT",
+    ] {
+        let _ = conn.execute(&format!("ALTER TABLE tracks ADD COLUMN {column}"), []);
+    }
+    let _ = conn.execute("ALTER TABLE tracks ADD COLUMN artist_sort TEXT", []);
+    let _ = conn.execute("ALTER TABLE tracks ADD COLUMN album_artist_sort TEXT", []);
+    let _ = conn.execute(
+        "ALTER TABLE tracks ADD COLUMN album_id INTEGER REFERENCES albums(id) ON DELETE SET NULL",
+        [],
+    );
+
+    if rekey_tracks {
+        let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+        conn.execute_batch(&format!(
+            "PRAGMA foreign_keys = OFF;
+             BEGIN;
+             CREATE TABLE tracks_rekeyed {TRACKS_TABLE};
+             INSERT INTO tracks_rekeyed ({LEGACY_TRACK_COLUMNS})
+                 SELECT {LEGACY_TRACK_COLUMNS} FROM tracks;
+             DROP TABLE tracks;
+             ALTER TABLE tracks_rekeyed RENAME TO tracks;
+             COMMIT;
+             PRAGMA foreign_keys = {};",
+            if foreign_keys { "ON" } else { "OFF" }
+        ))?;
+    }
+
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_tracks_path ON tracks(path);
+        CREATE INDEX IF NOT EXISTS idx_tracks_album ON tracks(album);
+        CREATE INDEX IF NOT EXISTS idx_tracks_artist ON tracks(track_artist);
+        CREATE INDEX IF NOT EXISTS idx_tracks_album_artist ON tracks(album_artist);
+        CREATE INDEX IF NOT EXISTS idx_tracks_missing ON tracks(missing);
+        CREATE INDEX IF NOT EXISTS idx_tracks_album_id ON tracks(album_id);
+        CREATE INDEX IF NOT EXISTS idx_tracks_work ON tracks(work);",
+    )?;
 
-    Ok(())
+    Ok(relink_albums || rebuild_credits)
 }
@@ -0,0 +1,77 @@
+use crate::Tempo;
+use rusqlite::{OptionalExtension, Result as SqliteResult, Row, params};
+
+use super::Database;
+
+fn row_to_tempo(row: &Row) -> SqliteResult<Tempo> {
+    Ok(Tempo {
+        speed: row.get("speed")?,
+        semitones: row.get("semitones")?,
+        preserve_pitch: row.get::<_, i64>("preserve_pitch")? != 0,
+    })
+}
+
+impl Database {
+    pub fn get_tempo_for_track(&self, track_id: i64) -> SqliteResult<Option<Tempo>> {
+        self.conn
+            .query_row(
+                "SELECT speed, semitones, preserve_pitch FROM (
+                    SELECT 0 AS priority, speed, semitones, preserve_pitch
+                    FROM track_tempo WHERE track_id = ?1
+                    UNION ALL
+                    SELECT 1 AS priority, g.speed, g.semitones, g.preserve_pitch
+                    FROM genre_tempo g
+                    JOIN tracks t ON g.genre = t.genre
+                    WHERE t.id = ?1
+                    UNION ALL
+                    SELECT 2 + tg.position AS priority, g.speed, g.semitones, g.preserve_pitch
+                    FROM genre_tempo g
+                    JOIN genres gn ON gn.name = g.genre
+                    JOIN track_genres tg ON tg.genre_id = gn.id
+                    WHERE tg.track_id = ?1
+                 )
+                 ORDER BY priority
+                 LIMIT 1",
+                params![track_id],
+                row_to_tempo,
+            )
+            .optional()
+    }
+
+    pub fn set_track_tempo(&self, track_id: i64, tempo: Option<Tempo>) -> SqliteResult<()> {
+        match tempo {
+            Some(tempo) => self.conn.execute(
+                "INSERT INTO track_tempo (track_id, speed, semitones, preserve_pitch)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(track_id) DO UPDATE SET
+                     speed          = excluded.speed,
+                     semitones      = excluded.semitones,
+                     preserve_pitch = excluded.preserve_pitch",
+                params![track_id, tempo.speed, tempo.semitones, tempo.preserve_pitch],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM track_tempo WHERE track_id = ?1",
+                params![track_id],
+            )?,
+        };
+        Ok(())
+    }
+
+    pub fn set_genre_tempo(&self, genre: &str, tempo: Option<Tempo>) -> SqliteResult<()> {
+        match tempo {
+            Some(tempo) => self.conn.execute(
+                "INSERT INTO genre_tempo (genre, speed, semitones, preserve_pitch)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(genre) DO UPDATE SET
+                     speed          = excluded.speed,
+                     semitones      = excluded.semitones,
+                     preserve_pitch = excluded.preserve_pitch",
+                params![genre, tempo.speed, tempo.semitones, tempo.preserve_pitch],
+            )?,
+            None => self
+                .conn
+                .execute("DELETE FROM genre_tempo WHERE genre = ?1", params![genre])?,
+        };
+        Ok(())
+    }
+}
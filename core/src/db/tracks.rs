@@ -5,6 +5,12 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::Database;
 
+pub(super) const TRACK_COLUMNS: &str =
+    "id, path, title, track_artist, album, album_artist, artist_sort, album_artist_sort,
+    genre, year, track_number, disc_number, comment, release_id, compilation, composer, conductor,
+    performer, work, movement, movement_number, grouping, album_id, duration, bit_rate,
+    sample_rate, bit_depth, channels, missing, cue_index, cue_start, cue_end, created_at";
+
 fn now() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -12,29 +18,57 @@ fn now() -> i64 {
         .as_secs() as i64
 }
 
-fn row_to_track(row: &Row) -> SqliteResult<Track> {
+pub(super) fn row_to_track(row: &Row) -> SqliteResult<Track> {
     Ok(Track {
         id: Some(row.get("id")?),
         path: PathBuf::from(row.get::<_, String>("path")?),
         missing: row.get::<_, i64>("missing")? != 0,
+        album_id: row.get("album_id")?,
         title: row.get("title")?,
         track_artist: row.get("track_artist")?,
         album: row.get("album")?,
         album_artist: row.get("album_artist")?,
+        artist_sort: row.get("artist_sort")?,
+        album_artist_sort: row.get("album_artist_sort")?,
+        credits: Vec::new(),
+        genres: Vec::new(),
         genre: row.get("genre")?,
         year: row.get("year")?,
         track_number: row.get("track_number")?,
         disc_number: row.get("disc_number")?,
         comment: row.get("comment")?,
+        release_id: row.get("release_id")?,
+        compilation: row.get::<_, i64>("compilation")? != 0,
+        composer: row.get("composer")?,
+        conductor: row.get("conductor")?,
+        performer: row.get("performer")?,
+        work: row.get("work")?,
+        movement: row.get("movement")?,
+        movement_number: row.get("movement_number")?,
+        grouping: row.get("grouping")?,
         duration: row.get("duration")?,
         bit_rate: row.get("bit_rate")?,
         sample_rate: row.get("sample_rate")?,
         bit_depth: row.get("bit_depth")?,
         channels: row.get("channels")?,
+        cue_index: row.get("cue_index")?,
+        cue_start: row.get("cue_start")?,
+        cue_end: row.get("cue_end")?,
+        chapters: Vec::new(),
+        added_at: row.get("created_at")?,
     })
 }
 
 impl Database {
+    pub(super) fn with_tags(&self, mut track: Track) -> SqliteResult<Track> {
+        if let Some(id) = track.id {
+            track.credits = self.get_credits_for_track(id)?;
+            track.genres = self.get_genres_for_track(id)?;
+            track.chapters = self.get_chapters_for_track(id)?;
+        }
+        Ok(track)
+    }
+
     pub fn batch_upsert_tracks(&self, tracks: &[Track]) -> SqliteResult<()> {
         if tracks.is_empty() {
             return Ok(());
@@ -47,27 +81,43 @@ impl Database {
             for track in tracks {
                 self.conn.execute(
                     "INSERT INTO tracks (
-                        path, title, track_artist, album, album_artist, genre,
-                        year, track_number, disc_number, comment,
-                        duration, bit_rate, sample_rate, bit_depth, channels,
+                        path, title, track_artist, album, album_artist, artist_sort,
+                        album_artist_sort, genre, year, track_number, disc_number,
+                        comment, release_id, compilation, composer, conductor, performer,
+                        work, movement, movement_number, grouping, duration, bit_rate,
+                        sample_rate, bit_depth, channels, cue_index, cue_start, cue_end,
                         created_at, updated_at, missing
-                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
-                              ?11, ?12, ?13, ?14, ?15, ?16, ?16, 0)
-                    ON CONFLICT(path) DO UPDATE SET
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+                              ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24,
+                              ?25, ?26, ?27, ?28, ?29, ?30, ?30, 0)
+                    ON CONFLICT(path, cue_index) DO UPDATE SET
                         title        = excluded.title,
                         track_artist = excluded.track_artist,
                         album        = excluded.album,
                         album_artist = excluded.album_artist,
+                        artist_sort  = excluded.artist_sort,
+                        album_artist_sort = excluded.album_artist_sort,
                         genre        = excluded.genre,
                         year         = excluded.year,
                         track_number = excluded.track_number,
                         disc_number  = excluded.disc_number,
                         comment      = excluded.comment,
+                        release_id   = excluded.release_id,
+                        compilation  = excluded.compilation,
+                        composer     = excluded.composer,
+                        conductor    = excluded.conductor,
+                        performer    = excluded.performer,
+                        work         = excluded.work,
+                        movement     = excluded.movement,
+                        movement_number = excluded.movement_number,
+                        grouping     = excluded.grouping,
                         duration     = excluded.duration,
                         bit_rate     = excluded.bit_rate,
                         sample_rate  = excluded.sample_rate,
                         bit_depth    = excluded.bit_depth,
                         channels     = excluded.channels,
+                        cue_start    = excluded.cue_start,
+                        cue_end      = excluded.cue_end,
                         updated_at   = excluded.updated_at,
                         missing      = 0",
                     params![
@@ -76,16 +126,30 @@ impl Database {
                         track.track_artist(),
                         track.album(),
                         track.album_artist(),
+                        track.artist_sort(),
+                        track.album_artist_sort(),
                         track.genre(),
                         track.year(),
                         track.track_number(),
                         track.disc_number(),
                         track.comment(),
+                        track.release_id(),
+                        track.compilation(),
+                        track.composer(),
+                        track.conductor(),
+                        track.performer(),
+                        track.work(),
+                        track.movement(),
+                        track.movement_number(),
+                        track.grouping(),
                         track.duration(),
                         track.bit_rate(),
                         track.sample_rate(),
                         track.bit_depth(),
                         track.channels(),
+                        track.cue_index(),
+                        track.cue_start(),
+                        track.cue_end(),
                         ts,
                     ],
                 )?;
@@ -104,52 +168,55 @@ impl Database {
     pub fn get_track_by_id(&self, id: i64) -> SqliteResult<Option<Track>> {
         self.conn
             .query_row(
-                "SELECT id, path, title, track_artist, album, album_artist,
-                        genre, year, track_number, disc_number, comment,
-                        duration, bit_rate, sample_rate, bit_depth, channels, missing
-                 FROM tracks WHERE id = ?1",
+                &format!(
+                    "SELECT {TRACK_COLUMNS}
+                     FROM tracks WHERE id = ?1"
+                ),
                 params![id],
                 row_to_track,
             )
-            .optional()
+            .optional()?
+            .map(|track| self.with_tags(track))
+            .transpose()
     }
 
     pub fn get_track_by_path(&self, path: &str) -> SqliteResult<Option<Track>> {
         self.conn
             .query_row(
-                "SELECT id, path, title, track_artist, album, album_artist,
-                        genre, year, track_number, disc_number, comment,
-                        duration, bit_rate, sample_rate, bit_depth, channels, missing
-                 FROM tracks WHERE path = ?1",
+                &format!(
+                    "SELECT {TRACK_COLUMNS}
+                     FROM tracks WHERE path = ?1
+                     ORDER BY cue_index"
+                ),
                 params![path],
                 row_to_track,
             )
-            .optional()
+            .optional()?
+            .map(|track| self.with_tags(track))
+            .transpose()
     }
 
     pub fn get_all_tracks(&self) -> SqliteResult<Vec<Track>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, path, title, track_artist, album, album_artist,
-                    genre, year, track_number, disc_number, comment,
-                    duration, bit_rate, sample_rate, bit_depth, channels, missing
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
              FROM tracks
-             ORDER BY album, disc_number, track_number",
-        )?;
+             ORDER BY album, disc_number, track_number"
+        ))?;
         stmt.query_map([], row_to_track)?
-            .collect::<SqliteResult<Vec<_>>>()
+            .map(|track| self.with_tags(track?))
+            .collect()
     }
 
     pub fn get_n_tracks(&self, limit: i64) -> SqliteResult<Vec<Track>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, path, title, track_artist, album, album_artist,
-                    genre, year, track_number, disc_number, comment,
-                    duration, bit_rate, sample_rate, bit_depth, channels, missing
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
              FROM tracks
              ORDER BY album, disc_number, track_number
-             LIMIT ?1",
-        )?;
+             LIMIT ?1"
+        ))?;
         stmt.query_map(params![limit], row_to_track)?
-            .collect::<SqliteResult<Vec<_>>>()
+            .map(|track| self.with_tags(track?))
+            .collect()
     }
 
     pub fn get_tracks_by_album(
@@ -157,30 +224,134 @@ impl Database {
         album_name: &str,
         artist: Option<&str>,
     ) -> SqliteResult<Vec<Track>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, path, title, track_artist, album, album_artist,
-                    genre, year, track_number, disc_number, comment,
-                    duration, bit_rate, sample_rate, bit_depth, channels, missing
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
              FROM tracks
              WHERE album = ?1
                AND (?2 IS NULL OR album_artist = ?2 OR track_artist = ?2)
-             ORDER BY disc_number, track_number",
-        )?;
+             ORDER BY disc_number, track_number"
+        ))?;
         stmt.query_map(params![album_name, artist], row_to_track)?
-            .collect::<SqliteResult<Vec<_>>>()
+            .map(|track| self.with_tags(track?))
+            .collect()
+    }
+
+    pub fn get_tracks_by_album_id(&self, album_id: i64) -> SqliteResult<Vec<Track>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
+             FROM tracks
+             WHERE album_id = ?1
+             ORDER BY disc_number, track_number"
+        ))?;
+        stmt.query_map(params![album_id], row_to_track)?
+            .map(|track| self.with_tags(track?))
+            .collect()
     }
 
     pub fn get_tracks_by_artist(&self, artist_name: &str) -> SqliteResult<Vec<Track>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, path, title, track_artist, album, album_artist,
-                    genre, year, track_number, disc_number, comment,
-                    duration, bit_rate, sample_rate, bit_depth, channels, missing
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
              FROM tracks
-             WHERE track_artist = ?1 OR album_artist = ?1
-             ORDER BY album, disc_number, track_number",
-        )?;
+             WHERE id IN (
+                SELECT ta.track_id FROM track_artists ta
+                JOIN artists ar ON ar.id = ta.artist_id
+                WHERE ar.name = ?1
+             )
+             ORDER BY album, disc_number, track_number"
+        ))?;
         stmt.query_map(params![artist_name], row_to_track)?
-            .collect::<SqliteResult<Vec<_>>>()
+            .map(|track| self.with_tags(track?))
+            .collect()
+    }
+
+    pub fn get_tracks_by_artist_id(&self, artist_id: i64) -> SqliteResult<Vec<Track>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
+             FROM tracks t
+             WHERE t.id IN (SELECT track_id FROM track_artists WHERE artist_id = ?1)
+               AND t.missing = 0
+             ORDER BY t.year NULLS LAST, t.album, t.disc_number, t.track_number"
+        ))?;
+        stmt.query_map(params![artist_id], row_to_track)?
+            .map(|track| self.with_tags(track?))
+            .collect()
+    }
+
+    pub fn get_tracks_by_genre(&self, genre: &str) -> SqliteResult<Vec<Track>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
+             FROM tracks
+             WHERE id IN (
+                SELECT tg.track_id FROM track_genres tg
+                JOIN genres g ON g.id = tg.genre_id
+                WHERE g.name = ?1
+             )
+             ORDER BY album, disc_number, track_number"
+        ))?;
+        stmt.query_map(params![genre], row_to_track)?
+            .map(|track| self.with_tags(track?))
+            .collect()
+    }
+
+    pub fn get_tracks_by_decade(&self, start: u32) -> SqliteResult<Vec<Track>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
+             FROM tracks
+             WHERE year >= ?1 AND year < ?1 + 10 AND missing = 0
+             ORDER BY year, album, disc_number, track_number"
+        ))?;
+        stmt.query_map(params![start], row_to_track)?
+            .map(|track| self.with_tags(track?))
+            .collect()
+    }
+
+    pub fn get_tracks_by_work(&self, composer_id: i64, work: &str) -> SqliteResult<Vec<Track>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
+             FROM tracks t
+             WHERE t.id IN (
+                SELECT track_id FROM track_artists WHERE artist_id = ?1 AND role = 'composer'
+             )
+               AND t.work = ?2 COLLATE NOCASE
+               AND t.missing = 0
+             ORDER BY t.album, t.disc_number, t.movement_number NULLS LAST, t.track_number"
+        ))?;
+        stmt.query_map(params![composer_id, work], row_to_track)?
+            .map(|track| self.with_tags(track?))
+            .collect()
+    }
+
+    pub fn get_tracks_in_folder(&self, prefix: &str) -> SqliteResult<Vec<Track>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {TRACK_COLUMNS}
+             FROM tracks
+             WHERE substr(path, 1, length(?1)) = ?1 AND missing = 0
+             ORDER BY path, cue_index"
+        ))?;
+        stmt.query_map(params![prefix], row_to_track)?
+            .map(|track| self.with_tags(track?))
+            .collect()
+    }
+
+    pub fn get_track_locations(&self) -> SqliteResult<Vec<(i64, PathBuf, f32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, path, duration FROM tracks WHERE missing = 0 ORDER BY path, cue_index",
+        )?;
+        stmt.query_map([], |row| {
+            Ok((
+                row.get("id")?,
+                PathBuf::from(row.get::<_, String>("path")?),
+                row.get("duration")?,
+            ))
+        })?
+        .collect()
+    }
+
+    pub fn get_track_ids_by_path(&self, path: &str) -> SqliteResult<Vec<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM tracks WHERE path = ?1 AND missing = 0")?;
+        stmt.query_map(params![path], |row| row.get(0))?.collect()
     }
 
     pub fn count_tracks(&self) -> SqliteResult<i64> {
@@ -188,6 +359,46 @@ impl Database {
             .query_row("SELECT COUNT(*) FROM tracks", [], |row| row.get(0))
     }
 
+    pub fn delete_tracks(&self, ids: &[i64]) -> SqliteResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        self.conn.execute_batch("BEGIN")?;
+        let result: SqliteResult<()> = (|| {
+            for id in ids {
+                self.conn.execute(
+                    "DELETE FROM playlist_tracks WHERE track_id = ?1",
+                    params![id],
+                )?;
+                self.conn
+                    .execute("DELETE FROM track_tempo WHERE track_id = ?1", params![id])?;
+                self.conn
+                    .execute("DELETE FROM track_artists WHERE track_id = ?1", params![id])?;
+                self.conn
+                    .execute("DELETE FROM track_genres WHERE track_id = ?1", params![id])?;
+                self.conn.execute(
+                    "DELETE FROM track_chapters WHERE track_id = ?1",
+                    params![id],
+                )?;
+                self.conn.execute(
+                    "UPDATE playlists SET cover_track_id = NULL WHERE cover_track_id = ?1",
+                    params![id],
+                )?;
+                self.conn
+                    .execute("DELETE FROM tracks WHERE id = ?1", params![id])?;
+            }
+            Ok(())
+        })();
+
+        if result.is_ok() {
+            self.conn.execute_batch("COMMIT")?;
+        } else {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+        result
+    }
+
     pub fn mark_all_missing(&self) -> SqliteResult<()> {
         self.conn.execute("UPDATE tracks SET missing = 1", [])?;
         Ok(())
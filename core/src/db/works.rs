@@ -0,0 +1,31 @@
+use crate::Work;
+use rusqlite::{Result as SqliteResult, params};
+
+use super::Database;
+
+impl Database {
+    pub fn get_works_by_composer(&self, composer_id: i64) -> SqliteResult<Vec<Work>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT MIN(t.work) AS name,
+                    COUNT(t.id) AS track_count,
+                    TOTAL(t.duration) AS duration,
+                    MIN(t.path) AS sample_track_path
+             FROM tracks t
+             JOIN track_artists ta ON ta.track_id = t.id AND ta.role = 'composer'
+             WHERE ta.artist_id = ?1 AND t.missing = 0 AND t.work IS NOT NULL
+             GROUP BY LOWER(t.work)
+             ORDER BY LOWER(t.work)",
+        )?;
+
+        stmt.query_map(params![composer_id], |row| {
+            Ok(Work {
+                name: row.get("name")?,
+                composer_id,
+                track_count: row.get("track_count")?,
+                duration: row.get::<_, f64>("duration")? as f32,
+                sample_track_path: row.get("sample_track_path")?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()
+    }
+}
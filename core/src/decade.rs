@@ -0,0 +1,14 @@
+#[derive(Debug, Clone)]
+pub struct Decade {
+    pub start: u32,
+    pub track_count: i64,
+    pub album_count: i64,
+    pub duration: f32,
+    pub top_artists: Vec<String>,
+}
+
+impl Decade {
+    pub fn label(&self) -> String {
+        format!("{}s", self.start)
+    }
+}
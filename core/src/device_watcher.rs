@@ -0,0 +1,54 @@
+use cpal::traits::DeviceTrait;
+use crossbeam::channel::{self, RecvTimeoutError, Sender};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::backend::find_output_device;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+type Resolved = Arc<Mutex<Option<(Option<String>, Option<String>)>>>;
+
+pub(crate) struct DeviceWatcher {
+    requests: Sender<Option<String>>,
+    resolved: Resolved,
+}
+
+impl DeviceWatcher {
+    pub(crate) fn spawn(requested: Option<&str>) -> Self {
+        let (requests, updates) = channel::unbounded();
+        let resolved = Resolved::default();
+
+        let shared = resolved.clone();
+        let mut requested = requested.map(str::to_string);
+        let _ = thread::Builder::new()
+            .name("verse-devices".into())
+            .spawn(move || {
+                loop {
+                    match updates.recv_timeout(CHECK_INTERVAL) {
+                        Ok(request) => requested = request,
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                    let name = find_output_device(requested.as_deref()).and_then(|d| d.name().ok());
+                    *shared.lock().unwrap() = Some((requested.clone(), name));
+                }
+            });
+
+        DeviceWatcher { requests, resolved }
+    }
+
+    pub(crate) fn request(&self, device: Option<&str>) {
+        let _ = self.requests.send(device.map(str::to_string));
+    }
+
+    pub(crate) fn resolved(&self, requested: Option<&str>) -> Option<Option<String>> {
+        match &*self.resolved.lock().unwrap() {
+            Some((polled, name)) if polled.as_deref() == requested => Some(name.clone()),
+            _ => None,
+        }
+    }
+}
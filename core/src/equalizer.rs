@@ -0,0 +1,263 @@
+use crossbeam::atomic::AtomicCell;
+use kira::effect::{Effect, EffectBuilder};
+use kira::{Frame, info::Info};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::biquad::{Biquad, Coefficients};
+
+pub const GRAPHIC_BANDS: usize = 10;
+pub const GRAPHIC_FREQUENCIES: [f32; GRAPHIC_BANDS] = [
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+pub const MAX_GAIN_DB: f32 = 12.0;
+pub const MAX_PARAMETRIC_BANDS: usize = 22;
+
+const MAX_FILTERS: usize = GRAPHIC_BANDS + MAX_PARAMETRIC_BANDS;
+const GRAPHIC_Q: f32 = 1.41;
+const GAIN_SMOOTHING: f32 = 0.002;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    Peaking,
+    LowShelf,
+    HighShelf,
+    LowPass,
+    HighPass,
+}
+
+impl FilterKind {
+    pub const ALL: [FilterKind; 5] = [
+        FilterKind::Peaking,
+        FilterKind::LowShelf,
+        FilterKind::HighShelf,
+        FilterKind::LowPass,
+        FilterKind::HighPass,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterKind::Peaking => "Peaking",
+            FilterKind::LowShelf => "Low shelf",
+            FilterKind::HighShelf => "High shelf",
+            FilterKind::LowPass => "Low-pass",
+            FilterKind::HighPass => "High-pass",
+        }
+    }
+
+    pub fn has_gain(&self) -> bool {
+        !matches!(self, FilterKind::LowPass | FilterKind::HighPass)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EqBand {
+    pub kind: FilterKind,
+    pub frequency: f32,
+    pub gain_db: f32,
+    pub q: f32,
+}
+
+impl EqBand {
+    pub fn new(kind: FilterKind, frequency: f32) -> Self {
+        EqBand {
+            kind,
+            frequency,
+            gain_db: 0.0,
+            q: if kind.has_gain() { 1.0 } else { 0.707 },
+        }
+    }
+
+    fn is_flat(&self) -> bool {
+        self.kind.has_gain() && self.gain_db == 0.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EqPreset {
+    pub name: String,
+    #[serde(default)]
+    pub preamp_db: f32,
+    #[serde(default)]
+    pub graphic: [f32; GRAPHIC_BANDS],
+    #[serde(default)]
+    pub bands: Vec<EqBand>,
+}
+
+impl EqPreset {
+    pub fn flat(name: impl Into<String>) -> Self {
+        EqPreset {
+            name: name.into(),
+            preamp_db: 0.0,
+            graphic: [0.0; GRAPHIC_BANDS],
+            bands: Vec::new(),
+        }
+    }
+
+    pub fn builtin() -> Vec<EqPreset> {
+        let graphic = |name: &str, preamp_db: f32, graphic: [f32; GRAPHIC_BANDS]| EqPreset {
+            name: name.to_string(),
+            preamp_db,
+            graphic,
+            bands: Vec::new(),
+        };
+
+        vec![
+            EqPreset::flat("Flat"),
+            graphic(
+                "Bass Boost",
+                -6.0,
+                [6.0, 5.0, 4.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            ),
+            graphic(
+                "Treble Boost",
+                -5.0,
+                [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.5, 3.0, 4.5, 5.0],
+            ),
+            graphic(
+                "Vocal",
+                -3.0,
+                [-2.0, -2.0, -1.0, 1.0, 3.0, 3.0, 2.0, 1.0, 0.0, -1.0],
+            ),
+            graphic(
+                "Loudness",
+                -5.0,
+                [5.0, 4.0, 2.0, 0.0, -1.0, 0.0, 0.0, 1.0, 3.0, 4.0],
+            ),
+        ]
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        EqPreset::builtin().iter().any(|p| p.name == self.name)
+    }
+
+    pub(crate) fn filters(&self) -> impl Iterator<Item = EqBand> + '_ {
+        GRAPHIC_FREQUENCIES
+            .iter()
+            .zip(self.graphic)
+            .map(|(&frequency, gain_db)| EqBand {
+                kind: FilterKind::Peaking,
+                frequency,
+                gain_db,
+                q: GRAPHIC_Q,
+            })
+            .chain(self.bands.iter().copied())
+            .filter(|band| !band.is_flat())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct EqParams {
+    preamp_db: f32,
+    filters: [Option<EqBand>; MAX_FILTERS],
+}
+
+impl EqParams {
+    pub(crate) const BYPASS: EqParams = EqParams {
+        preamp_db: 0.0,
+        filters: [None; MAX_FILTERS],
+    };
+
+    pub(crate) fn new(preamp_db: f32, bands: impl Iterator<Item = EqBand>) -> Self {
+        let mut filters = [None; MAX_FILTERS];
+        for (slot, band) in filters.iter_mut().zip(bands) {
+            *slot = Some(band);
+        }
+        EqParams { preamp_db, filters }
+    }
+
+    pub(crate) fn from_preset(preset: Option<&EqPreset>) -> Self {
+        match preset {
+            Some(preset) => EqParams::new(preset.preamp_db, preset.filters()),
+            None => EqParams::BYPASS,
+        }
+    }
+}
+
+pub(crate) struct EqualizerBuilder {
+    params: Arc<AtomicCell<EqParams>>,
+}
+
+impl EqualizerBuilder {
+    pub(crate) fn new() -> (Self, Arc<AtomicCell<EqParams>>) {
+        let params = Arc::new(AtomicCell::new(EqParams::BYPASS));
+        (
+            Self {
+                params: params.clone(),
+            },
+            params,
+        )
+    }
+}
+
+impl EffectBuilder for EqualizerBuilder {
+    type Handle = ();
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        (Box::new(Equalizer::new(self.params)), ())
+    }
+}
+
+struct Equalizer {
+    params: Arc<AtomicCell<EqParams>>,
+    applied: EqParams,
+    filters: [Biquad; MAX_FILTERS],
+    gain: f32,
+    target_gain: f32,
+    sample_rate: f32,
+}
+
+impl Equalizer {
+    fn new(params: Arc<AtomicCell<EqParams>>) -> Self {
+        Self {
+            params,
+            applied: EqParams::BYPASS,
+            filters: [Biquad::new(); MAX_FILTERS],
+            gain: 1.0,
+            target_gain: 1.0,
+            sample_rate: 44100.0,
+        }
+    }
+
+    fn apply(&mut self, params: EqParams) {
+        for (filter, band) in self.filters.iter_mut().zip(params.filters) {
+            filter.set_target(match band {
+                Some(band) => Coefficients::new(&band, self.sample_rate),
+                None => Coefficients::IDENTITY,
+            });
+        }
+        self.target_gain = 10f32.powf(params.preamp_db / 20.0);
+        self.applied = params;
+    }
+}
+
+impl Effect for Equalizer {
+    fn process(&mut self, input: &mut [Frame], dt: f64, _info: &Info) {
+        let params = self.params.load();
+
+        if dt > 0.0 {
+            let sr = (1.0 / dt) as f32;
+            if (sr - self.sample_rate).abs() > 1.0 {
+                self.sample_rate = sr;
+                self.apply(params);
+            }
+        }
+
+        if params != self.applied {
+            self.apply(params);
+        }
+
+        for frame in input.iter_mut() {
+            self.gain += (self.target_gain - self.gain) * GAIN_SMOOTHING;
+
+            let mut out = *frame * self.gain;
+            for filter in self.filters.iter_mut() {
+                if !filter.is_bypassed() {
+                    out = filter.process(out);
+                }
+            }
+            *frame = out;
+        }
+    }
+}
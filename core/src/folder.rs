@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct Folder {
+    pub path: PathBuf,
+    pub name: String,
+    pub folders: Vec<Folder>,
+    pub track_ids: Vec<i64>,
+    pub track_count: i64,
+    pub duration: f32,
+}
+
+impl Folder {
+    fn new(path: PathBuf) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+        Folder {
+            path,
+            name,
+            folders: Vec::new(),
+            track_ids: Vec::new(),
+            track_count: 0,
+            duration: 0.0,
+        }
+    }
+
+    pub fn find(&self, path: &Path) -> Option<&Folder> {
+        if self.path == path {
+            return Some(self);
+        }
+        if !path.starts_with(&self.path) {
+            return None;
+        }
+        self.folders.iter().find_map(|folder| folder.find(path))
+    }
+
+    fn insert(&mut self, dir: &Path, id: i64, duration: f32) {
+        self.track_count += 1;
+        self.duration += duration;
+
+        let Ok(relative) = dir.strip_prefix(&self.path) else {
+            return;
+        };
+        let Some(next) = relative.components().next() else {
+            self.track_ids.push(id);
+            return;
+        };
+
+        let child = self.path.join(next);
+        let index = match self.folders.iter().position(|f| f.path == child) {
+            Some(index) => index,
+            None => {
+                self.folders.push(Folder::new(child));
+                self.folders.len() - 1
+            }
+        };
+        self.folders[index].insert(dir, id, duration);
+    }
+
+    fn sort(&mut self) {
+        self.folders
+            .sort_by_cached_key(|folder| folder.name.to_lowercase());
+        for folder in &mut self.folders {
+            folder.sort();
+        }
+    }
+}
+
+pub(crate) fn build_tree(roots: &[&Path], mut tracks: Vec<(i64, PathBuf, f32)>) -> Vec<Folder> {
+    tracks.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let mut tree: Vec<Folder> = roots
+        .iter()
+        .map(|root| Folder::new(root.to_path_buf()))
+        .collect();
+    let mut outside: Vec<(i64, PathBuf, f32)> = Vec::new();
+
+    for (id, path, duration) in tracks {
+        let Some(dir) = path.parent() else {
+            continue;
+        };
+        match tree.iter_mut().find(|root| dir.starts_with(&root.path)) {
+            Some(root) => root.insert(dir, id, duration),
+            None => outside.push((id, path, duration)),
+        }
+    }
+
+    if let Some(common) = common_ancestor(outside.iter().filter_map(|(_, path, _)| path.parent())) {
+        let mut root = Folder::new(common);
+        for (id, path, duration) in &outside {
+            if let Some(dir) = path.parent() {
+                root.insert(dir, *id, *duration);
+            }
+        }
+        tree.push(root);
+    }
+
+    tree.retain(|root| root.track_count > 0);
+    for root in &mut tree {
+        root.sort();
+    }
+    tree
+}
+
+fn common_ancestor<'a>(mut dirs: impl Iterator<Item = &'a Path>) -> Option<PathBuf> {
+    let mut common = dirs.next()?.to_path_buf();
+    for dir in dirs {
+        while !dir.starts_with(&common) {
+            if !common.pop() {
+                break;
+            }
+        }
+    }
+    Some(common)
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioFormat {
+    Mp3,
+    Flac,
+    Wav,
+    Aiff,
+    Vorbis,
+    Opus,
+    Aac,
+    Mp4,
+    WavPack,
+    MonkeysAudio,
+    Musepack,
+}
+
+impl AudioFormat {
+    pub const ALL: [AudioFormat; 11] = [
+        AudioFormat::Mp3,
+        AudioFormat::Flac,
+        AudioFormat::Wav,
+        AudioFormat::Aiff,
+        AudioFormat::Vorbis,
+        AudioFormat::Opus,
+        AudioFormat::Aac,
+        AudioFormat::Mp4,
+        AudioFormat::WavPack,
+        AudioFormat::MonkeysAudio,
+        AudioFormat::Musepack,
+    ];
+
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            AudioFormat::Mp3 => &["mp3"],
+            AudioFormat::Flac => &["flac"],
+            AudioFormat::Wav => &["wav"],
+            AudioFormat::Aiff => &["aif", "aiff"],
+            AudioFormat::Vorbis => &["ogg", "oga"],
+            AudioFormat::Opus => &["opus"],
+            AudioFormat::Aac => &["aac"],
+            AudioFormat::Mp4 => &["m4a", "m4b", "mp4"],
+            AudioFormat::WavPack => &["wv"],
+            AudioFormat::MonkeysAudio => &["ape"],
+            AudioFormat::Musepack => &["mpc", "mp+", "mpp"],
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extensions().contains(&ext.as_str()))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "MP3",
+            AudioFormat::Flac => "FLAC",
+            AudioFormat::Wav => "WAV",
+            AudioFormat::Aiff => "AIFF",
+            AudioFormat::Vorbis => "Ogg Vorbis",
+            AudioFormat::Opus => "Opus",
+            AudioFormat::Aac => "AAC",
+            AudioFormat::Mp4 => "MPEG-4 (AAC/ALAC)",
+            AudioFormat::WavPack => "WavPack",
+            AudioFormat::MonkeysAudio => "Monkey's Audio",
+            AudioFormat::Musepack => "Musepack",
+        }
+    }
+
+    pub fn is_decodable(self) -> bool {
+        !matches!(
+            self,
+            AudioFormat::Opus
+                | AudioFormat::WavPack
+                | AudioFormat::MonkeysAudio
+                | AudioFormat::Musepack
+        )
+    }
+}
+
+impl fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
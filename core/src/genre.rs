@@ -0,0 +1,80 @@
+#[derive(Debug, Clone)]
+pub struct Genre {
+    pub id: i64,
+    pub name: String,
+    pub track_count: i64,
+    pub album_count: i64,
+    pub duration: f32,
+    pub top_artists: Vec<String>,
+}
+
+const ALIASES: &[(&str, &str)] = &[
+    ("hiphop", "Hip-Hop"),
+    ("randb", "R&B"),
+    ("rnb", "R&B"),
+    ("rhythmandblues", "R&B"),
+    ("drumandbass", "Drum & Bass"),
+    ("drumnbass", "Drum & Bass"),
+    ("dnb", "Drum & Bass"),
+    ("rockandroll", "Rock & Roll"),
+    ("rocknroll", "Rock & Roll"),
+    ("lofi", "Lo-Fi"),
+    ("synthpop", "Synthpop"),
+    ("postrock", "Post-Rock"),
+    ("postpunk", "Post-Punk"),
+    ("altrock", "Alternative Rock"),
+    ("alternativerock", "Alternative Rock"),
+    ("electronica", "Electronic"),
+    ("edm", "EDM"),
+    ("idm", "IDM"),
+    ("kpop", "K-Pop"),
+    ("jpop", "J-Pop"),
+    ("ost", "Soundtrack"),
+];
+
+fn alias_key(name: &str) -> String {
+    name.to_lowercase()
+        .replace('&', "and")
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+fn title_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut start = true;
+    for c in name.chars() {
+        if start {
+            out.extend(c.to_uppercase());
+        } else {
+            out.extend(c.to_lowercase());
+        }
+        start = c.is_whitespace() || c == '-' || c == '/';
+    }
+    out
+}
+
+pub fn normalize_genre(name: &str) -> String {
+    let name = name.trim();
+    let key = alias_key(name);
+    if let Some((_, canonical)) = ALIASES.iter().find(|(alias, _)| *alias == key) {
+        return canonical.to_string();
+    }
+    let lower = !name.chars().any(char::is_uppercase);
+    let upper = !name.chars().any(char::is_lowercase);
+    if lower || (upper && key.len() > 3) {
+        title_case(name)
+    } else {
+        name.to_string()
+    }
+}
+
+pub(crate) fn normalize_genres(values: Vec<String>) -> Vec<String> {
+    let mut genres: Vec<String> = Vec::new();
+    for genre in values.iter().map(|v| normalize_genre(v)) {
+        if !genres.iter().any(|g| g.eq_ignore_ascii_case(&genre)) {
+            genres.push(genre);
+        }
+    }
+    genres
+}
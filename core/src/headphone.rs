@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+use thiserror::Error;
+
+use crate::{
+    ConfigError,
+    equalizer::{EqBand, EqParams, FilterKind},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeadphoneProfile {
+    pub name: String,
+    pub preamp_db: f32,
+    pub filters: Vec<EqBand>,
+}
+
+impl HeadphoneProfile {
+    pub fn import(path: &Path) -> Result<Self, HeadphoneError> {
+        let contents = fs::read_to_string(path)?;
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| s.trim_end_matches("ParametricEQ").trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "Headphones".to_string());
+
+        Self::parse(name, &contents)
+    }
+
+    pub fn parse(name: impl Into<String>, contents: &str) -> Result<Self, HeadphoneError> {
+        let mut preamp_db = 0.0;
+        let mut filters = Vec::new();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |error| HeadphoneError::InvalidLine(index + 1, line.to_string(), error);
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+
+            if key.trim().eq_ignore_ascii_case("preamp") {
+                preamp_db = parse_value("Preamp", value.split_whitespace()).map_err(invalid)?;
+            } else if key.trim_start().starts_with("Filter")
+                && let Some(band) = parse_filter(value).map_err(invalid)?
+            {
+                filters.push(band);
+            }
+        }
+
+        if filters.is_empty() {
+            return Err(HeadphoneError::NoFilters);
+        }
+
+        Ok(HeadphoneProfile {
+            name: name.into(),
+            preamp_db,
+            filters,
+        })
+    }
+
+    pub(crate) fn params(&self) -> EqParams {
+        EqParams::new(self.preamp_db, self.filters.iter().copied())
+    }
+}
+
+fn parse_value<'a>(
+    name: &str,
+    mut tokens: impl Iterator<Item = &'a str>,
+) -> Result<f32, ProfileLineError> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| ProfileLineError::MissingValue(name.to_string()))?;
+    token
+        .parse()
+        .map_err(|_| ProfileLineError::InvalidValue(name.to_string(), token.to_string()))
+}
+
+fn parse_filter(value: &str) -> Result<Option<EqBand>, ProfileLineError> {
+    let mut tokens = value.split_whitespace();
+
+    match tokens.next() {
+        Some(state) if state.eq_ignore_ascii_case("on") => {}
+        Some(_) => return Ok(None),
+        None => return Err(ProfileLineError::MissingState),
+    }
+
+    let kind = tokens.next().ok_or(ProfileLineError::MissingType)?;
+    let kind = match kind.to_ascii_uppercase().as_str() {
+        "PK" | "PEQ" => FilterKind::Peaking,
+        "LS" | "LSC" => FilterKind::LowShelf,
+        "HS" | "HSC" => FilterKind::HighShelf,
+        "LP" | "LPQ" => FilterKind::LowPass,
+        "HP" | "HPQ" => FilterKind::HighPass,
+        _ => return Err(ProfileLineError::UnsupportedType(kind.to_string())),
+    };
+
+    let mut band = EqBand::new(kind, 0.0);
+
+    while let Some(token) = tokens.next() {
+        match token.to_ascii_lowercase().as_str() {
+            "fc" => band.frequency = parse_value("Fc", &mut tokens)?,
+            "gain" => band.gain_db = parse_value("Gain", &mut tokens)?,
+            "q" => band.q = parse_value("Q", &mut tokens)?,
+            _ => {}
+        }
+    }
+
+    if band.frequency <= 0.0 {
+        return Err(ProfileLineError::MissingFrequency);
+    }
+    Ok(Some(band))
+}
+
+#[derive(Debug, Error)]
+pub enum HeadphoneError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid profile line {0} \"{1}\": {2}")]
+    InvalidLine(usize, String, ProfileLineError),
+    #[error("Profile contains no filters")]
+    NoFilters,
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ProfileLineError {
+    #[error("missing filter state")]
+    MissingState,
+    #[error("missing filter type")]
+    MissingType,
+    #[error("unsupported filter type {0}")]
+    UnsupportedType(String),
+    #[error("missing value for {0}")]
+    MissingValue(String),
+    #[error("invalid value for {0}: {1}")]
+    InvalidValue(String, String),
+    #[error("filter has no frequency")]
+    MissingFrequency,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTOEQ: &str = "Preamp: -6.2 dB
+Filter 1: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70
+Filter 2: ON PK Fc 2200 Hz Gain -3.1 dB Q 1.41
+Filter 3: OFF PK Fc 4000 Hz Gain 2.0 dB Q 2.00
+Filter 4: ON HSC Fc 10000 Hz Gain -2.0 dB Q 0.70
+";
+
+    fn line_error(contents: &str) -> (usize, ProfileLineError) {
+        match HeadphoneProfile::parse("Test", contents) {
+            Err(HeadphoneError::InvalidLine(line, _, error)) => (line, error),
+            other => panic!("expected an invalid line, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_enabled_filters_and_preamp() {
+        let profile = HeadphoneProfile::parse("Test", AUTOEQ).unwrap();
+
+        assert_eq!(profile.preamp_db, -6.2);
+        assert_eq!(
+            profile.filters,
+            [
+                EqBand {
+                    kind: FilterKind::LowShelf,
+                    frequency: 105.0,
+                    gain_db: 5.5,
+                    q: 0.7,
+                },
+                EqBand {
+                    kind: FilterKind::Peaking,
+                    frequency: 2200.0,
+                    gain_db: -3.1,
+                    q: 1.41,
+                },
+                EqBand {
+                    kind: FilterKind::HighShelf,
+                    frequency: 10000.0,
+                    gain_db: -2.0,
+                    q: 0.7,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_unkeyed_lines() {
+        let profile = HeadphoneProfile::parse(
+            "Test",
+            "# AutoEQ\nDevice: Headphones\nFilter: ON LP Fc 18000 Hz\n",
+        )
+        .unwrap();
+
+        assert_eq!(profile.preamp_db, 0.0);
+        assert_eq!(profile.filters, [EqBand::new(FilterKind::LowPass, 18000.0)]);
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        assert_eq!(
+            line_error("Preamp: loud\n"),
+            (
+                1,
+                ProfileLineError::InvalidValue("Preamp".to_string(), "loud".to_string())
+            )
+        );
+        assert_eq!(
+            line_error(AUTOEQ.replace("Gain -3.1", "Gain").as_str()),
+            (
+                3,
+                ProfileLineError::InvalidValue("Gain".to_string(), "dB".to_string())
+            )
+        );
+        assert_eq!(
+            line_error("Filter 1: ON PK Fc\n"),
+            (1, ProfileLineError::MissingValue("Fc".to_string()))
+        );
+        assert_eq!(
+            line_error("Filter 1: ON PK Gain 3 dB\n"),
+            (1, ProfileLineError::MissingFrequency)
+        );
+        assert_eq!(
+            line_error("Filter 1: ON\n"),
+            (1, ProfileLineError::MissingType)
+        );
+        assert_eq!(
+            line_error("Filter 1:\n"),
+            (1, ProfileLineError::MissingState)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_filter_types() {
+        let contents = AUTOEQ.replace("ON PK", "ON BP");
+        assert_eq!(
+            line_error(&contents),
+            (3, ProfileLineError::UnsupportedType("BP".to_string()))
+        );
+
+        let err = HeadphoneProfile::parse("Test", &contents).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid profile line 3 \"Filter 2: ON BP Fc 2200 Hz Gain -3.1 dB Q 1.41\": \
+             unsupported filter type BP"
+        );
+    }
+
+    #[test]
+    fn rejects_profiles_without_enabled_filters() {
+        assert!(matches!(
+            HeadphoneProfile::parse("Test", "Preamp: -1 dB\nFilter 1: OFF PK Fc 100 Hz\n"),
+            Err(HeadphoneError::NoFilters)
+        ));
+    }
+}
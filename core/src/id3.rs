@@ -0,0 +1,39 @@
+use std::fs::File;
+use std::path::Path;
+
+use lofty::{
+    config::ParseOptions,
+    file::AudioFile,
+    id3::v2::Id3v2Tag,
+    iff::{aiff::AiffFile, wav::WavFile},
+    mpeg::MpegFile,
+};
+
+use crate::AudioFormat;
+
+pub(crate) fn read(path: &Path) -> Option<Id3v2Tag> {
+    let format = AudioFormat::from_path(path)?;
+    if !matches!(
+        format,
+        AudioFormat::Mp3 | AudioFormat::Wav | AudioFormat::Aiff
+    ) {
+        return None;
+    }
+
+    let mut file = File::open(path).ok()?;
+    let options = ParseOptions::new()
+        .read_properties(false)
+        .read_cover_art(false);
+    match format {
+        AudioFormat::Mp3 => MpegFile::read_from(&mut file, options).ok()?.remove_id3v2(),
+        AudioFormat::Wav => WavFile::read_from(&mut file, options).ok()?.remove_id3v2(),
+        _ => AiffFile::read_from(&mut file, options).ok()?.remove_id3v2(),
+    }
+}
+
+pub(crate) fn user_text(tag: &Id3v2Tag, description: &str) -> Option<String> {
+    tag.get_user_text(description)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
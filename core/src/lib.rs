@@ -1,24 +1,65 @@
 mod album;
+mod artist;
 mod audio_analyzer;
 mod backend;
+mod biquad;
+mod chapter;
 mod config;
+mod cue;
 mod db;
+mod decade;
+mod device_watcher;
+mod equalizer;
+mod folder;
+mod format;
+mod genre;
+mod headphone;
+mod id3;
 mod library;
+mod library_service;
+mod loop_region;
 mod media;
+mod output;
+mod pitch_shifter;
 mod player;
 mod playlist;
 mod queue;
+mod resampler;
+mod sleep;
+mod tags;
+mod tempo;
 mod track;
+mod work;
 
 pub use album::Album;
+pub use artist::{Artist, ArtistCredit, ArtistRole};
 pub use audio_analyzer::VisData;
-pub use config::{Config, ConfigError};
+pub use backend::{Backend, PlaybackError};
+pub use chapter::Chapter;
+pub use config::{Config, ConfigError, HOME_VAR, LaunchOptions, Paths, VARIOUS_ARTISTS};
+pub use decade::Decade;
+pub use equalizer::{
+    EqBand, EqPreset, FilterKind, GRAPHIC_BANDS, GRAPHIC_FREQUENCIES, MAX_GAIN_DB,
+    MAX_PARAMETRIC_BANDS,
+};
+pub use folder::Folder;
+pub use format::AudioFormat;
+pub use genre::{Genre, normalize_genre};
+pub use headphone::{HeadphoneError, HeadphoneProfile, ProfileLineError};
 pub use library::{Library, LibraryError};
+pub use library_service::LibraryService;
+pub use loop_region::{LoopRegion, MIN_LOOP_LENGTH};
 pub use media::extract_artwork_bytes;
-pub use player::{Player, PlayerError};
+pub use output::{AudioSettings, BUFFER_SIZES, HeadlessOutput, OutputError, SAMPLE_RATES};
+pub use player::{Player, PlayerBuilder, PlayerError};
 pub use playlist::Playlist;
 pub use queue::LoopMode;
-pub use track::Track;
+pub use resampler::ResamplerQuality;
+pub use sleep::StopAfter;
+pub use tags::TagSeparators;
+pub use tempo::{MAX_SEMITONES, MAX_SPEED, MIN_SPEED, Tempo, TempoScope};
+pub use track::{TagEdit, Track, TrackError};
+pub use work::Work;
 
 pub(crate) use db::Database;
 pub(crate) use queue::Queue;
@@ -1,71 +1,164 @@
 use rayon::prelude::*;
-use std::{fs::create_dir_all, path::Path};
+use std::{
+    fs::{self, create_dir_all},
+    io,
+    path::{MAIN_SEPARATOR, Path, PathBuf},
+};
 use thiserror::Error;
 use walkdir::WalkDir;
 
-use crate::{Album, Config, ConfigError, Database, Playlist, Track};
+use crate::{
+    Album, Artist, ArtistRole, AudioFormat, ConfigError, Database, Decade, Folder, Genre, Paths,
+    Playlist, TagEdit, TagSeparators, Tempo, Track, TrackError, VARIOUS_ARTISTS, Work, folder,
+    normalize_genre,
+};
 
 pub struct Library {
     db: Database,
+    separators: TagSeparators,
+    various_artists: String,
 }
 
 impl Library {
     pub fn new() -> Result<Self, LibraryError> {
-        let db_path = Config::database_path()?;
+        Self::open(&Paths::resolve()?.database())
+    }
+
+    pub fn open(db_path: &Path) -> Result<Self, LibraryError> {
+        Self::open_with_separators(db_path, TagSeparators::default())
+    }
 
+    pub fn open_with_separators(
+        db_path: &Path,
+        separators: TagSeparators,
+    ) -> Result<Self, LibraryError> {
         if let Some(parent) = db_path.parent() {
             create_dir_all(parent)?;
         }
 
         Ok(Library {
-            db: Database::new(&db_path)?,
+            db: Database::new(db_path, &separators)?,
+            separators,
+            various_artists: VARIOUS_ARTISTS.to_string(),
         })
     }
 
-    pub fn populate(&mut self, root: &Path) -> Result<(), LibraryError> {
-        Config::set_root(root.to_path_buf())?;
-        self.reload()
+    pub fn set_tag_separators(&mut self, separators: TagSeparators) {
+        self.separators = separators;
+    }
+
+    pub fn set_various_artists(&mut self, label: &str) {
+        self.various_artists = label.to_string();
     }
 
-    pub fn reload(&mut self) -> Result<(), LibraryError> {
-        if let Some(root) = Config::root() {
-            self.db.mark_all_missing()?;
-            Self::scan_directory(&self.db, &root)?;
-            Ok(())
-        } else {
-            Err(LibraryError::RootNotSet)
+    fn label_compilations(&self, mut albums: Vec<Album>) -> Vec<Album> {
+        for album in albums
+            .iter_mut()
+            .filter(|a| a.compilation && a.artist.is_none())
+        {
+            album.artist = Some(self.various_artists.clone());
         }
+        albums
     }
 
-    fn scan_directory(db: &Database, root: &Path) -> Result<(), LibraryError> {
-        const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "ogg", "m4a", "aac"];
+    pub fn scan(&mut self, root: &Path) -> Result<(), LibraryError> {
+        self.db.mark_all_missing()?;
+        Self::scan_directory(&self.db, root, &self.separators)
+    }
 
+    fn scan_directory(
+        db: &Database,
+        root: &Path,
+        separators: &TagSeparators,
+    ) -> Result<(), LibraryError> {
         let audio_files: Vec<_> = WalkDir::new(root)
             .follow_links(true)
             .into_iter()
             .flatten()
-            .filter(|e| {
-                e.file_type().is_file()
-                    && e.path()
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .map(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-                        .unwrap_or(false)
-            })
+            .filter(|e| e.file_type().is_file() && AudioFormat::from_path(e.path()).is_some())
             .map(|e| e.into_path())
             .collect();
 
         let tracks: Vec<Track> = audio_files
             .par_iter()
-            .filter_map(|path| Track::from_path(path).ok())
+            .filter_map(|path| Track::read_all(path, separators).ok())
+            .flatten()
             .collect();
 
-        db.batch_upsert_tracks(&tracks)?;
-        db.batch_upsert_albums_from_tracks(&tracks)?;
+        Self::store_tracks(db, &tracks)?;
+        Self::prune_orphans(db)
+    }
+
+    fn store_tracks(db: &Database, tracks: &[Track]) -> Result<(), LibraryError> {
+        db.batch_upsert_tracks(tracks)?;
+        db.batch_upsert_albums_from_tracks(tracks)?;
+        db.batch_upsert_artists_from_tracks(tracks)?;
+        db.batch_upsert_genres_from_tracks(tracks)?;
+        db.batch_upsert_chapters_from_tracks(tracks)?;
+        Ok(())
+    }
 
+    fn prune_orphans(db: &Database) -> Result<(), LibraryError> {
+        db.delete_orphan_albums()?;
+        db.delete_orphan_artists()?;
+        db.delete_orphan_genres()?;
         Ok(())
     }
 
+    pub fn edit_tags(&mut self, track_ids: &[i64], edit: &TagEdit) -> Result<(), LibraryError> {
+        if edit.is_empty() {
+            return Ok(());
+        }
+
+        let mut paths: Vec<PathBuf> = Vec::with_capacity(track_ids.len());
+        for &id in track_ids {
+            let Some(track) = self.db.get_track_by_id(id)? else {
+                continue;
+            };
+            if track.is_virtual() {
+                return Err(LibraryError::VirtualTrackEdit);
+            }
+            if !paths.iter().any(|path| path == track.path()) {
+                paths.push(track.path().to_path_buf());
+            }
+        }
+
+        let mut tracks = Vec::with_capacity(paths.len());
+        for path in &paths {
+            edit.write(path)?;
+            tracks.extend(Track::read_all(path, &self.separators)?);
+        }
+
+        Self::store_tracks(&self.db, &tracks)?;
+        Self::prune_orphans(&self.db)
+    }
+
+    pub fn delete_tracks(&mut self, track_ids: &[i64]) -> Result<(), LibraryError> {
+        let mut paths: Vec<PathBuf> = Vec::with_capacity(track_ids.len());
+        for &id in track_ids {
+            let Some(track) = self.db.get_track_by_id(id)? else {
+                continue;
+            };
+            let siblings = self
+                .db
+                .get_track_ids_by_path(&track.path().to_string_lossy())?;
+            if !siblings.iter().all(|id| track_ids.contains(id)) {
+                return Err(LibraryError::PartialVirtualDelete);
+            }
+            if !paths.iter().any(|path| path == track.path()) {
+                paths.push(track.path().to_path_buf());
+            }
+        }
+        for path in &paths {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        self.db.delete_tracks(track_ids)?;
+        Self::prune_orphans(&self.db)
+    }
+
     pub fn query_track_from_id(&self, id: i64) -> Result<Option<Track>, LibraryError> {
         Ok(self.db.get_track_by_id(id)?)
     }
@@ -86,16 +179,86 @@ impl Library {
         Ok(self.db.get_tracks_by_album(album_name, artist)?)
     }
 
+    pub fn query_tracks_by_album_id(&self, album_id: i64) -> Result<Vec<Track>, LibraryError> {
+        Ok(self.db.get_tracks_by_album_id(album_id)?)
+    }
+
     pub fn query_tracks_by_artist(&self, artist_name: &str) -> Result<Vec<Track>, LibraryError> {
         Ok(self.db.get_tracks_by_artist(artist_name)?)
     }
 
+    pub fn query_tracks_by_artist_id(&self, artist_id: i64) -> Result<Vec<Track>, LibraryError> {
+        Ok(self.db.get_tracks_by_artist_id(artist_id)?)
+    }
+
+    pub fn query_tracks_by_genre(&self, genre: &str) -> Result<Vec<Track>, LibraryError> {
+        Ok(self.db.get_tracks_by_genre(&normalize_genre(genre))?)
+    }
+
     pub fn query_track_count(&self) -> Result<i64, LibraryError> {
         Ok(self.db.count_tracks()?)
     }
 
     pub fn query_all_albums(&self) -> Result<Vec<Album>, LibraryError> {
-        Ok(self.db.get_all_albums()?)
+        Ok(self.label_compilations(self.db.get_all_albums()?))
+    }
+
+    pub fn query_albums_by_artist(&self, artist_id: i64) -> Result<Vec<Album>, LibraryError> {
+        Ok(self.label_compilations(self.db.get_albums_by_artist(artist_id)?))
+    }
+
+    pub fn query_all_artists(&self) -> Result<Vec<Artist>, LibraryError> {
+        Ok(self.db.get_all_artists()?)
+    }
+
+    pub fn query_all_genres(&self) -> Result<Vec<Genre>, LibraryError> {
+        Ok(self.db.get_all_genres()?)
+    }
+
+    pub fn query_decades(&self) -> Result<Vec<Decade>, LibraryError> {
+        Ok(self.db.get_decades()?)
+    }
+
+    pub fn query_albums_by_genre(&self, genre_id: i64) -> Result<Vec<Album>, LibraryError> {
+        Ok(self.label_compilations(self.db.get_albums_by_genre(genre_id)?))
+    }
+
+    pub fn query_albums_by_decade(&self, start: u32) -> Result<Vec<Album>, LibraryError> {
+        Ok(self.label_compilations(self.db.get_albums_by_decade(start)?))
+    }
+
+    pub fn query_tracks_by_decade(&self, start: u32) -> Result<Vec<Track>, LibraryError> {
+        Ok(self.db.get_tracks_by_decade(start)?)
+    }
+
+    pub fn query_folder_tree(&self, roots: &[&Path]) -> Result<Vec<Folder>, LibraryError> {
+        Ok(folder::build_tree(roots, self.db.get_track_locations()?))
+    }
+
+    pub fn query_tracks_in_folder(&self, dir: &Path) -> Result<Vec<Track>, LibraryError> {
+        let mut prefix = dir.to_string_lossy().into_owned();
+        if !prefix.ends_with(MAIN_SEPARATOR) {
+            prefix.push(MAIN_SEPARATOR);
+        }
+        let mut tracks = self.db.get_tracks_in_folder(&prefix)?;
+        tracks.sort_by(|a, b| a.path().cmp(b.path()));
+        Ok(tracks)
+    }
+
+    pub fn query_composers(&self) -> Result<Vec<Artist>, LibraryError> {
+        Ok(self.db.get_artists_by_role(ArtistRole::Composer)?)
+    }
+
+    pub fn query_works_by_composer(&self, composer_id: i64) -> Result<Vec<Work>, LibraryError> {
+        Ok(self.db.get_works_by_composer(composer_id)?)
+    }
+
+    pub fn query_tracks_by_work(
+        &self,
+        composer_id: i64,
+        work: &str,
+    ) -> Result<Vec<Track>, LibraryError> {
+        Ok(self.db.get_tracks_by_work(composer_id, work)?)
     }
 
     pub fn query_track_from_path(&self, path: &str) -> Result<Option<Track>, LibraryError> {
@@ -146,6 +309,18 @@ impl Library {
         Ok(self.db.set_playlist_cover(playlist_id, track_id)?)
     }
 
+    pub fn query_tempo(&self, track_id: i64) -> Result<Option<Tempo>, LibraryError> {
+        Ok(self.db.get_tempo_for_track(track_id)?)
+    }
+
+    pub fn set_track_tempo(&self, track_id: i64, tempo: Option<Tempo>) -> Result<(), LibraryError> {
+        Ok(self.db.set_track_tempo(track_id, tempo)?)
+    }
+
+    pub fn set_genre_tempo(&self, genre: &str, tempo: Option<Tempo>) -> Result<(), LibraryError> {
+        Ok(self.db.set_genre_tempo(genre, tempo)?)
+    }
+
     pub fn clear_library(&self) -> Result<(), LibraryError> {
         Ok(self.db.clear_library()?)
     }
@@ -161,4 +336,12 @@ pub enum LibraryError {
     Config(#[from] ConfigError),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("Track error: {0}")]
+    Track(#[from] TrackError),
+    #[error("Library service stopped")]
+    ServiceStopped,
+    #[error("Tracks split from a CUE sheet cannot be edited")]
+    VirtualTrackEdit,
+    #[error("Tracks split from a CUE sheet can only be deleted together")]
+    PartialVirtualDelete,
 }
@@ -0,0 +1,130 @@
+use futures_channel::oneshot;
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use crate::{
+    Album, Artist, Decade, Genre, Library, LibraryError, Playlist, TagEdit, TagSeparators, Track,
+};
+
+type Job = Box<dyn FnOnce(&mut Library) + Send>;
+
+#[derive(Clone)]
+pub struct LibraryService {
+    sender: crossbeam::channel::Sender<Job>,
+}
+
+impl LibraryService {
+    pub fn spawn(db_path: &Path) -> Result<Self, LibraryError> {
+        Self::with_library(Library::open(db_path)?)
+    }
+
+    pub fn with_library(mut library: Library) -> Result<Self, LibraryError> {
+        let (sender, jobs) = crossbeam::channel::unbounded::<Job>();
+
+        thread::Builder::new()
+            .name("verse-library".into())
+            .spawn(move || {
+                for job in jobs {
+                    job(&mut library);
+                }
+            })?;
+
+        Ok(LibraryService { sender })
+    }
+
+    pub fn run<T, F>(
+        &self,
+        f: F,
+    ) -> impl Future<Output = Result<T, LibraryError>> + Send + use<T, F>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Library) -> Result<T, LibraryError> + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        let sent = self.sender.send(Box::new(move |library: &mut Library| {
+            let _ = reply.send(f(library));
+        }));
+
+        async move {
+            sent.map_err(|_| LibraryError::ServiceStopped)?;
+            result.await.map_err(|_| LibraryError::ServiceStopped)?
+        }
+    }
+
+    pub fn set_tag_separators(&self, separators: TagSeparators) {
+        let _ = self.sender.send(Box::new(move |library: &mut Library| {
+            library.set_tag_separators(separators);
+        }));
+    }
+
+    pub fn set_various_artists(&self, label: String) {
+        let _ = self.sender.send(Box::new(move |library: &mut Library| {
+            library.set_various_artists(&label);
+        }));
+    }
+
+    pub fn all_tracks(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Track>, LibraryError>> + Send + use<> {
+        self.run(|library| library.query_all_tracks())
+    }
+
+    pub fn all_albums(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Album>, LibraryError>> + Send + use<> {
+        self.run(|library| library.query_all_albums())
+    }
+
+    pub fn all_artists(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Artist>, LibraryError>> + Send + use<> {
+        self.run(|library| library.query_all_artists())
+    }
+
+    pub fn composers(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Artist>, LibraryError>> + Send + use<> {
+        self.run(|library| library.query_composers())
+    }
+
+    pub fn genres(&self) -> impl Future<Output = Result<Vec<Genre>, LibraryError>> + Send + use<> {
+        self.run(|library| library.query_all_genres())
+    }
+
+    pub fn decades(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Decade>, LibraryError>> + Send + use<> {
+        self.run(|library| library.query_decades())
+    }
+
+    pub fn all_playlists(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Playlist>, LibraryError>> + Send + use<> {
+        self.run(|library| library.get_all_playlists())
+    }
+
+    pub fn scan(
+        &self,
+        root: PathBuf,
+    ) -> impl Future<Output = Result<(), LibraryError>> + Send + use<> {
+        self.run(move |library| library.scan(&root))
+    }
+
+    pub fn edit_tags(
+        &self,
+        track_ids: Vec<i64>,
+        edit: TagEdit,
+    ) -> impl Future<Output = Result<(), LibraryError>> + Send + use<> {
+        self.run(move |library| library.edit_tags(&track_ids, &edit))
+    }
+
+    pub fn delete_tracks(
+        &self,
+        track_ids: Vec<i64>,
+    ) -> impl Future<Output = Result<(), LibraryError>> + Send + use<> {
+        self.run(move |library| library.delete_tracks(&track_ids))
+    }
+}
@@ -0,0 +1,34 @@
+pub const MIN_LOOP_LENGTH: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopRegion {
+    pub start: f64,
+    pub end: f64,
+    pub speed_step: f64,
+}
+
+impl LoopRegion {
+    pub fn new(a: f64, b: f64) -> Self {
+        let start = a.min(b).max(0.0);
+        let end = a.max(b).max(start + MIN_LOOP_LENGTH);
+
+        LoopRegion {
+            start,
+            end,
+            speed_step: 0.0,
+        }
+    }
+
+    pub fn with_speed_step(mut self, speed_step: f64) -> Self {
+        self.speed_step = speed_step.max(0.0);
+        self
+    }
+
+    pub fn length(&self) -> f64 {
+        self.end - self.start
+    }
+
+    pub(crate) fn wrapped(&self, previous: f64, position: f64) -> bool {
+        previous > position && previous - position >= self.length() / 2.0
+    }
+}
@@ -0,0 +1,273 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{BufferSize, Device, SampleRate, Stream, StreamConfig, StreamError};
+use crossbeam::channel::{Receiver, Sender, unbounded};
+use kira::{
+    Frame,
+    backend::{Backend, Renderer},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use thiserror::Error;
+
+use crate::resampler::{Resampler, ResamplerQuality};
+
+pub const BUFFER_SIZES: [u32; 5] = [256, 512, 1024, 2048, 4096];
+pub const SAMPLE_RATES: [u32; 5] = [44100, 48000, 88200, 96000, 192000];
+
+const HEADLESS_SAMPLE_RATE: u32 = 48000;
+const HEADLESS_BLOCK_FRAMES: usize = 512;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioSettings {
+    #[serde(default)]
+    pub buffer_size: Option<u32>,
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    #[serde(default)]
+    pub resampler: ResamplerQuality,
+}
+
+#[derive(Clone)]
+pub struct HeadlessOutput {
+    renderer: Arc<Mutex<Option<Renderer>>>,
+    sample_rate: u32,
+}
+
+impl HeadlessOutput {
+    pub(crate) fn new(sample_rate: Option<u32>) -> Self {
+        HeadlessOutput {
+            renderer: Arc::new(Mutex::new(None)),
+            sample_rate: sample_rate.unwrap_or(HEADLESS_SAMPLE_RATE),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn render(&self, duration: Duration) -> Vec<Frame> {
+        let frames = (duration.as_secs_f64() * self.sample_rate as f64).round() as usize;
+        self.render_frames(frames)
+    }
+
+    pub fn render_frames(&self, frames: usize) -> Vec<Frame> {
+        let mut output = Vec::with_capacity(frames);
+        let mut renderer = self.renderer.lock().unwrap();
+        let Some(renderer) = renderer.as_mut() else {
+            output.resize(frames, Frame::ZERO);
+            return output;
+        };
+
+        let mut block = [0.0; HEADLESS_BLOCK_FRAMES * 2];
+        while output.len() < frames {
+            let len = (frames - output.len()).min(HEADLESS_BLOCK_FRAMES);
+            let block = &mut block[..len * 2];
+            renderer.on_start_processing();
+            renderer.process(block, 2);
+            output.extend(block.chunks_exact(2).map(|s| Frame::new(s[0], s[1])));
+        }
+        output
+    }
+
+    pub fn write_wav(&self, path: &Path, frames: &[Frame]) -> io::Result<()> {
+        let data_len = (frames.len() * 4) as u32;
+        let mut file = BufWriter::new(File::create(path)?);
+
+        file.write_all(b"RIFF")?;
+        file.write_all(&(36 + data_len).to_le_bytes())?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?;
+        file.write_all(&2u16.to_le_bytes())?;
+        file.write_all(&self.sample_rate.to_le_bytes())?;
+        file.write_all(&(self.sample_rate * 4).to_le_bytes())?;
+        file.write_all(&4u16.to_le_bytes())?;
+        file.write_all(&16u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&data_len.to_le_bytes())?;
+
+        for frame in frames {
+            for sample in [frame.left, frame.right] {
+                let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+                file.write_all(&sample.to_le_bytes())?;
+            }
+        }
+        file.flush()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum OutputTarget {
+    Device(Option<Device>),
+    Headless(HeadlessOutput),
+}
+
+pub(crate) struct OutputSettings {
+    pub(crate) target: OutputTarget,
+    pub(crate) audio: AudioSettings,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        OutputSettings {
+            target: OutputTarget::Device(None),
+            audio: AudioSettings::default(),
+        }
+    }
+}
+
+enum Sink {
+    Device {
+        device: Device,
+        config: StreamConfig,
+        stream: Option<Stream>,
+    },
+    Headless(HeadlessOutput),
+}
+
+pub(crate) struct OutputBackend {
+    sink: Sink,
+    render_rate: u32,
+    resampler: ResamplerQuality,
+    error_sender: Sender<StreamError>,
+    errors: Receiver<StreamError>,
+}
+
+impl OutputBackend {
+    pub(crate) fn pop_error(&mut self) -> Option<StreamError> {
+        self.errors.try_recv().ok()
+    }
+}
+
+impl Backend for OutputBackend {
+    type Settings = OutputSettings;
+    type Error = OutputError;
+
+    fn setup(
+        settings: Self::Settings,
+        _internal_buffer_size: usize,
+    ) -> Result<(Self, u32), Self::Error> {
+        let (error_sender, errors) = unbounded();
+
+        let device = match settings.target {
+            OutputTarget::Headless(output) => {
+                let render_rate = output.sample_rate;
+                return Ok((
+                    OutputBackend {
+                        sink: Sink::Headless(output),
+                        render_rate,
+                        resampler: settings.audio.resampler,
+                        error_sender,
+                        errors,
+                    },
+                    render_rate,
+                ));
+            }
+            OutputTarget::Device(Some(device)) => device,
+            OutputTarget::Device(None) => cpal::default_host()
+                .default_output_device()
+                .ok_or(OutputError::NoDevice)?,
+        };
+
+        let default_config = device.default_output_config()?;
+        let channels = default_config.channels();
+        let render_rate = settings
+            .audio
+            .sample_rate
+            .unwrap_or(default_config.sample_rate().0);
+
+        let device_rate = if supports_rate(&device, channels, render_rate) {
+            render_rate
+        } else {
+            default_config.sample_rate().0
+        };
+
+        let config = StreamConfig {
+            channels,
+            sample_rate: SampleRate(device_rate),
+            buffer_size: settings
+                .audio
+                .buffer_size
+                .map_or(BufferSize::Default, BufferSize::Fixed),
+        };
+
+        Ok((
+            OutputBackend {
+                sink: Sink::Device {
+                    device,
+                    config,
+                    stream: None,
+                },
+                render_rate,
+                resampler: settings.audio.resampler,
+                error_sender,
+                errors,
+            },
+            render_rate,
+        ))
+    }
+
+    fn start(&mut self, renderer: Renderer) -> Result<(), Self::Error> {
+        let (device, config, stream) = match &mut self.sink {
+            Sink::Headless(output) => {
+                *output.renderer.lock().unwrap() = Some(renderer);
+                return Ok(());
+            }
+            Sink::Device {
+                device,
+                config,
+                stream,
+            } => (device, config, stream),
+        };
+
+        let channels = config.channels;
+        let mut resampler = Resampler::new(
+            renderer,
+            self.render_rate,
+            config.sample_rate.0,
+            self.resampler,
+        );
+        let error_sender = self.error_sender.clone();
+
+        let output = device.build_output_stream(
+            config,
+            move |data: &mut [f32], _| resampler.fill(data, channels),
+            move |err| {
+                let _ = error_sender.send(err);
+            },
+            None,
+        )?;
+        output.play()?;
+
+        *stream = Some(output);
+        Ok(())
+    }
+}
+
+fn supports_rate(device: &Device, channels: u16, rate: u32) -> bool {
+    device.supported_output_configs().is_ok_and(|mut configs| {
+        configs.any(|c| {
+            c.channels() == channels
+                && c.min_sample_rate().0 <= rate
+                && rate <= c.max_sample_rate().0
+        })
+    })
+}
+
+#[derive(Debug, Error)]
+pub enum OutputError {
+    #[error("No output device available")]
+    NoDevice,
+    #[error("Failed to query output config: {0}")]
+    DefaultConfig(#[from] cpal::DefaultStreamConfigError),
+    #[error("Failed to build output stream: {0}")]
+    BuildStream(#[from] cpal::BuildStreamError),
+    #[error("Failed to start output stream: {0}")]
+    PlayStream(#[from] cpal::PlayStreamError),
+}
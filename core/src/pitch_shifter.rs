@@ -0,0 +1,99 @@
+use crossbeam::atomic::AtomicCell;
+use kira::effect::{Effect, EffectBuilder};
+use kira::{Frame, info::Info};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+const BUFFER_SIZE: usize = 8192;
+const WINDOW_SECONDS: f32 = 0.05;
+const MIX_STEP: f32 = 1.0 / 2048.0;
+
+pub(crate) struct PitchShifterBuilder {
+    ratio: Arc<AtomicCell<f32>>,
+}
+
+impl PitchShifterBuilder {
+    pub(crate) fn new() -> (Self, Arc<AtomicCell<f32>>) {
+        let ratio = Arc::new(AtomicCell::new(1.0));
+        (
+            Self {
+                ratio: ratio.clone(),
+            },
+            ratio,
+        )
+    }
+}
+
+impl EffectBuilder for PitchShifterBuilder {
+    type Handle = ();
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        (Box::new(PitchShifter::new(self.ratio)), ())
+    }
+}
+
+struct PitchShifter {
+    ratio: Arc<AtomicCell<f32>>,
+    buffer: Vec<Frame>,
+    write_pos: usize,
+    phase: f32,
+    mix: f32,
+    sample_rate: f32,
+}
+
+impl PitchShifter {
+    fn new(ratio: Arc<AtomicCell<f32>>) -> Self {
+        Self {
+            ratio,
+            buffer: vec![Frame::ZERO; BUFFER_SIZE],
+            write_pos: 0,
+            phase: 0.0,
+            mix: 0.0,
+            sample_rate: 44100.0,
+        }
+    }
+
+    fn read(&self, delay: f32) -> Frame {
+        let pos = (self.write_pos as f32 - delay).rem_euclid(BUFFER_SIZE as f32);
+        let i = pos as usize % BUFFER_SIZE;
+        let frac = pos - pos.floor();
+        let a = self.buffer[i];
+        let b = self.buffer[(i + 1) % BUFFER_SIZE];
+        a + (b - a) * frac
+    }
+}
+
+impl Effect for PitchShifter {
+    fn process(&mut self, input: &mut [Frame], dt: f64, _info: &Info) {
+        if dt > 0.0 {
+            self.sample_rate = (1.0 / dt) as f32;
+        }
+
+        let ratio = self.ratio.load();
+        let target_mix = if (ratio - 1.0).abs() < 1e-3 { 0.0 } else { 1.0 };
+        let window = (WINDOW_SECONDS * self.sample_rate).min((BUFFER_SIZE / 2) as f32);
+        let step = (1.0 - ratio) / window;
+
+        for frame in input.iter_mut() {
+            self.buffer[self.write_pos] = *frame;
+
+            if self.mix > 0.0 || target_mix > 0.0 {
+                let p1 = self.phase;
+                let p2 = (self.phase + 0.5).fract();
+                let wet = self.read(p1 * window) * (PI * p1).sin()
+                    + self.read(p2 * window) * (PI * p2).sin();
+
+                self.phase = (self.phase + step).rem_euclid(1.0);
+                self.mix = if target_mix > self.mix {
+                    (self.mix + MIX_STEP).min(1.0)
+                } else {
+                    (self.mix - MIX_STEP).max(0.0)
+                };
+
+                *frame = *frame * (1.0 - self.mix) + wet * self.mix;
+            }
+
+            self.write_pos = (self.write_pos + 1) % BUFFER_SIZE;
+        }
+    }
+}
@@ -1,37 +1,104 @@
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use thiserror::Error;
 
-use kira::backend::cpal;
-
 use crate::{
-    Album, Config, ConfigError, Library, LibraryError, Playlist, Queue, Track, VisData,
-    backend::{Backend, BackendState, PlaybackError},
+    Album, Artist, AudioSettings, Chapter, Config, ConfigError, Decade, EqPreset, Folder, Genre,
+    HeadlessOutput, HeadphoneError, HeadphoneProfile, Library, LibraryError, LibraryService,
+    LoopRegion, OutputError, Paths, Playlist, Queue, TagSeparators, Tempo, TempoScope, Track,
+    VisData, Work,
+    backend::{self, Backend, BackendState, PlaybackError},
     queue::LoopMode,
+    sleep::{SleepTimer, StopAfter},
 };
 
+const CHAPTER_RESTART_SECS: f32 = 3.0;
+
 pub struct Player {
+    config: Config,
     backend: Backend,
     library: Library,
+    library_service: LibraryService,
     queue: Queue,
+    stop_after: Option<StopAfter>,
+    sleep_timer: Option<SleepTimer>,
+    chapters: Option<(i64, Vec<Chapter>)>,
 }
 
 impl Player {
     pub fn new() -> Result<Self, PlayerError> {
-        Config::init()?;
+        PlayerBuilder::new().build()
+    }
 
-        Ok(Player {
-            backend: Backend::new()?,
-            library: Library::new()?,
+    pub fn builder() -> PlayerBuilder {
+        PlayerBuilder::new()
+    }
+
+    fn with_parts(
+        config: Config,
+        backend: Backend,
+        library: Library,
+        library_service: LibraryService,
+    ) -> Self {
+        let mut player = Player {
+            config,
+            backend,
+            library,
+            library_service,
             queue: Queue::new(),
-        })
+            stop_after: None,
+            sleep_timer: None,
+            chapters: None,
+        };
+        player.apply_device_eq_preset();
+        player.apply_headphone_profile();
+        player
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn library_service(&self) -> LibraryService {
+        self.library_service.clone()
+    }
+
+    pub fn library_root(&self) -> Option<&Path> {
+        self.config.root()
+    }
+
+    pub fn set_library_root(&mut self, root: &Path) -> Result<(), ConfigError> {
+        self.config.set_root(root.to_path_buf())
+    }
+
+    pub fn set_tag_separators(&mut self, separators: TagSeparators) -> Result<(), ConfigError> {
+        self.library.set_tag_separators(separators.clone());
+        self.library_service.set_tag_separators(separators.clone());
+        self.config.set_tag_separators(separators)
+    }
+
+    pub fn set_various_artists(&mut self, label: Option<&str>) -> Result<(), ConfigError> {
+        self.config.set_various_artists(label)?;
+        let label = self.config.various_artists();
+        self.library.set_various_artists(label);
+        self.library_service.set_various_artists(label.to_string());
+        Ok(())
     }
 
     pub fn populate_library(&mut self, root: &Path) -> Result<(), LibraryError> {
-        self.library.populate(root)
+        self.config.set_root(root.to_path_buf())?;
+        self.library.scan(root)
     }
 
     pub fn reload_library(&mut self) -> Result<(), LibraryError> {
-        self.library.reload()
+        let root = self
+            .config
+            .root()
+            .ok_or(LibraryError::RootNotSet)?
+            .to_path_buf();
+        self.library.scan(&root)
     }
 
     pub fn query_all_tracks(&self) -> Result<Vec<Track>, LibraryError> {
@@ -50,14 +117,75 @@ impl Player {
         self.library.query_tracks_by_album(album_name, artist)
     }
 
+    pub fn query_tracks_by_album_id(&self, album_id: i64) -> Result<Vec<Track>, LibraryError> {
+        self.library.query_tracks_by_album_id(album_id)
+    }
+
     pub fn query_tracks_by_artist(&self, artist_name: &str) -> Result<Vec<Track>, LibraryError> {
         self.library.query_tracks_by_artist(artist_name)
     }
 
+    pub fn query_tracks_by_artist_id(&self, artist_id: i64) -> Result<Vec<Track>, LibraryError> {
+        self.library.query_tracks_by_artist_id(artist_id)
+    }
+
     pub fn query_all_albums(&self) -> Result<Vec<Album>, LibraryError> {
         self.library.query_all_albums()
     }
 
+    pub fn query_albums_by_artist(&self, artist_id: i64) -> Result<Vec<Album>, LibraryError> {
+        self.library.query_albums_by_artist(artist_id)
+    }
+
+    pub fn query_all_artists(&self) -> Result<Vec<Artist>, LibraryError> {
+        self.library.query_all_artists()
+    }
+
+    pub fn query_all_genres(&self) -> Result<Vec<Genre>, LibraryError> {
+        self.library.query_all_genres()
+    }
+
+    pub fn query_decades(&self) -> Result<Vec<Decade>, LibraryError> {
+        self.library.query_decades()
+    }
+
+    pub fn query_albums_by_genre(&self, genre_id: i64) -> Result<Vec<Album>, LibraryError> {
+        self.library.query_albums_by_genre(genre_id)
+    }
+
+    pub fn query_albums_by_decade(&self, start: u32) -> Result<Vec<Album>, LibraryError> {
+        self.library.query_albums_by_decade(start)
+    }
+
+    pub fn query_tracks_by_decade(&self, start: u32) -> Result<Vec<Track>, LibraryError> {
+        self.library.query_tracks_by_decade(start)
+    }
+
+    pub fn query_folder_tree(&self) -> Result<Vec<Folder>, LibraryError> {
+        let roots: Vec<&Path> = self.config.root().into_iter().collect();
+        self.library.query_folder_tree(&roots)
+    }
+
+    pub fn query_tracks_in_folder(&self, dir: &Path) -> Result<Vec<Track>, LibraryError> {
+        self.library.query_tracks_in_folder(dir)
+    }
+
+    pub fn query_composers(&self) -> Result<Vec<Artist>, LibraryError> {
+        self.library.query_composers()
+    }
+
+    pub fn query_works_by_composer(&self, composer_id: i64) -> Result<Vec<Work>, LibraryError> {
+        self.library.query_works_by_composer(composer_id)
+    }
+
+    pub fn query_tracks_by_work(
+        &self,
+        composer_id: i64,
+        work: &str,
+    ) -> Result<Vec<Track>, LibraryError> {
+        self.library.query_tracks_by_work(composer_id, work)
+    }
+
     pub fn query_track_from_id(&self, id: i64) -> Result<Option<Track>, LibraryError> {
         self.library.query_track_from_id(id)
     }
@@ -66,6 +194,10 @@ impl Player {
         self.library.query_track_from_path(path)
     }
 
+    pub fn query_tracks_by_genre(&self, genre: &str) -> Result<Vec<Track>, LibraryError> {
+        self.library.query_tracks_by_genre(genre)
+    }
+
     pub fn query_track_count(&self) -> Result<i64, LibraryError> {
         self.library.query_track_count()
     }
@@ -119,7 +251,7 @@ impl Player {
         self.queue.clear();
         self.backend.stop();
         self.library.clear_library()?;
-        Config::clear_root()?;
+        self.config.clear_root()?;
         Ok(())
     }
 
@@ -140,14 +272,226 @@ impl Player {
         self.backend.seek(pos);
     }
 
+    pub fn chapters(&self) -> &[Chapter] {
+        match &self.chapters {
+            Some((id, chapters)) if self.queue.current_id() == Some(*id) => chapters,
+            _ => &[],
+        }
+    }
+
+    pub fn current_chapter(&self) -> Option<usize> {
+        Chapter::index_at(self.chapters(), self.backend.position() as f32)
+    }
+
+    pub fn next_chapter(&mut self) {
+        let chapters = self.chapters();
+        let next = Chapter::index_at(chapters, self.backend.position() as f32).map_or(0, |i| i + 1);
+        if let Some(start) = chapters.get(next).map(|c| c.start) {
+            self.backend.seek(start as f64);
+        }
+    }
+
+    pub fn previous_chapter(&mut self) {
+        let chapters = self.chapters();
+        let position = self.backend.position() as f32;
+        let Some(current) = Chapter::index_at(chapters, position) else {
+            return;
+        };
+
+        let index = if position - chapters[current].start > CHAPTER_RESTART_SECS {
+            current
+        } else {
+            current.saturating_sub(1)
+        };
+        let start = chapters[index].start;
+        self.backend.seek(start as f64);
+    }
+
+    pub fn jump_to_chapter(&mut self, index: usize) {
+        if let Some(start) = self.chapters().get(index).map(|c| c.start) {
+            self.backend.seek(start as f64);
+        }
+    }
+
     pub fn set_volume(&mut self, vol: f32) {
         self.backend.set_volume(vol);
     }
 
+    pub fn set_tempo(&mut self, tempo: Tempo) {
+        self.backend.set_tempo(tempo);
+    }
+
+    pub fn tempo(&self) -> Tempo {
+        self.backend.tempo()
+    }
+
+    pub fn remember_tempo(&self, scope: TempoScope) -> Result<(), LibraryError> {
+        self.store_tempo(scope, Some(self.backend.tempo()))
+    }
+
+    pub fn forget_tempo(&self, scope: TempoScope) -> Result<(), LibraryError> {
+        self.store_tempo(scope, None)
+    }
+
+    fn store_tempo(&self, scope: TempoScope, tempo: Option<Tempo>) -> Result<(), LibraryError> {
+        let Some(track) = self.clone_current_track() else {
+            return Ok(());
+        };
+
+        match (scope, track.id(), track.genre()) {
+            (TempoScope::Track, Some(id), _) => self.library.set_track_tempo(id, tempo),
+            (TempoScope::Genre, _, Some(genre)) => self.library.set_genre_tempo(genre, tempo),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn eq_presets(&self) -> Vec<EqPreset> {
+        let mut presets = EqPreset::builtin();
+        for preset in self.config.eq_presets() {
+            match presets.iter_mut().find(|p| p.name == preset.name) {
+                Some(existing) => *existing = preset.clone(),
+                None => presets.push(preset.clone()),
+            }
+        }
+        presets
+    }
+
+    pub fn equalizer(&self) -> Option<&EqPreset> {
+        self.backend.equalizer()
+    }
+
+    pub fn set_equalizer(&mut self, preset: Option<EqPreset>) {
+        self.backend.set_equalizer(preset);
+    }
+
+    pub fn select_eq_preset(&mut self, name: Option<&str>) -> Result<(), ConfigError> {
+        let preset = name.and_then(|name| self.eq_presets().into_iter().find(|p| p.name == name));
+        let name = preset.as_ref().map(|p| p.name.clone());
+        self.backend.set_equalizer(preset);
+
+        if let Some(device) = self.backend.output_device() {
+            self.config.set_device_eq_preset(device, name.as_deref())?;
+        }
+        Ok(())
+    }
+
+    pub fn save_eq_preset(&mut self, name: &str) -> Result<(), ConfigError> {
+        let Some(current) = self.backend.equalizer() else {
+            return Ok(());
+        };
+
+        let preset = EqPreset {
+            name: name.to_string(),
+            ..current.clone()
+        };
+        self.config.save_eq_preset(preset)?;
+        self.select_eq_preset(Some(name))
+    }
+
+    pub fn delete_eq_preset(&mut self, name: &str) -> Result<(), ConfigError> {
+        self.config.delete_eq_preset(name)?;
+        if self.backend.equalizer().is_some_and(|p| p.name == name) {
+            self.apply_device_eq_preset();
+        }
+        Ok(())
+    }
+
+    pub fn output_device(&self) -> Option<&str> {
+        self.backend.output_device()
+    }
+
+    pub fn output_devices(&self) -> Vec<String> {
+        backend::output_devices()
+    }
+
+    pub fn selected_output_device(&self) -> Option<String> {
+        self.config.output_device().map(str::to_string)
+    }
+
+    pub fn set_output_device(&mut self, name: Option<&str>) -> Result<(), PlayerError> {
+        self.backend.set_output_device(name)?;
+        self.config.set_output_device(name)?;
+        self.apply_device_eq_preset();
+        Ok(())
+    }
+
+    pub fn audio_settings(&self) -> AudioSettings {
+        self.backend.audio_settings()
+    }
+
+    pub fn set_audio_settings(&mut self, settings: AudioSettings) -> Result<(), PlayerError> {
+        self.backend.set_audio_settings(settings)?;
+        self.config
+            .set_audio_settings(self.backend.audio_settings())?;
+        self.apply_device_eq_preset();
+        Ok(())
+    }
+
+    fn apply_device_eq_preset(&mut self) {
+        let preset = self
+            .backend
+            .output_device()
+            .and_then(|device| self.config.device_eq_preset(device))
+            .and_then(|name| self.eq_presets().into_iter().find(|p| p.name == name));
+        self.backend.set_equalizer(preset);
+    }
+
+    pub fn headphone_profiles(&self) -> &[HeadphoneProfile] {
+        self.config.headphone_profiles()
+    }
+
+    pub fn headphone_profile(&self) -> Option<&HeadphoneProfile> {
+        self.backend.headphone_profile()
+    }
+
+    pub fn import_headphone_profile(&mut self, path: &Path) -> Result<(), HeadphoneError> {
+        let profile = HeadphoneProfile::import(path)?;
+        let name = profile.name.clone();
+        self.config.save_headphone_profile(profile)?;
+        self.select_headphone_profile(Some(&name))?;
+        Ok(())
+    }
+
+    pub fn select_headphone_profile(&mut self, name: Option<&str>) -> Result<(), ConfigError> {
+        self.config.set_headphone_profile(name)?;
+        self.apply_headphone_profile();
+        Ok(())
+    }
+
+    pub fn remove_headphone_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        self.config.remove_headphone_profile(name)?;
+        self.apply_headphone_profile();
+        Ok(())
+    }
+
+    fn apply_headphone_profile(&mut self) {
+        let profile = self.config.headphone_profile().cloned();
+        self.backend.set_headphone_profile(profile);
+    }
+
+    pub fn set_loop_region(&mut self, region: Option<LoopRegion>) {
+        self.backend.set_loop_region(region);
+    }
+
+    pub fn clear_loop_region(&mut self) {
+        self.backend.set_loop_region(None);
+    }
+
+    pub fn loop_region(&self) -> Option<LoopRegion> {
+        self.backend.loop_region()
+    }
+
     pub fn is_playing(&self) -> bool {
         self.backend.is_playing()
     }
 
+    pub fn is_stopped(&self) -> bool {
+        matches!(
+            self.backend.state(),
+            BackendState::Idle | BackendState::Finished
+        )
+    }
+
     pub fn position(&self) -> f64 {
         self.backend.position()
     }
@@ -157,12 +501,105 @@ impl Player {
     }
 
     pub fn update(&mut self) -> Result<(), PlaybackError> {
+        if self.backend.check_device()? {
+            self.apply_device_eq_preset();
+        }
+        self.update_sleep_timer();
+        self.backend.update_loop();
+
         if self.backend.state() == BackendState::Finished {
-            self.start_next()?;
+            if self.take_stop_after() {
+                self.queue.next();
+                self.backend.stop();
+            } else {
+                self.start_next()?;
+            }
         }
         Ok(())
     }
 
+    pub fn set_stop_after(&mut self, stop_after: Option<StopAfter>) {
+        self.stop_after = match stop_after {
+            Some(StopAfter::Tracks(0)) => None,
+            other => other,
+        };
+    }
+
+    pub fn stop_after(&self) -> Option<StopAfter> {
+        self.stop_after
+    }
+
+    pub fn start_sleep_timer(&mut self, duration: Duration) {
+        self.cancel_sleep_timer();
+        self.sleep_timer = Some(SleepTimer::new(duration));
+    }
+
+    pub fn cancel_sleep_timer(&mut self) {
+        if let Some(timer) = self.sleep_timer.take()
+            && timer.is_fading()
+        {
+            self.backend.cancel_fade();
+        }
+    }
+
+    pub fn sleep_remaining(&self) -> Option<Duration> {
+        self.sleep_timer.as_ref().map(SleepTimer::remaining)
+    }
+
+    pub fn has_pending_stop(&self) -> bool {
+        self.stop_after.is_some() || self.sleep_timer.is_some()
+    }
+
+    pub fn cancel_pending_stop(&mut self) {
+        self.stop_after = None;
+        self.cancel_sleep_timer();
+    }
+
+    fn update_sleep_timer(&mut self) {
+        let Some(timer) = &mut self.sleep_timer else {
+            return;
+        };
+
+        if timer.is_expired() {
+            self.sleep_timer = None;
+            self.backend.pause();
+        } else if timer.should_start_fade() {
+            timer.start_fade();
+            self.backend.fade_out(timer.remaining());
+        }
+    }
+
+    fn take_stop_after(&mut self) -> bool {
+        let Some(stop_after) = self.stop_after else {
+            return false;
+        };
+
+        let stop = match stop_after {
+            StopAfter::Album => !self.next_shares_album(),
+            _ => stop_after.advance().is_none(),
+        };
+
+        self.stop_after = if stop { None } else { stop_after.advance() };
+        stop
+    }
+
+    fn next_shares_album(&self) -> bool {
+        let next_id = match self.queue.loop_mode() {
+            LoopMode::Single => self.queue.current_id(),
+            _ => self.queue.upcoming().front().copied(),
+        };
+
+        let album_of = |id: Option<i64>| {
+            id.and_then(|id| self.library.query_track_from_id(id).ok().flatten())
+                .and_then(|t| t.album_id())
+        };
+
+        match album_of(self.queue.current_id()) {
+            Some(current) => album_of(next_id) == Some(current),
+            None => false,
+        }
+    }
+
     pub fn shuffle_queue(&mut self) {
         self.queue.shuffle();
     }
@@ -171,6 +608,21 @@ impl Player {
         self.queue.remove_index(index);
     }
 
+    pub fn remove_many_from_queue(&mut self, indices: &[usize]) {
+        self.queue.remove_indices(indices);
+    }
+
+    pub fn remove_tracks_from_queue(&mut self, track_ids: &[i64]) {
+        if self
+            .queue
+            .current_id()
+            .is_some_and(|id| track_ids.contains(&id))
+        {
+            self.backend.stop();
+        }
+        self.queue.remove_tracks(track_ids);
+    }
+
     pub fn move_to_queue_front(&mut self, index: usize) {
         self.queue.move_front(index);
     }
@@ -251,7 +703,17 @@ impl Player {
     fn play_track(&mut self, track_id: Option<i64>) -> Result<(), PlaybackError> {
         if let Some(id) = track_id {
             if let Ok(Some(track)) = self.library.query_track_from_id(id) {
-                self.backend.load_and_play(track.path())?;
+                let tempo = self.library.query_tempo(id).ok().flatten();
+                self.backend.set_tempo(tempo.unwrap_or_default());
+                self.chapters = Some((id, track.chapters().to_vec()));
+                match track.cue_start() {
+                    Some(start) => self.backend.load_and_play_range(
+                        track.path(),
+                        start as f64,
+                        track.cue_end().map(f64::from),
+                    )?,
+                    None => self.backend.load_and_play(track.path())?,
+                }
             }
         }
         Ok(())
@@ -260,16 +722,85 @@ impl Player {
 
 impl Drop for Player {
     fn drop(&mut self) {
-        let _ = Config::save_current();
+        let _ = self.config.save();
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayerBuilder {
+    config_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+}
+
+impl PlayerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn home(self, dir: impl Into<PathBuf>) -> Self {
+        self.paths(Paths::home(dir))
+    }
+
+    pub fn paths(self, paths: Paths) -> Self {
+        self.config_dir(paths.config_dir).data_dir(paths.data_dir)
+    }
+
+    pub fn config_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config_dir = Some(dir.into());
+        self
+    }
+
+    pub fn data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(dir.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Player, PlayerError> {
+        let (config, library, service) = self.open()?;
+        let backend = Backend::new(config.output_device(), config.audio_settings())?;
+        Ok(Player::with_parts(config, backend, library, service))
+    }
+
+    pub fn build_headless(self) -> Result<(Player, HeadlessOutput), PlayerError> {
+        let (config, library, service) = self.open()?;
+        let (backend, output) = Backend::headless(config.audio_settings())?;
+        Ok((
+            Player::with_parts(config, backend, library, service),
+            output,
+        ))
+    }
+
+    fn open(self) -> Result<(Config, Library, LibraryService), PlayerError> {
+        let paths = match (self.config_dir, self.data_dir) {
+            (Some(config_dir), Some(data_dir)) => Paths::new(config_dir, data_dir),
+            (config_dir, data_dir) => {
+                let defaults = Paths::resolve()?;
+                Paths::new(
+                    config_dir.unwrap_or(defaults.config_dir),
+                    data_dir.unwrap_or(defaults.data_dir),
+                )
+            }
+        };
+
+        let config = Config::load(&paths.config_file())?;
+        let separators = config.tag_separators();
+        let mut library = Library::open_with_separators(&paths.database(), separators.clone())?;
+        library.set_various_artists(config.various_artists());
+        let mut worker = Library::open_with_separators(&paths.database(), separators.clone())?;
+        worker.set_various_artists(config.various_artists());
+        let service = LibraryService::with_library(worker)?;
+        Ok((config, library, service))
     }
 }
 
 #[derive(Debug, Error)]
 pub enum PlayerError {
     #[error("Backend error: {0}")]
-    Backend(#[from] cpal::Error),
+    Backend(#[from] OutputError),
     #[error("Config error: {0}")]
     Config(#[from] ConfigError),
     #[error("Library error: {0}")]
     Library(#[from] LibraryError),
+    #[error("Playback error: {0}")]
+    Playback(#[from] PlaybackError),
 }
@@ -32,7 +32,11 @@ impl Queue {
     }
 
     pub fn add_next(&mut self, track_id: i64) {
-        self.upcoming.push_front(track_id);
+        if self.current.is_none() {
+            self.current = Some(track_id);
+        } else {
+            self.upcoming.push_front(track_id);
+        }
     }
 
     pub fn add(&mut self, track_id: i64) {
@@ -148,9 +152,319 @@ impl Queue {
         self.upcoming.remove(i)
     }
 
+    pub fn remove_indices(&mut self, indices: &[usize]) {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        for &i in indices.iter().rev() {
+            self.upcoming.remove(i);
+        }
+    }
+
+    pub fn remove_tracks(&mut self, track_ids: &[i64]) {
+        self.history.retain(|id| !track_ids.contains(id));
+        self.upcoming.retain(|id| !track_ids.contains(id));
+    }
+
     pub fn move_front(&mut self, i: usize) {
         if let Some(track_id) = self.upcoming.remove(i) {
             self.upcoming.push_front(track_id);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn queue(ids: &[i64]) -> Queue {
+        let mut queue = Queue::new();
+        queue.add_many(ids.iter().copied());
+        queue
+    }
+
+    fn order(queue: &Queue) -> Vec<i64> {
+        queue
+            .history
+            .iter()
+            .copied()
+            .chain(queue.current)
+            .chain(queue.upcoming.iter().copied())
+            .collect()
+    }
+
+    #[test]
+    fn add_sets_current_then_appends() {
+        let mut q = Queue::new();
+        q.add(1);
+        q.add(2);
+        q.add(3);
+
+        assert_eq!(q.current_id(), Some(1));
+        assert_eq!(q.upcoming(), &[2, 3]);
+    }
+
+    #[test]
+    fn add_next_on_empty_queue_sets_current() {
+        let mut q = Queue::new();
+        q.add_next(1);
+
+        assert_eq!(q.current_id(), Some(1));
+        assert!(q.upcoming().is_empty());
+    }
+
+    #[test]
+    fn add_many_next_preserves_order_before_upcoming() {
+        let mut q = queue(&[1, 2, 3]);
+        q.add_many_next([10, 11, 12].into_iter());
+
+        assert_eq!(q.current_id(), Some(1));
+        assert_eq!(q.upcoming(), &[10, 11, 12, 2, 3]);
+    }
+
+    #[test]
+    fn add_many_next_on_empty_queue_takes_first_as_current() {
+        let mut q = Queue::new();
+        q.add_many_next([4, 5, 6].into_iter());
+
+        assert_eq!(q.current_id(), Some(4));
+        assert_eq!(q.upcoming(), &[5, 6]);
+    }
+
+    #[test]
+    fn remove_indices_ignores_duplicates_and_out_of_range() {
+        let mut q = queue(&[1, 2, 3, 4, 5]);
+        q.remove_indices(&[3, 0, 3, 9]);
+
+        assert_eq!(q.current_id(), Some(1));
+        assert_eq!(q.upcoming(), &[3, 4]);
+    }
+
+    #[test]
+    fn remove_tracks_keeps_current() {
+        let mut q = queue(&[1, 2, 3, 2]);
+        q.next();
+        q.remove_tracks(&[1, 2]);
+
+        assert_eq!(q.current_id(), Some(2));
+        assert!(q.history().is_empty());
+        assert_eq!(q.upcoming(), &[3]);
+    }
+
+    #[test]
+    fn next_stops_at_end_without_loop() {
+        let mut q = queue(&[1, 2]);
+
+        assert_eq!(q.next(), Some(2));
+        assert_eq!(q.next(), Some(2));
+        assert_eq!(q.history(), &[1]);
+    }
+
+    #[test]
+    fn next_repeats_in_single_loop() {
+        let mut q = queue(&[1, 2]);
+        q.set_loop_mode(LoopMode::Single);
+
+        assert_eq!(q.next(), Some(1));
+        assert_eq!(q.upcoming(), &[2]);
+    }
+
+    #[test]
+    fn queue_loop_wraps_history_into_upcoming() {
+        let mut q = queue(&[1, 2, 3]);
+        q.set_loop_mode(LoopMode::Queue);
+
+        assert_eq!(q.next(), Some(2));
+        assert_eq!(q.next(), Some(3));
+        assert_eq!(q.next(), Some(1));
+        assert!(q.history().is_empty());
+        assert_eq!(q.upcoming(), &[2, 3]);
+    }
+
+    #[test]
+    fn queue_loop_with_single_track_repeats() {
+        let mut q = queue(&[1]);
+        q.set_loop_mode(LoopMode::Queue);
+
+        assert_eq!(q.next(), Some(1));
+        assert!(q.history().is_empty());
+        assert!(q.upcoming().is_empty());
+    }
+
+    #[test]
+    fn previous_with_empty_history_keeps_current() {
+        let mut q = queue(&[1, 2]);
+
+        assert_eq!(q.previous(), Some(1));
+        assert_eq!(q.upcoming(), &[2]);
+    }
+
+    #[test]
+    fn previous_returns_current_to_upcoming() {
+        let mut q = queue(&[1, 2, 3]);
+        q.next();
+        q.next();
+
+        assert_eq!(q.previous(), Some(2));
+        assert_eq!(q.upcoming(), &[3]);
+        assert_eq!(q.history(), &[1]);
+    }
+
+    #[test]
+    fn previous_on_empty_queue_is_none() {
+        let mut q = Queue::new();
+
+        assert_eq!(q.previous(), None);
+        assert_eq!(q.next(), None);
+    }
+
+    #[test]
+    fn cycle_loop_mode_visits_all_modes() {
+        let mut q = Queue::new();
+
+        assert_eq!(q.cycle_loop_mode(), LoopMode::Queue);
+        assert_eq!(q.cycle_loop_mode(), LoopMode::Single);
+        assert_eq!(q.cycle_loop_mode(), LoopMode::None);
+    }
+
+    #[test]
+    fn remove_and_move_front_ignore_out_of_range() {
+        let mut q = queue(&[1, 2, 3]);
+
+        assert_eq!(q.remove_index(5), None);
+        q.move_front(5);
+        assert_eq!(q.upcoming(), &[2, 3]);
+
+        q.move_front(1);
+        assert_eq!(q.upcoming(), &[3, 2]);
+        assert_eq!(q.remove_index(0), Some(3));
+        assert_eq!(q.upcoming(), &[2]);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Add,
+        AddNext,
+        AddMany(usize),
+        AddManyNext(usize),
+        Next,
+        Previous,
+        Remove(usize),
+        MoveFront(usize),
+        Shuffle,
+        SetLoop(LoopMode),
+        Clear,
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            3 => Just(Op::Add),
+            2 => Just(Op::AddNext),
+            1 => (0..5usize).prop_map(Op::AddMany),
+            1 => (0..5usize).prop_map(Op::AddManyNext),
+            4 => Just(Op::Next),
+            3 => Just(Op::Previous),
+            1 => (0..8usize).prop_map(Op::Remove),
+            1 => (0..8usize).prop_map(Op::MoveFront),
+            1 => Just(Op::Shuffle),
+            1 => prop_oneof![
+                Just(LoopMode::None),
+                Just(LoopMode::Queue),
+                Just(LoopMode::Single),
+            ]
+            .prop_map(Op::SetLoop),
+            1 => Just(Op::Clear),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn no_track_lost_or_duplicated(ops in prop::collection::vec(op(), 0..64)) {
+            let mut q = Queue::new();
+            let mut expected: Vec<i64> = Vec::new();
+            let mut next_id = 0;
+            let mut fresh = |count: usize| {
+                let ids: Vec<i64> = (next_id..next_id + count as i64).collect();
+                next_id += count as i64;
+                ids
+            };
+
+            for op in ops {
+                let before = order(&q);
+                match op {
+                    Op::Add => {
+                        let ids = fresh(1);
+                        q.add(ids[0]);
+                        expected.extend(ids);
+                    }
+                    Op::AddNext => {
+                        let ids = fresh(1);
+                        q.add_next(ids[0]);
+                        expected.extend(ids);
+                    }
+                    Op::AddMany(n) => {
+                        let ids = fresh(n);
+                        q.add_many(ids.iter().copied());
+                        expected.extend(ids);
+                    }
+                    Op::AddManyNext(n) => {
+                        let ids = fresh(n);
+                        q.add_many_next(ids.iter().copied());
+                        expected.extend(ids);
+                    }
+                    Op::Next => {
+                        let current = q.next();
+                        prop_assert_eq!(current, q.current_id());
+                        prop_assert_eq!(order(&q), before);
+                    }
+                    Op::Previous => {
+                        let current = q.previous();
+                        prop_assert_eq!(current, q.current_id());
+                        prop_assert_eq!(order(&q), before);
+                    }
+                    Op::Remove(i) => {
+                        if let Some(id) = q.remove_index(i) {
+                            expected.retain(|&e| e != id);
+                        }
+                    }
+                    Op::MoveFront(i) => q.move_front(i),
+                    Op::Shuffle => {
+                        let current = q.current_id();
+                        let history = q.history().clone();
+                        q.shuffle();
+                        prop_assert_eq!(q.current_id(), current);
+                        prop_assert_eq!(q.history(), &history);
+                    }
+                    Op::SetLoop(mode) => q.set_loop_mode(mode),
+                    Op::Clear => {
+                        q.clear();
+                        expected.clear();
+                    }
+                }
+
+                let mut actual = order(&q);
+                actual.sort_unstable();
+                let mut wanted = expected.clone();
+                wanted.sort_unstable();
+                prop_assert_eq!(actual, wanted);
+
+                if q.current_id().is_none() {
+                    prop_assert!(q.history().is_empty());
+                    prop_assert!(q.upcoming().is_empty());
+                }
+            }
+        }
+
+        #[test]
+        fn queue_loop_visits_every_track(len in 1..20usize, steps in 0..100usize) {
+            let ids: Vec<i64> = (0..len as i64).collect();
+            let mut q = queue(&ids);
+            q.set_loop_mode(LoopMode::Queue);
+
+            for step in 1..=steps {
+                prop_assert_eq!(q.next(), Some((step % len) as i64));
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use kira::{Frame, backend::Renderer};
+use serde::{Deserialize, Serialize};
+use std::{f32::consts::PI, fmt};
+
+const BLOCK_FRAMES: usize = 256;
+const LANCZOS_A: usize = 4;
+const LANCZOS_PHASES: usize = 256;
+const HISTORY: usize = LANCZOS_A - 1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResamplerQuality {
+    Fast,
+    #[default]
+    Balanced,
+    High,
+}
+
+impl ResamplerQuality {
+    pub const ALL: [ResamplerQuality; 3] = [
+        ResamplerQuality::Fast,
+        ResamplerQuality::Balanced,
+        ResamplerQuality::High,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResamplerQuality::Fast => "Fast (linear)",
+            ResamplerQuality::Balanced => "Balanced (cubic)",
+            ResamplerQuality::High => "High (Lanczos)",
+        }
+    }
+}
+
+impl fmt::Display for ResamplerQuality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub(crate) struct Resampler {
+    renderer: Renderer,
+    quality: ResamplerQuality,
+    step: f64,
+    position: f64,
+    history: Vec<Frame>,
+    scratch: Vec<f32>,
+    lanczos: Vec<[f32; 2 * LANCZOS_A]>,
+}
+
+impl Resampler {
+    pub(crate) fn new(
+        renderer: Renderer,
+        render_rate: u32,
+        output_rate: u32,
+        quality: ResamplerQuality,
+    ) -> Self {
+        let mut history = Vec::with_capacity(BLOCK_FRAMES * 4);
+        history.resize(HISTORY, Frame::ZERO);
+
+        Self {
+            renderer,
+            quality,
+            step: render_rate as f64 / output_rate as f64,
+            position: HISTORY as f64,
+            history,
+            scratch: vec![0.0; BLOCK_FRAMES * 2],
+            lanczos: lanczos_table(),
+        }
+    }
+
+    pub(crate) fn fill(&mut self, out: &mut [f32], channels: u16) {
+        self.renderer.on_start_processing();
+
+        if self.step == 1.0 {
+            self.renderer.process(out, channels);
+            return;
+        }
+
+        let channels = channels as usize;
+        for chunk in out.chunks_mut(channels) {
+            let frame = self.next_frame();
+            match chunk {
+                [mono] => *mono = (frame.left + frame.right) * 0.5,
+                [left, right, rest @ ..] => {
+                    *left = frame.left;
+                    *right = frame.right;
+                    rest.fill(0.0);
+                }
+                [] => {}
+            }
+        }
+    }
+
+    fn next_frame(&mut self) -> Frame {
+        while self.history.len() < self.position as usize + LANCZOS_A + 1 {
+            self.render_block();
+        }
+
+        let index = self.position as usize;
+        let frac = (self.position - index as f64) as f32;
+        let h = &self.history;
+        let frame = match self.quality {
+            ResamplerQuality::Fast => h[index] + (h[index + 1] - h[index]) * frac,
+            ResamplerQuality::Balanced => {
+                let (y0, y1, y2, y3) = (h[index - 1], h[index], h[index + 1], h[index + 2]);
+                let c1 = (y2 - y0) * 0.5;
+                let c2 = y0 - y1 * 2.5 + y2 * 2.0 - y3 * 0.5;
+                let c3 = (y3 - y0) * 0.5 + (y1 - y2) * 1.5;
+                ((c3 * frac + c2) * frac + c1) * frac + y1
+            }
+            ResamplerQuality::High => {
+                let phase = ((frac * LANCZOS_PHASES as f32) as usize).min(LANCZOS_PHASES - 1);
+                let weights = &self.lanczos[phase];
+                let start = index + 1 - LANCZOS_A;
+                weights
+                    .iter()
+                    .zip(&h[start..start + 2 * LANCZOS_A])
+                    .fold(Frame::ZERO, |acc, (&w, &f)| acc + f * w)
+            }
+        };
+
+        self.position += self.step;
+        frame
+    }
+
+    fn render_block(&mut self) {
+        let consumed = (self.position as usize).saturating_sub(HISTORY);
+        let kept = self.history.len() - consumed;
+        self.history.copy_within(consumed.., 0);
+        self.history.truncate(kept);
+        self.position -= consumed as f64;
+
+        self.renderer.process(&mut self.scratch, 2);
+        self.history
+            .extend(self.scratch.chunks_exact(2).map(|s| Frame::new(s[0], s[1])));
+    }
+}
+
+fn lanczos_table() -> Vec<[f32; 2 * LANCZOS_A]> {
+    let sinc = |x: f32| {
+        if x.abs() < 1e-6 {
+            1.0
+        } else {
+            (PI * x).sin() / (PI * x)
+        }
+    };
+    let a = LANCZOS_A as f32;
+
+    (0..LANCZOS_PHASES)
+        .map(|phase| {
+            let frac = phase as f32 / LANCZOS_PHASES as f32;
+            let mut weights = [0.0; 2 * LANCZOS_A];
+            for (k, w) in weights.iter_mut().enumerate() {
+                let x = k as f32 - (a - 1.0) - frac;
+                *w = if x.abs() < a {
+                    sinc(x) * sinc(x / a)
+                } else {
+                    0.0
+                };
+            }
+            let sum: f32 = weights.iter().sum();
+            weights.map(|w| w / sum)
+        })
+        .collect()
+}
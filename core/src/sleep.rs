@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+const FADE_DURATION: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopAfter {
+    Track,
+    Tracks(u32),
+    Album,
+}
+
+impl StopAfter {
+    pub(crate) fn advance(self) -> Option<StopAfter> {
+        match self {
+            StopAfter::Tracks(n) if n > 1 => Some(StopAfter::Tracks(n - 1)),
+            StopAfter::Album => Some(StopAfter::Album),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SleepTimer {
+    deadline: Instant,
+    fade: Duration,
+    fading: bool,
+}
+
+impl SleepTimer {
+    pub(crate) fn new(duration: Duration) -> Self {
+        SleepTimer {
+            deadline: Instant::now() + duration,
+            fade: FADE_DURATION.min(duration),
+            fading: false,
+        }
+    }
+
+    pub(crate) fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    pub(crate) fn should_start_fade(&self) -> bool {
+        !self.fading && self.remaining() <= self.fade
+    }
+
+    pub(crate) fn start_fade(&mut self) {
+        self.fading = true;
+    }
+
+    pub(crate) fn is_fading(&self) -> bool {
+        self.fading
+    }
+
+    pub(crate) fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{ArtistCredit, ArtistRole};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagSeparators {
+    pub values: Vec<String>,
+    pub featuring: Vec<String>,
+}
+
+impl Default for TagSeparators {
+    fn default() -> Self {
+        TagSeparators {
+            values: vec![";".into(), "/".into()],
+            featuring: vec![" feat. ".into(), " ft. ".into(), " featuring ".into()],
+        }
+    }
+}
+
+impl TagSeparators {
+    pub fn split<'a>(&self, value: &'a str) -> Vec<&'a str> {
+        let mut parts = vec![value];
+        for separator in self.values.iter().filter(|s| !s.is_empty()) {
+            parts = parts
+                .into_iter()
+                .flat_map(|part| part.split(separator.as_str()))
+                .collect();
+        }
+        parts
+            .into_iter()
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
+
+    fn split_featuring<'a>(&self, value: &'a str) -> (&'a str, Option<&'a str>) {
+        let lower = value.to_ascii_lowercase();
+        self.featuring
+            .iter()
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| Some((lower.find(&s.to_ascii_lowercase())?, s.len())))
+            .min_by_key(|&(at, _)| at)
+            .map_or((value, None), |(at, len)| {
+                (&value[..at], Some(&value[at + len..]))
+            })
+    }
+
+    pub fn values(&self, raw: &[String]) -> Vec<String> {
+        let mut values: Vec<String> = Vec::new();
+        for value in raw.iter().flat_map(|v| self.split(v)) {
+            if !values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+                values.push(value.to_string());
+            }
+        }
+        values
+    }
+
+    pub fn credits(&self, role: ArtistRole, raw: &[String], sort: &[String]) -> Vec<ArtistCredit> {
+        let mut named: Vec<(&str, ArtistRole)> = Vec::new();
+        for value in raw {
+            let (main, featured) = match role {
+                ArtistRole::Primary => self.split_featuring(value),
+                _ => (value.as_str(), None),
+            };
+            named.extend(self.split(main).into_iter().map(|name| (name, role)));
+            if let Some(featured) = featured {
+                named.extend(
+                    self.split(featured)
+                        .into_iter()
+                        .map(|name| (name, ArtistRole::Featured)),
+                );
+            }
+        }
+
+        let sort_names: Vec<&str> = sort
+            .iter()
+            .flat_map(|s| self.split(self.split_featuring(s).0))
+            .collect();
+        let leading = named.iter().take_while(|(_, r)| *r == role).count();
+
+        named
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, role))| ArtistCredit {
+                name: name.to_string(),
+                sort_name: (leading == sort_names.len() && i < leading)
+                    .then(|| sort_names[i].to_string()),
+                role,
+            })
+            .collect()
+    }
+}
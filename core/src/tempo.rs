@@ -0,0 +1,45 @@
+pub const MIN_SPEED: f64 = 0.5;
+pub const MAX_SPEED: f64 = 2.0;
+pub const MAX_SEMITONES: f64 = 12.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tempo {
+    pub speed: f64,
+    pub semitones: f64,
+    pub preserve_pitch: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempoScope {
+    Track,
+    Genre,
+}
+
+impl Default for Tempo {
+    fn default() -> Self {
+        Tempo {
+            speed: 1.0,
+            semitones: 0.0,
+            preserve_pitch: true,
+        }
+    }
+}
+
+impl Tempo {
+    pub fn clamped(self) -> Self {
+        Tempo {
+            speed: self.speed.clamp(MIN_SPEED, MAX_SPEED),
+            semitones: self.semitones.clamp(-MAX_SEMITONES, MAX_SEMITONES),
+            preserve_pitch: self.preserve_pitch,
+        }
+    }
+
+    pub(crate) fn pitch_ratio(&self) -> f32 {
+        let transpose = 2f64.powf(self.semitones / 12.0);
+        if self.preserve_pitch {
+            (transpose / self.speed) as f32
+        } else {
+            transpose as f32
+        }
+    }
+}
@@ -1,39 +1,140 @@
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+use crate::{
+    ArtistCredit, ArtistRole, Chapter, TagSeparators, chapter, cue::CueSheet,
+    genre::normalize_genres, id3,
+};
+
 use lofty::{
+    config::WriteOptions,
     error::LoftyError,
     file::{AudioFile, TaggedFileExt},
     probe::Probe,
-    tag::Accessor,
+    tag::{Accessor, ItemKey, Tag, TagExt},
 };
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagEdit {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub genre: Option<String>,
+    pub year: Option<u32>,
+}
+
+impl TagEdit {
+    pub fn is_empty(&self) -> bool {
+        *self == TagEdit::default()
+    }
+
+    pub(crate) fn write(&self, path: &Path) -> Result<(), TrackError> {
+        let mut file = Probe::open(path)?.read()?;
+        if file.primary_tag().is_none() {
+            file.insert_tag(Tag::new(file.primary_tag_type()));
+        }
+        let Some(tag) = file.primary_tag_mut() else {
+            return Ok(());
+        };
+
+        if let Some(title) = &self.title {
+            tag.set_title(title.clone());
+        }
+        if let Some(artist) = &self.artist {
+            tag.set_artist(artist.clone());
+        }
+        if let Some(album) = &self.album {
+            tag.set_album(album.clone());
+        }
+        if let Some(album_artist) = &self.album_artist {
+            tag.insert_text(ItemKey::AlbumArtist, album_artist.clone());
+        }
+        if let Some(genre) = &self.genre {
+            tag.set_genre(genre.clone());
+        }
+        if let Some(year) = self.year {
+            tag.set_year(year);
+        }
+
+        tag.save_to_path(path, WriteOptions::default())?;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Track {
     pub(crate) id: Option<i64>,
 
     pub(crate) path: PathBuf,
     pub(crate) missing: bool,
+    pub(crate) album_id: Option<i64>,
 
     pub(crate) title: Option<String>,
     pub(crate) track_artist: Option<String>,
     pub(crate) album: Option<String>,
     pub(crate) album_artist: Option<String>,
+    pub(crate) artist_sort: Option<String>,
+    pub(crate) album_artist_sort: Option<String>,
+    pub(crate) credits: Vec<ArtistCredit>,
+    pub(crate) genres: Vec<String>,
     pub(crate) genre: Option<String>,
     pub(crate) year: Option<u32>,
     pub(crate) track_number: Option<u32>,
     pub(crate) disc_number: Option<u32>,
     pub(crate) comment: Option<String>,
+    pub(crate) release_id: Option<String>,
+    pub(crate) compilation: bool,
+    pub(crate) composer: Option<String>,
+    pub(crate) conductor: Option<String>,
+    pub(crate) performer: Option<String>,
+    pub(crate) work: Option<String>,
+    pub(crate) movement: Option<String>,
+    pub(crate) movement_number: Option<u32>,
+    pub(crate) grouping: Option<String>,
 
     pub(crate) duration: f32,
     pub(crate) bit_rate: Option<u32>,
     pub(crate) sample_rate: Option<u32>,
     pub(crate) bit_depth: Option<u8>,
     pub(crate) channels: Option<u8>,
+
+    pub(crate) cue_index: u32,
+    pub(crate) cue_start: Option<f32>,
+    pub(crate) cue_end: Option<f32>,
+    pub(crate) chapters: Vec<Chapter>,
+
+    pub(crate) added_at: Option<i64>,
 }
 
 impl Track {
     pub fn from_path(path: &Path) -> Result<Self, TrackError> {
+        Self::read(path, &TagSeparators::default())
+    }
+
+    pub fn read(path: &Path, separators: &TagSeparators) -> Result<Self, TrackError> {
+        Self::read_tagged(path, separators).map(|(track, _)| track)
+    }
+
+    pub(crate) fn read_all(
+        path: &Path,
+        separators: &TagSeparators,
+    ) -> Result<Vec<Self>, TrackError> {
+        let (track, embedded) = Self::read_tagged(path, separators)?;
+        let sheet = embedded
+            .as_deref()
+            .and_then(CueSheet::parse)
+            .or_else(|| CueSheet::sidecar(path));
+        Ok(match sheet {
+            Some(sheet) => track.split_cue(&sheet, separators),
+            None => vec![track],
+        })
+    }
+
+    fn read_tagged(
+        path: &Path,
+        separators: &TagSeparators,
+    ) -> Result<(Self, Option<String>), TrackError> {
         let file = Probe::open(path)?.read()?;
         let props = file.properties();
         let duration = props.duration().as_secs_f32();
@@ -41,53 +142,176 @@ impl Track {
         let sample_rate = props.sample_rate();
         let bit_depth = props.bit_depth();
         let channels = props.channels();
+        let id3 = id3::read(path);
 
-        let (
-            title,
-            track_artist,
-            album,
-            album_artist,
-            genre,
-            year,
-            track_number,
-            disc_number,
-            comment,
-        ) = if let Some(tag) = file.primary_tag().or_else(|| file.first_tag()) {
-            (
-                tag.title().map(|s| s.into()),
-                tag.artist().map(|s| s.into()),
-                tag.album().map(|s| s.into()),
-                tag.get_string(&lofty::tag::ItemKey::AlbumArtist)
-                    .map(|s| s.into()),
-                tag.genre().map(|s| s.into()),
-                tag.year(),
-                tag.track(),
-                tag.disk(),
-                tag.comment().map(|s| s.into()),
-            )
-        } else {
-            (None, None, None, None, None, None, None, None, None)
-        };
-
-        Ok(Track {
+        let mut track = Track {
             id: None,
             path: path.to_path_buf(),
             missing: false,
-            title,
-            track_artist,
-            album,
-            album_artist,
-            genre,
-            year,
-            track_number,
-            disc_number,
-            comment,
+            album_id: None,
+            title: None,
+            track_artist: None,
+            album: None,
+            album_artist: None,
+            artist_sort: None,
+            album_artist_sort: None,
+            credits: Vec::new(),
+            genres: Vec::new(),
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            comment: None,
+            release_id: None,
+            compilation: false,
+            composer: None,
+            conductor: None,
+            performer: None,
+            work: None,
+            movement: None,
+            movement_number: None,
+            grouping: None,
             duration,
             bit_rate,
             sample_rate,
             bit_depth,
             channels,
-        })
+            cue_index: 0,
+            cue_start: None,
+            cue_end: None,
+            chapters: chapter::read(path, id3.as_ref(), duration),
+            added_at: None,
+        };
+        let mut cue_sheet = None;
+
+        if let Some(tag) = file.primary_tag().or_else(|| file.first_tag()) {
+            let text = |key: ItemKey| tag.get_string(&key).map(|s| s.into());
+            let values = |key: ItemKey| -> Vec<String> {
+                tag.get_strings(&key)
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            };
+            let joined = |values: &[String]| (!values.is_empty()).then(|| values.join("; "));
+
+            let artists = values(ItemKey::TrackArtist);
+            let album_artists = values(ItemKey::AlbumArtist);
+            let genres = values(ItemKey::Genre);
+            let composers = values(ItemKey::Composer);
+            let conductors = values(ItemKey::Conductor);
+            let performers = values(ItemKey::Performer);
+
+            track.title = tag.title().map(|s| s.into());
+            track.track_artist = joined(&artists);
+            track.album = tag.album().map(|s| s.into());
+            track.album_artist = joined(&album_artists);
+            track.artist_sort = text(ItemKey::TrackArtistSortOrder);
+            track.album_artist_sort = text(ItemKey::AlbumArtistSortOrder);
+            track.genre = joined(&genres);
+            track.year = tag.year();
+            track.track_number = tag.track();
+            track.disc_number = tag.disk();
+            track.comment = tag.comment().map(|s| s.into());
+            track.release_id = text(ItemKey::MusicBrainzReleaseId);
+            track.compilation = tag
+                .get_string(&ItemKey::FlagCompilation)
+                .is_some_and(|flag| {
+                    let flag = flag.trim();
+                    flag == "1"
+                        || flag.eq_ignore_ascii_case("true")
+                        || flag.eq_ignore_ascii_case("yes")
+                });
+            track.composer = joined(&composers);
+            track.conductor = joined(&conductors);
+            track.performer = joined(&performers);
+            track.work = text(ItemKey::Work);
+            track.movement = text(ItemKey::Movement);
+            track.movement_number = tag
+                .get_string(&ItemKey::MovementNumber)
+                .and_then(|n| n.split('/').next()?.trim().parse().ok());
+            track.grouping = text(ItemKey::ContentGroup);
+
+            track.credits = [
+                (
+                    ArtistRole::Primary,
+                    artists,
+                    values(ItemKey::TrackArtistSortOrder),
+                ),
+                (
+                    ArtistRole::AlbumArtist,
+                    album_artists,
+                    values(ItemKey::AlbumArtistSortOrder),
+                ),
+                (ArtistRole::Remixer, values(ItemKey::Remixer), Vec::new()),
+                (ArtistRole::Composer, composers, Vec::new()),
+                (ArtistRole::Conductor, conductors, Vec::new()),
+                (ArtistRole::Performer, performers, Vec::new()),
+            ]
+            .iter()
+            .flat_map(|(role, raw, sort)| separators.credits(*role, raw, sort))
+            .collect();
+            track.genres = normalize_genres(separators.values(&genres));
+            cue_sheet = text(ItemKey::Unknown("CUESHEET".to_string()));
+        }
+        if track.work.is_none() {
+            track.work = id3.as_ref().and_then(|tag| id3::user_text(tag, "WORK"));
+        }
+
+        Ok((track, cue_sheet))
+    }
+
+    fn split_cue(self, sheet: &CueSheet, separators: &TagSeparators) -> Vec<Track> {
+        let cues = sheet.tracks_for(&self.path);
+        if cues.is_empty() {
+            return vec![self];
+        }
+
+        cues.iter()
+            .enumerate()
+            .map(|(i, cue)| {
+                let end = cues
+                    .get(i + 1)
+                    .map(|next| next.start)
+                    .filter(|&end| end > cue.start);
+                let mut track = self.clone();
+                track.cue_index = cue.number;
+                track.cue_start = Some(cue.start);
+                track.cue_end = end;
+                track.duration = (end.unwrap_or(self.duration) - cue.start).max(0.0);
+                track.chapters = self
+                    .chapters
+                    .iter()
+                    .filter(|c| c.start >= cue.start && end.is_none_or(|end| c.start < end))
+                    .map(|c| Chapter {
+                        title: c.title.clone(),
+                        start: c.start - cue.start,
+                        end: end.map_or(c.end, |end| c.end.min(end)) - cue.start,
+                    })
+                    .collect();
+                track.track_number = Some(cue.number);
+                if let Some(title) = &cue.title {
+                    track.title = Some(title.clone());
+                }
+                if let Some(performer) = cue.performer.as_ref().or(sheet.performer.as_ref()) {
+                    track.track_artist = Some(performer.clone());
+                    track.artist_sort = None;
+                }
+                if let Some(album) = &sheet.title {
+                    track.album = Some(album.clone());
+                }
+                if let Some(performer) = &sheet.performer {
+                    track.album_artist = Some(performer.clone());
+                    track.album_artist_sort = None;
+                }
+                if let Some(genre) = &sheet.genre {
+                    track.genre = Some(genre.clone());
+                }
+                track.year = sheet.year.or(track.year);
+                track.split_tags(separators);
+                track
+            })
+            .collect()
     }
 
     pub fn id(&self) -> Option<i64> {
@@ -102,6 +326,10 @@ impl Track {
         self.missing
     }
 
+    pub fn album_id(&self) -> Option<i64> {
+        self.album_id
+    }
+
     pub fn title(&self) -> Option<&str> {
         self.title.as_deref()
     }
@@ -118,10 +346,49 @@ impl Track {
         self.album_artist.as_deref()
     }
 
+    pub fn artist_sort(&self) -> Option<&str> {
+        self.artist_sort.as_deref()
+    }
+
+    pub fn album_artist_sort(&self) -> Option<&str> {
+        self.album_artist_sort.as_deref()
+    }
+
     pub fn genre(&self) -> Option<&str> {
         self.genre.as_deref()
     }
 
+    pub fn credits(&self) -> &[ArtistCredit] {
+        &self.credits
+    }
+
+    pub fn genres(&self) -> &[String] {
+        &self.genres
+    }
+
+    pub(crate) fn split_tags(&mut self, separators: &TagSeparators) {
+        let raw = |value: &Option<String>| value.iter().cloned().collect::<Vec<_>>();
+        self.credits = separators.credits(
+            ArtistRole::Primary,
+            &raw(&self.track_artist),
+            &raw(&self.artist_sort),
+        );
+        self.credits.extend(separators.credits(
+            ArtistRole::AlbumArtist,
+            &raw(&self.album_artist),
+            &raw(&self.album_artist_sort),
+        ));
+        for (role, value) in [
+            (ArtistRole::Composer, &self.composer),
+            (ArtistRole::Conductor, &self.conductor),
+            (ArtistRole::Performer, &self.performer),
+        ] {
+            self.credits
+                .extend(separators.credits(role, &raw(value), &[]));
+        }
+        self.genres = normalize_genres(separators.values(&raw(&self.genre)));
+    }
+
     pub fn year(&self) -> Option<u32> {
         self.year
     }
@@ -138,6 +405,57 @@ impl Track {
         self.comment.as_deref()
     }
 
+    pub fn release_id(&self) -> Option<&str> {
+        self.release_id.as_deref()
+    }
+
+    pub fn compilation(&self) -> bool {
+        self.compilation
+    }
+
+    pub fn composer(&self) -> Option<&str> {
+        self.composer.as_deref()
+    }
+
+    pub fn conductor(&self) -> Option<&str> {
+        self.conductor.as_deref()
+    }
+
+    pub fn performer(&self) -> Option<&str> {
+        self.performer.as_deref()
+    }
+
+    pub fn work(&self) -> Option<&str> {
+        self.work.as_deref()
+    }
+
+    pub fn movement(&self) -> Option<&str> {
+        self.movement.as_deref()
+    }
+
+    pub fn movement_number(&self) -> Option<u32> {
+        self.movement_number
+    }
+
+    pub fn grouping(&self) -> Option<&str> {
+        self.grouping.as_deref()
+    }
+
+    pub fn work_title(&self) -> Option<String> {
+        let movement = match (self.movement_number, self.movement.as_deref()) {
+            (Some(number), Some(name)) => Some(format!("{}. {name}", roman_numeral(number))),
+            (_, name) => name.map(str::to_string),
+        };
+        match (self.work.as_deref(), movement) {
+            (Some(work), Some(movement)) => Some(format!("{work}: {movement}")),
+            (Some(work), None) => Some(match self.title() {
+                Some(title) if title != work => format!("{work}: {title}"),
+                _ => work.to_string(),
+            }),
+            (None, movement) => movement,
+        }
+    }
+
     pub fn duration(&self) -> f32 {
         self.duration
     }
@@ -157,6 +475,59 @@ impl Track {
     pub fn channels(&self) -> Option<u8> {
         self.channels
     }
+
+    pub fn cue_index(&self) -> u32 {
+        self.cue_index
+    }
+
+    pub fn cue_start(&self) -> Option<f32> {
+        self.cue_start
+    }
+
+    pub fn cue_end(&self) -> Option<f32> {
+        self.cue_end
+    }
+
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        self.cue_start.is_some()
+    }
+
+    pub fn added_at(&self) -> Option<i64> {
+        self.added_at
+    }
+}
+
+fn roman_numeral(mut number: u32) -> String {
+    if number == 0 || number >= 4000 {
+        return number.to_string();
+    }
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            out.push_str(numeral);
+            number -= value;
+        }
+    }
+    out
 }
 
 #[derive(Debug, Error)]
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone)]
+pub struct Work {
+    pub name: String,
+    pub composer_id: i64,
+    pub track_count: i64,
+    pub duration: f32,
+    pub sample_track_path: Option<String>,
+}
@@ -0,0 +1,497 @@
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use kira::Frame;
+use lofty::{
+    config::WriteOptions,
+    id3::v2::{BinaryFrame, Frame as Id3Frame, FrameId, Id3v2Tag},
+    tag::{Accessor, ItemKey, ItemValue, Tag, TagExt, TagItem, TagType},
+};
+use verse_core::HeadlessOutput;
+
+const SAMPLE_RATE: u32 = 44100;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Mp3,
+    Flac,
+    Ogg,
+    M4a,
+    Aiff,
+    Opus,
+}
+
+impl Format {
+    pub const ALL: [Format; 6] = [
+        Format::Mp3,
+        Format::Flac,
+        Format::Ogg,
+        Format::M4a,
+        Format::Aiff,
+        Format::Opus,
+    ];
+
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Mp3 => "mp3",
+            Format::Flac => "flac",
+            Format::Ogg => "ogg",
+            Format::M4a => "m4a",
+            Format::Aiff => "aiff",
+            Format::Opus => "opus",
+        }
+    }
+
+    fn tag_type(self) -> TagType {
+        match self {
+            Format::Mp3 | Format::Aiff => TagType::Id3v2,
+            Format::Flac | Format::Ogg | Format::Opus => TagType::VorbisComments,
+            Format::M4a => TagType::Mp4Ilst,
+        }
+    }
+
+    fn stream(self) -> Vec<u8> {
+        match self {
+            Format::Mp3 => mp3_stream(),
+            Format::Flac => flac_stream(),
+            Format::Ogg => ogg_stream(),
+            Format::M4a => m4a_stream(),
+            Format::Aiff => aiff_stream(),
+            Format::Opus => opus_stream(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Tags<'a> {
+    pub title: &'a str,
+    pub artist: &'a str,
+    pub album: Option<&'a str>,
+    pub album_artist: Option<&'a str>,
+    pub artist_sort: Option<&'a str>,
+    pub album_artist_sort: Option<&'a str>,
+    pub extra_artists: &'a [&'a str],
+    pub composer: Option<&'a str>,
+    pub conductor: Option<&'a str>,
+    pub work: Option<&'a str>,
+    pub movement: Option<&'a str>,
+    pub movement_number: Option<u32>,
+    pub remixer: Option<&'a str>,
+    pub genre: Option<&'a str>,
+    pub extra_genres: &'a [&'a str],
+    pub year: Option<u32>,
+    pub track: Option<u32>,
+    pub disc: Option<u32>,
+    pub release_id: Option<&'a str>,
+    pub compilation: bool,
+    pub cue_sheet: Option<&'a str>,
+    pub chapters: &'a [(&'a str, u32)],
+}
+
+pub fn write(dir: &Path, name: &str, format: Format, tags: &Tags) -> PathBuf {
+    let path = dir.join(format!("{name}.{}", format.extension()));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(&path, format.stream()).unwrap();
+
+    let mut tag = Tag::new(format.tag_type());
+    tag.set_title(tags.title.to_string());
+    insert_values(
+        &mut tag,
+        ItemKey::TrackArtist,
+        [tags.artist].iter().chain(tags.extra_artists),
+    );
+    if let Some(album) = tags.album {
+        tag.set_album(album.to_string());
+    }
+    if let Some(album_artist) = tags.album_artist {
+        tag.insert_text(ItemKey::AlbumArtist, album_artist.to_string());
+    }
+    if let Some(composer) = tags.composer {
+        tag.insert_text(ItemKey::Composer, composer.to_string());
+    }
+    if let Some(conductor) = tags.conductor {
+        tag.insert_text(ItemKey::Conductor, conductor.to_string());
+    }
+    if let Some(work) = tags.work.filter(|_| format.tag_type() != TagType::Id3v2) {
+        tag.insert_text(ItemKey::Work, work.to_string());
+    }
+    if let Some(movement) = tags.movement {
+        tag.insert_text(ItemKey::Movement, movement.to_string());
+    }
+    if let Some(number) = tags.movement_number {
+        tag.insert_text(ItemKey::MovementNumber, number.to_string());
+    }
+    if let Some(remixer) = tags.remixer {
+        tag.insert_text(ItemKey::Remixer, remixer.to_string());
+    }
+    if let Some(artist_sort) = tags.artist_sort {
+        tag.insert_text(ItemKey::TrackArtistSortOrder, artist_sort.to_string());
+    }
+    if let Some(album_artist_sort) = tags.album_artist_sort {
+        tag.insert_text(ItemKey::AlbumArtistSortOrder, album_artist_sort.to_string());
+    }
+    insert_values(
+        &mut tag,
+        ItemKey::Genre,
+        tags.genre.iter().chain(tags.extra_genres),
+    );
+    if let Some(year) = tags.year {
+        tag.set_year(year);
+    }
+    if let Some(track) = tags.track {
+        tag.set_track(track);
+    }
+    if let Some(disc) = tags.disc {
+        tag.set_disk(disc);
+    }
+    if let Some(release_id) = tags.release_id {
+        tag.insert_text(ItemKey::MusicBrainzReleaseId, release_id.to_string());
+    }
+    if tags.compilation {
+        tag.insert_text(ItemKey::FlagCompilation, "1".to_string());
+    }
+    if let Some(cue_sheet) = tags.cue_sheet {
+        tag.insert_unchecked(TagItem::new(
+            ItemKey::Unknown("CUESHEET".to_string()),
+            ItemValue::Text(cue_sheet.to_string()),
+        ));
+    }
+    match format {
+        Format::Mp3 | Format::Aiff if !tags.chapters.is_empty() || tags.work.is_some() => {
+            let mut tag = Id3v2Tag::from(tag);
+            if let Some(work) = tags.work {
+                tag.insert_user_text("WORK".to_string(), work.to_string());
+            }
+            add_chapter_frames(&mut tag, tags.chapters);
+            tag.save_to_path(&path, WriteOptions::default()).unwrap();
+        }
+        Format::M4a if !tags.chapters.is_empty() => {
+            tag.save_to_path(&path, WriteOptions::default()).unwrap();
+            add_nero_chapters(&path, tags.chapters);
+        }
+        _ => tag.save_to_path(&path, WriteOptions::default()).unwrap(),
+    }
+
+    path
+}
+
+fn insert_values<'a>(tag: &mut Tag, key: ItemKey, values: impl Iterator<Item = &'a &'a str>) {
+    let values: Vec<&str> = values.copied().collect();
+    if values.is_empty() {
+        return;
+    }
+    // lofty writes repeated ID3v2 items as duplicate frames; v2.4 expects one NUL-separated frame
+    if tag.tag_type() == TagType::Id3v2 {
+        tag.insert_text(key, values.join("\0"));
+    } else {
+        for value in values {
+            tag.push(TagItem::new(
+                key.clone(),
+                ItemValue::Text(value.to_string()),
+            ));
+        }
+    }
+}
+
+pub fn write_chapters(path: &Path, chapters: &[(&str, u32)]) {
+    let mut tag = Id3v2Tag::new();
+    add_chapter_frames(&mut tag, chapters);
+    tag.save_to_path(path, WriteOptions::default()).unwrap();
+}
+
+fn add_chapter_frames(tag: &mut Id3v2Tag, chapters: &[(&str, u32)]) {
+    for (i, (title, start)) in chapters.iter().enumerate() {
+        let end = chapters.get(i + 1).map_or(*start, |(_, next)| *next);
+
+        let mut data = format!("chp{i}\0").into_bytes();
+        data.extend_from_slice(&start.to_be_bytes());
+        data.extend_from_slice(&end.to_be_bytes());
+        data.extend_from_slice(&[0xFF; 8]);
+        data.extend_from_slice(b"TIT2");
+        data.extend_from_slice(&(title.len() as u32 + 1).to_be_bytes());
+        data.extend_from_slice(&[0, 0, 3]);
+        data.extend_from_slice(title.as_bytes());
+
+        let id = FrameId::new("CHAP").unwrap();
+        tag.insert(Id3Frame::Binary(BinaryFrame::new(id, data)));
+    }
+}
+
+fn add_nero_chapters(path: &Path, chapters: &[(&str, u32)]) {
+    let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, chapters.len() as u8];
+    for (title, start) in chapters {
+        chpl.extend_from_slice(&(*start as u64 * 10_000).to_be_bytes());
+        chpl.push(title.len() as u8);
+        chpl.extend_from_slice(title.as_bytes());
+    }
+    let udta = atom(b"udta", &atom(b"chpl", &chpl));
+
+    let mut data = fs::read(path).unwrap();
+    let size_at = |data: &[u8], offset: usize| {
+        u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap()) as usize
+    };
+    let mut offset = 0;
+    while &data[offset + 4..offset + 8] != b"moov" {
+        offset += size_at(&data, offset);
+    }
+    let size = size_at(&data, offset);
+    data.splice(offset + size..offset + size, udta.iter().copied());
+    data[offset..offset + 4].copy_from_slice(&((size + udta.len()) as u32).to_be_bytes());
+    fs::write(path, data).unwrap();
+}
+
+pub fn tone(output: &HeadlessOutput, path: &Path, seconds: f32, frequency: f32) {
+    let rate = output.sample_rate() as f32;
+    let frames: Vec<Frame> = (0..(seconds * rate) as usize)
+        .map(|i| {
+            Frame::from_mono(0.5 * (std::f32::consts::TAU * frequency * i as f32 / rate).sin())
+        })
+        .collect();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    output.write_wav(path, &frames).unwrap();
+}
+
+fn mp3_stream() -> Vec<u8> {
+    const FRAME_LEN: usize = 417;
+    const FRAMES: usize = 40;
+
+    let mut data = Vec::with_capacity(FRAME_LEN * FRAMES);
+    for _ in 0..FRAMES {
+        let mut frame = [0u8; FRAME_LEN];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        data.extend_from_slice(&frame);
+    }
+    data
+}
+
+fn flac_stream() -> Vec<u8> {
+    let info: u64 = ((SAMPLE_RATE as u64) << 44) | (1 << 41) | (15 << 36) | SAMPLE_RATE as u64;
+
+    let mut data = b"fLaC".to_vec();
+    data.extend_from_slice(&[0x00, 0, 0, 34]);
+    data.extend_from_slice(&4096u16.to_be_bytes());
+    data.extend_from_slice(&4096u16.to_be_bytes());
+    data.extend_from_slice(&[0; 6]);
+    data.extend_from_slice(&info.to_be_bytes());
+    data.extend_from_slice(&[0; 16]);
+    data.extend_from_slice(&[0x81, 0, 4, 0]);
+    data.extend_from_slice(&[0; 1024]);
+    data
+}
+
+fn ogg_stream() -> Vec<u8> {
+    let mut ident = b"\x01vorbis".to_vec();
+    ident.extend_from_slice(&0u32.to_le_bytes());
+    ident.push(2);
+    ident.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    ident.extend_from_slice(&0i32.to_le_bytes());
+    ident.extend_from_slice(&128_000i32.to_le_bytes());
+    ident.extend_from_slice(&0i32.to_le_bytes());
+    ident.extend_from_slice(&[0xB8, 0x01]);
+
+    let vendor = b"verse";
+    let mut comment = b"\x03vorbis".to_vec();
+    comment.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    comment.extend_from_slice(vendor);
+    comment.extend_from_slice(&0u32.to_le_bytes());
+    comment.push(1);
+
+    let mut setup = b"\x05vorbis".to_vec();
+    setup.extend_from_slice(&[0; 8]);
+    setup.push(1);
+
+    let mut data = ogg_page(0x02, 0, 0, &[&ident]);
+    data.extend(ogg_page(0x00, 0, 1, &[&comment, &setup]));
+    data.extend(ogg_page(0x04, SAMPLE_RATE as u64, 2, &[&[0; 16]]));
+    data
+}
+
+fn opus_stream() -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1);
+    head.push(2);
+    head.extend_from_slice(&312u16.to_le_bytes());
+    head.extend_from_slice(&48_000u32.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes());
+    head.push(0);
+
+    let vendor = b"verse";
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+
+    let mut data = ogg_page(0x02, 0, 0, &[&head]);
+    data.extend(ogg_page(0x00, 0, 1, &[&tags]));
+    data.extend(ogg_page(0x04, 48_312, 2, &[&[0xFC; 16]]));
+    data
+}
+
+fn aiff_stream() -> Vec<u8> {
+    let frames = SAMPLE_RATE;
+
+    let mut comm = Vec::with_capacity(18);
+    comm.extend_from_slice(&1u16.to_be_bytes());
+    comm.extend_from_slice(&frames.to_be_bytes());
+    comm.extend_from_slice(&16u16.to_be_bytes());
+    comm.extend_from_slice(&[0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]);
+
+    let mut ssnd = vec![0; 8];
+    ssnd.resize(8 + frames as usize * 2, 0);
+
+    let mut body = b"AIFF".to_vec();
+    for (id, chunk) in [(b"COMM", &comm), (b"SSND", &ssnd)] {
+        body.extend_from_slice(id);
+        body.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+        body.extend_from_slice(chunk);
+    }
+
+    let mut data = b"FORM".to_vec();
+    data.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    data.extend(body);
+    data
+}
+
+fn ogg_page(header_type: u8, granule: u64, sequence: u32, packets: &[&[u8]]) -> Vec<u8> {
+    let mut segments = Vec::new();
+    for packet in packets {
+        segments.extend(std::iter::repeat_n(255u8, packet.len() / 255));
+        segments.push((packet.len() % 255) as u8);
+    }
+
+    let mut page = b"OggS".to_vec();
+    page.push(0);
+    page.push(header_type);
+    page.extend_from_slice(&granule.to_le_bytes());
+    page.extend_from_slice(&1u32.to_le_bytes());
+    page.extend_from_slice(&sequence.to_le_bytes());
+    page.extend_from_slice(&0u32.to_le_bytes());
+    page.push(segments.len() as u8);
+    page.extend_from_slice(&segments);
+    for packet in packets {
+        page.extend_from_slice(packet);
+    }
+
+    let crc = ogg_crc(&page);
+    page[22..26].copy_from_slice(&crc.to_le_bytes());
+    page
+}
+
+fn ogg_crc(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for &byte in data {
+        crc ^= (byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn m4a_stream() -> Vec<u8> {
+    let matrix: Vec<u8> = [0x0001_0000u32, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect();
+
+    let mut mvhd = vec![0; 12];
+    mvhd.extend_from_slice(&1000u32.to_be_bytes());
+    mvhd.extend_from_slice(&1000u32.to_be_bytes());
+    mvhd.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    mvhd.extend_from_slice(&0x0100u16.to_be_bytes());
+    mvhd.extend_from_slice(&[0; 10]);
+    mvhd.extend_from_slice(&matrix);
+    mvhd.extend_from_slice(&[0; 24]);
+    mvhd.extend_from_slice(&2u32.to_be_bytes());
+
+    let mut tkhd = vec![0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0];
+    tkhd.extend_from_slice(&1u32.to_be_bytes());
+    tkhd.extend_from_slice(&[0; 4]);
+    tkhd.extend_from_slice(&1000u32.to_be_bytes());
+    tkhd.extend_from_slice(&[0; 12]);
+    tkhd.extend_from_slice(&0x0100u16.to_be_bytes());
+    tkhd.extend_from_slice(&[0; 2]);
+    tkhd.extend_from_slice(&matrix);
+    tkhd.extend_from_slice(&[0; 8]);
+
+    let mut mdhd = vec![0; 12];
+    mdhd.extend_from_slice(&SAMPLE_RATE.to_be_bytes());
+    mdhd.extend_from_slice(&SAMPLE_RATE.to_be_bytes());
+    mdhd.extend_from_slice(&[0x55, 0xC4, 0, 0]);
+
+    let mut hdlr = vec![0; 8];
+    hdlr.extend_from_slice(b"soun");
+    hdlr.extend_from_slice(&[0; 12]);
+    hdlr.extend_from_slice(b"SoundHandler\0");
+
+    let decoder_config = [
+        &[0x04, 17, 0x40, 0x15, 0, 0, 0][..],
+        &128_000u32.to_be_bytes(),
+        &128_000u32.to_be_bytes(),
+        &[0x05, 2, 0x12, 0x10],
+    ]
+    .concat();
+    let mut esds = vec![0, 0, 0, 0, 0x03, 25, 0, 0, 0];
+    esds.extend_from_slice(&decoder_config);
+    esds.extend_from_slice(&[0x06, 1, 0x02]);
+
+    let mut mp4a = vec![0, 0, 0, 0, 0, 0, 0, 1];
+    mp4a.extend_from_slice(&[0; 8]);
+    mp4a.extend_from_slice(&2u16.to_be_bytes());
+    mp4a.extend_from_slice(&16u16.to_be_bytes());
+    mp4a.extend_from_slice(&[0; 4]);
+    mp4a.extend_from_slice(&(SAMPLE_RATE << 16).to_be_bytes());
+    mp4a.extend(atom(b"esds", &esds));
+
+    let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+    stsd.extend(atom(b"mp4a", &mp4a));
+
+    let stbl = [
+        atom(b"stsd", &stsd),
+        atom(b"stts", &[0; 8]),
+        atom(b"stsc", &[0; 8]),
+        atom(b"stsz", &[0; 12]),
+        atom(b"stco", &[0; 8]),
+    ]
+    .concat();
+    let minf = [atom(b"smhd", &[0; 8]), atom(b"stbl", &stbl)].concat();
+    let mdia = [
+        atom(b"mdhd", &mdhd),
+        atom(b"hdlr", &hdlr),
+        atom(b"minf", &minf),
+    ]
+    .concat();
+    let trak = [atom(b"tkhd", &tkhd), atom(b"mdia", &mdia)].concat();
+    let moov = [atom(b"mvhd", &mvhd), atom(b"trak", &trak)].concat();
+
+    let mut ftyp = b"M4A ".to_vec();
+    ftyp.extend_from_slice(&0u32.to_be_bytes());
+    ftyp.extend_from_slice(b"M4A mp42isom");
+
+    [
+        atom(b"ftyp", &ftyp),
+        atom(b"moov", &moov),
+        atom(b"mdat", &[0; 16]),
+    ]
+    .concat()
+}
+
+fn atom(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+    data.extend_from_slice(name);
+    data.extend_from_slice(payload);
+    data
+}
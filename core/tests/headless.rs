@@ -0,0 +1,212 @@
+mod fixtures;
+
+use std::{fs, path::Path, thread, time::Duration};
+
+use kira::Frame;
+use tempfile::TempDir;
+use verse_core::{AudioFormat, AudioSettings, Backend, HeadlessOutput, LoopRegion, PlaybackError};
+
+const BLOCK: Duration = Duration::from_millis(20);
+
+fn headless() -> (Backend, HeadlessOutput) {
+    Backend::headless(AudioSettings::default()).unwrap()
+}
+
+fn render_until(
+    output: &HeadlessOutput,
+    backend: &Backend,
+    limit: Duration,
+    done: impl Fn(&Backend) -> bool,
+) -> Vec<Frame> {
+    let mut frames = Vec::new();
+    let mut rendered = Duration::ZERO;
+    while !done(backend) && rendered < limit {
+        frames.extend(output.render(BLOCK));
+        rendered += BLOCK;
+        thread::sleep(Duration::from_millis(1));
+    }
+    frames
+}
+
+#[test]
+fn renders_silence_when_idle() {
+    let (_backend, output) = headless();
+    let frames = output.render(Duration::from_millis(100));
+
+    assert_eq!(frames.len(), output.sample_rate() as usize / 10);
+    assert!(frames.iter().all(|f| f.left == 0.0 && f.right == 0.0));
+}
+
+#[test]
+fn plays_track_to_completion() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, output) = headless();
+    let path = dir.path().join("tone-440.wav");
+    fixtures::tone(&output, &path, 0.5, 440.0);
+
+    backend.load_and_play(&path).unwrap();
+    let frames = render_until(
+        &output,
+        &backend,
+        Duration::from_secs(5),
+        Backend::is_finished,
+    );
+
+    assert!(backend.is_finished());
+    assert!(frames.iter().any(|f| f.left.abs() > 0.1));
+}
+
+#[test]
+fn undecodable_formats_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, _output) = headless();
+    let path = dir.path().join("track.wv");
+    fs::write(&path, b"wvpk").unwrap();
+
+    let err = backend.load_and_play(&path).unwrap_err();
+    assert!(matches!(
+        err,
+        PlaybackError::UnsupportedFormat(AudioFormat::WavPack)
+    ));
+    assert_eq!(
+        AudioFormat::from_path(Path::new("Song.AIFF")),
+        Some(AudioFormat::Aiff)
+    );
+    assert!(AudioFormat::from_path(Path::new("notes.txt")).is_none());
+}
+
+#[test]
+fn range_playback_stops_at_end_offset() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, output) = headless();
+    let path = dir.path().join("tone-440.wav");
+    fixtures::tone(&output, &path, 2.0, 440.0);
+
+    backend.load_and_play_range(&path, 1.0, Some(1.5)).unwrap();
+    assert!(backend.position() < 0.1);
+    let frames = render_until(
+        &output,
+        &backend,
+        Duration::from_secs(5),
+        Backend::is_finished,
+    );
+
+    assert!(backend.is_finished());
+    assert!(frames.len() < output.sample_rate() as usize);
+    assert!(frames.iter().any(|f| f.left.abs() > 0.1));
+}
+
+#[test]
+fn seek_moves_position() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, output) = headless();
+    let path = dir.path().join("tone-440.wav");
+    fixtures::tone(&output, &path, 2.0, 440.0);
+
+    backend.load_and_play(&path).unwrap();
+    render_until(&output, &backend, Duration::from_secs(2), |b| {
+        b.position() > 0.0
+    });
+    backend.seek(1.5);
+    render_until(&output, &backend, Duration::from_secs(2), |b| {
+        b.position() >= 1.5
+    });
+
+    assert!(backend.position() >= 1.5);
+    assert!(!backend.is_finished());
+}
+
+#[test]
+fn loop_region_keeps_playhead_inside() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, output) = headless();
+    let path = dir.path().join("tone-440.wav");
+    fixtures::tone(&output, &path, 1.0, 440.0);
+
+    backend.load_and_play(&path).unwrap();
+    backend.set_loop_region(Some(LoopRegion::new(0.2, 0.4)));
+    render_until(&output, &backend, Duration::from_secs(2), |b| {
+        b.position() >= 0.2
+    });
+    for _ in 0..50 {
+        output.render(BLOCK);
+    }
+
+    let position = backend.position();
+    assert!((0.2..=0.4).contains(&position), "position {position}");
+    assert!(!backend.is_finished());
+}
+
+#[test]
+fn analyzer_tracks_output() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, output) = headless();
+    let path = dir.path().join("tone-1000.wav");
+    fixtures::tone(&output, &path, 2.0, 1000.0);
+
+    backend.load_and_play(&path).unwrap();
+    render_until(&output, &backend, Duration::from_secs(2), |b| {
+        b.position() >= 0.5
+    });
+
+    let vis = backend.vis_data();
+    assert!(vis.peak_left > 0.1 && vis.peak_right > 0.1);
+    assert!(vis.rms_left > 0.05);
+
+    let loudest = vis
+        .bins()
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i)
+        .unwrap();
+    assert!(vis.bins()[loudest] > 0.0);
+    assert!(loudest > 0 && loudest < vis.bins().len() - 1);
+}
+
+#[test]
+fn pause_silences_output() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, output) = headless();
+    let path = dir.path().join("tone-440.wav");
+    fixtures::tone(&output, &path, 3.0, 440.0);
+
+    backend.load_and_play(&path).unwrap();
+    render_until(&output, &backend, Duration::from_secs(2), |b| {
+        b.position() > 0.2
+    });
+    backend.pause();
+    output.render(Duration::from_secs(1));
+
+    let frames = output.render(Duration::from_millis(200));
+    assert!(frames.iter().all(|f| f.left.abs() < 1e-3));
+    assert!(!backend.is_playing());
+}
+
+#[test]
+fn fade_out_carries_over_to_next_track() {
+    let dir = TempDir::new().unwrap();
+    let (mut backend, output) = headless();
+    let first = dir.path().join("tone-440.wav");
+    fixtures::tone(&output, &first, 3.0, 440.0);
+    let second = dir.path().join("tone-660.wav");
+    fixtures::tone(&output, &second, 3.0, 660.0);
+
+    backend.load_and_play(&first).unwrap();
+    render_until(&output, &backend, Duration::from_secs(2), |b| {
+        b.position() > 0.2
+    });
+    backend.fade_out(Duration::from_millis(200));
+    output.render(Duration::from_millis(400));
+    thread::sleep(Duration::from_millis(250));
+
+    backend.load_and_play(&second).unwrap();
+    let frames = output.render(Duration::from_millis(200));
+    assert!(frames.iter().all(|f| f.left.abs() < 1e-3));
+
+    backend.cancel_fade();
+    let frames = render_until(&output, &backend, Duration::from_secs(2), |b| {
+        b.position() > 0.5
+    });
+    assert!(frames.iter().any(|f| f.left.abs() > 0.1));
+}
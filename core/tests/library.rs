@@ -0,0 +1,1125 @@
+mod fixtures;
+
+use std::{fs, path::Path};
+
+use fixtures::{Format, Tags};
+use futures_executor::block_on;
+use tempfile::TempDir;
+use verse_core::{
+    ArtistRole, Library, LibraryError, LibraryService, TagEdit, TagSeparators, Track,
+};
+
+struct Harness {
+    music: TempDir,
+    _data: TempDir,
+    library: Library,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let music = TempDir::new().unwrap();
+        let data = TempDir::new().unwrap();
+        let library = Library::open(&data.path().join("db").join("library.db")).unwrap();
+        Harness {
+            music,
+            _data: data,
+            library,
+        }
+    }
+
+    fn add(&self, name: &str, format: Format, tags: Tags) {
+        fixtures::write(self.music.path(), name, format, &tags);
+    }
+
+    fn scan(&mut self) {
+        self.library.scan(self.music.path()).unwrap();
+    }
+
+    fn track(&self, title: &str) -> Track {
+        self.library
+            .query_all_tracks()
+            .unwrap()
+            .into_iter()
+            .find(|t| t.title() == Some(title))
+            .unwrap_or_else(|| panic!("no track titled {title}"))
+    }
+}
+
+fn album_track<'a>(title: &'a str, album: &'a str, track: u32) -> Tags<'a> {
+    Tags {
+        title,
+        artist: "Artist",
+        album: Some(album),
+        album_artist: Some("Artist"),
+        track: Some(track),
+        ..Tags::default()
+    }
+}
+
+fn titles_by_artist(library: &Library, artist: &str) -> Vec<String> {
+    let mut titles: Vec<String> = library
+        .query_tracks_by_artist(artist)
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.title().map(str::to_string))
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn scans_every_supported_format() {
+    let mut h = Harness::new();
+    for (i, format) in Format::ALL.into_iter().enumerate() {
+        let title = format!("{format:?} Song");
+        h.add(
+            &format!("{i}"),
+            format,
+            Tags {
+                title: &title,
+                artist: "Format Artist",
+                genre: Some("Test"),
+                ..Tags::default()
+            },
+        );
+    }
+    fs::write(h.music.path().join("notes.txt"), "not audio").unwrap();
+    h.scan();
+
+    let tracks = h.library.query_all_tracks().unwrap();
+    assert_eq!(tracks.len(), Format::ALL.len());
+    assert_eq!(h.library.query_track_count().unwrap(), 6);
+
+    for format in Format::ALL {
+        let track = h.track(&format!("{format:?} Song"));
+        assert_eq!(track.track_artist(), Some("Format Artist"));
+        assert_eq!(track.genre(), Some("Test"));
+        assert!(!track.missing());
+        assert!(
+            track.duration() > 0.5,
+            "{format:?} duration {}",
+            track.duration()
+        );
+    }
+}
+
+#[test]
+fn scans_nested_directories() {
+    let mut h = Harness::new();
+    h.add("a/b/c/deep", Format::Flac, album_track("Deep", "Nested", 1));
+    h.add("top", Format::Mp3, album_track("Top", "Nested", 2));
+    h.scan();
+
+    let track = h.track("Deep");
+    assert!(track.path().starts_with(h.music.path().join("a/b/c")));
+    assert_eq!(h.library.query_track_count().unwrap(), 2);
+}
+
+#[test]
+fn folder_tree_aggregates_and_plays_in_path_order() {
+    let mut h = Harness::new();
+    for (name, title) in [
+        ("rock/b/01", "B1"),
+        ("rock/a/02", "A2"),
+        ("rock/a/01", "A1"),
+        ("jazz/01", "J1"),
+        ("top", "Top"),
+    ] {
+        h.add(name, Format::Flac, album_track(title, "Folders", 1));
+    }
+    h.scan();
+
+    let tree = h.library.query_folder_tree(&[h.music.path()]).unwrap();
+    assert_eq!(tree.len(), 1);
+    let root = &tree[0];
+    assert_eq!(root.path, h.music.path());
+    assert_eq!(root.track_count, 5);
+    assert_eq!(root.track_ids, [h.track("Top").id().unwrap()]);
+    let names: Vec<&str> = root.folders.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["jazz", "rock"]);
+
+    let rock = root.find(&h.music.path().join("rock")).unwrap();
+    assert_eq!(rock.track_count, 3);
+    assert!(rock.track_ids.is_empty());
+    assert_eq!(rock.folders[0].track_ids.len(), 2);
+    let total: f32 = ["A1", "A2", "B1"]
+        .iter()
+        .map(|t| h.track(t).duration())
+        .sum();
+    assert!((rock.duration - total).abs() < 0.01);
+
+    let titles: Vec<String> = h
+        .library
+        .query_tracks_in_folder(&rock.path)
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.title().map(str::to_string))
+        .collect();
+    assert_eq!(titles, ["A1", "A2", "B1"]);
+}
+
+#[test]
+fn groups_tracks_into_albums() {
+    let mut h = Harness::new();
+    h.add("one", Format::Flac, album_track("One", "First", 1));
+    h.add("two", Format::Flac, album_track("Two", "First", 2));
+    h.add("three", Format::Ogg, album_track("Three", "Second", 1));
+    h.add(
+        "single",
+        Format::Mp3,
+        Tags {
+            title: "Single",
+            artist: "Someone",
+            ..Tags::default()
+        },
+    );
+    h.scan();
+
+    let albums = h.library.query_all_albums().unwrap();
+    let names: Vec<&str> = albums.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["First", "Second"]);
+    assert!(albums.iter().all(|a| a.artist.as_deref() == Some("Artist")));
+    assert!(albums.iter().all(|a| a.sample_track_path.is_some()));
+
+    let first: Vec<u32> = h
+        .library
+        .query_tracks_by_album("First", Some("Artist"))
+        .unwrap()
+        .iter()
+        .filter_map(Track::track_number)
+        .collect();
+    assert_eq!(first, [1, 2]);
+}
+
+#[test]
+fn same_album_name_by_different_artists_stays_separate() {
+    let mut h = Harness::new();
+    for (name, artist) in [("a", "Queen"), ("b", "ABBA")] {
+        h.add(
+            name,
+            Format::Flac,
+            Tags {
+                title: name,
+                artist,
+                album: Some("Greatest Hits"),
+                album_artist: Some(artist),
+                ..Tags::default()
+            },
+        );
+    }
+    for dir in ["x", "y"] {
+        h.add(
+            &format!("{dir}/loose"),
+            Format::Mp3,
+            Tags {
+                title: dir,
+                artist: "Various",
+                album: Some("Hits"),
+                ..Tags::default()
+            },
+        );
+    }
+    h.scan();
+
+    let albums = h.library.query_all_albums().unwrap();
+    assert_eq!(albums.len(), 4);
+    for album in &albums {
+        assert_eq!(album.track_count, 1);
+        let tracks = h.library.query_tracks_by_album_id(album.id).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].album_id(), Some(album.id));
+        assert_eq!(
+            album.sample_track_path.as_deref(),
+            tracks[0].path().to_str()
+        );
+    }
+}
+
+#[test]
+fn release_id_groups_album_and_aggregates() {
+    let mut h = Harness::new();
+    for (i, (disc, artist)) in [(1, "One"), (1, "Two"), (2, "Three")]
+        .into_iter()
+        .enumerate()
+    {
+        h.add(
+            &format!("t{i}"),
+            Format::Flac,
+            Tags {
+                title: artist,
+                artist,
+                album: Some("Split"),
+                album_artist: Some(artist),
+                disc: Some(disc),
+                release_id: Some("8d7a5d1c-0000-4000-8000-000000000001"),
+                ..Tags::default()
+            },
+        );
+    }
+    h.scan();
+
+    let albums = h.library.query_all_albums().unwrap();
+    assert_eq!(albums.len(), 1);
+    let album = &albums[0];
+    assert_eq!(album.track_count, 3);
+    assert_eq!(album.disc_count, 2);
+    assert_eq!(
+        album.release_id.as_deref(),
+        Some("8d7a5d1c-0000-4000-8000-000000000001")
+    );
+    let total: f32 = h
+        .library
+        .query_tracks_by_album_id(album.id)
+        .unwrap()
+        .iter()
+        .map(Track::duration)
+        .sum();
+    assert!((album.duration - total).abs() < 0.01);
+}
+
+#[test]
+fn compilations_group_by_directory_under_various_artists() {
+    let mut h = Harness::new();
+    for (i, (format, artist)) in [
+        (Format::Mp3, "Blondie"),
+        (Format::Flac, "Devo"),
+        (Format::M4a, "Talking Heads"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        h.add(
+            &format!("now/{i}"),
+            format,
+            Tags {
+                title: artist,
+                artist,
+                album: Some("New Wave Hits"),
+                album_artist: Some(artist),
+                track: Some(i as u32 + 1),
+                compilation: true,
+                ..Tags::default()
+            },
+        );
+    }
+    h.add(
+        "other/0",
+        Format::Ogg,
+        Tags {
+            title: "Elsewhere",
+            artist: "Blondie",
+            album: Some("New Wave Hits"),
+            compilation: true,
+            ..Tags::default()
+        },
+    );
+    h.scan();
+
+    assert!(h.track("Devo").compilation());
+    let albums = h.library.query_all_albums().unwrap();
+    assert_eq!(albums.len(), 2);
+    assert!(albums.iter().all(|a| a.compilation));
+    assert!(
+        albums
+            .iter()
+            .all(|a| a.artist.as_deref() == Some("Various Artists"))
+    );
+    let mut counts: Vec<i64> = albums.iter().map(|a| a.track_count).collect();
+    counts.sort();
+    assert_eq!(counts, [1, 3]);
+
+    h.library.set_various_artists("Verschiedene");
+    let albums = h.library.query_all_albums().unwrap();
+    assert!(
+        albums
+            .iter()
+            .all(|a| a.artist.as_deref() == Some("Verschiedene"))
+    );
+}
+
+#[test]
+fn missing_tracks_drop_orphan_albums() {
+    let mut h = Harness::new();
+    h.add("keep", Format::Flac, album_track("Keep", "Kept", 1));
+    h.add("gone", Format::Ogg, album_track("Gone", "Orphan", 1));
+    h.scan();
+    assert_eq!(h.library.query_all_albums().unwrap().len(), 2);
+
+    fs::remove_file(h.track("Gone").path()).unwrap();
+    h.scan();
+
+    let albums = h.library.query_all_albums().unwrap();
+    assert_eq!(albums.len(), 1);
+    assert_eq!(albums[0].name, "Kept");
+    assert_eq!(h.track("Gone").album_id(), None);
+
+    h.add("gone", Format::Ogg, album_track("Gone", "Orphan", 1));
+    h.scan();
+    assert_eq!(h.library.query_all_albums().unwrap().len(), 2);
+    assert!(h.track("Gone").album_id().is_some());
+}
+
+#[test]
+fn artists_merge_track_and_album_artists() {
+    let mut h = Harness::new();
+    let abbey_road = |title: &'static str, artist: &'static str, track: u32| Tags {
+        title,
+        artist,
+        album: Some("Abbey Road"),
+        album_artist: Some("The Beatles"),
+        track: Some(track),
+        ..Tags::default()
+    };
+    h.add(
+        "one",
+        Format::Flac,
+        Tags {
+            artist_sort: Some("Beatles, The"),
+            album_artist_sort: Some("Beatles, The"),
+            ..abbey_road("One", "The Beatles", 1)
+        },
+    );
+    h.add("two", Format::Mp3, abbey_road("Two", "the beatles", 2));
+    h.add(
+        "guest",
+        Format::Ogg,
+        abbey_road("Guest", "Billy Preston", 3),
+    );
+    h.add(
+        "solo",
+        Format::M4a,
+        Tags {
+            title: "Solo",
+            artist: "ABBA",
+            album: Some("Gold"),
+            album_artist: Some("ABBA"),
+            ..Tags::default()
+        },
+    );
+    h.scan();
+
+    let artists = h.library.query_all_artists().unwrap();
+    let summary: Vec<(&str, Option<&str>, i64, i64)> = artists
+        .iter()
+        .map(|a| {
+            (
+                a.name.as_str(),
+                a.sort_name.as_deref(),
+                a.album_count,
+                a.track_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("ABBA", None, 1, 1),
+            ("The Beatles", Some("Beatles, The"), 1, 3),
+            ("Billy Preston", None, 1, 1),
+        ]
+    );
+
+    let albums = h.library.query_albums_by_artist(artists[2].id).unwrap();
+    assert_eq!(albums.len(), 1);
+    assert_eq!(albums[0].name, "Abbey Road");
+    assert_eq!(albums[0].track_count, 3);
+
+    let tracks = h.library.query_tracks_by_artist_id(artists[1].id).unwrap();
+    assert_eq!(tracks.len(), 3);
+    assert_eq!(h.track("One").artist_sort(), Some("Beatles, The"));
+
+    fs::remove_file(h.track("Solo").path()).unwrap();
+    h.scan();
+    let names: Vec<String> = h
+        .library
+        .query_all_artists()
+        .unwrap()
+        .into_iter()
+        .map(|a| a.name)
+        .collect();
+    assert_eq!(names, ["The Beatles", "Billy Preston"]);
+}
+
+#[test]
+fn multi_value_tags_credit_every_artist_and_genre() {
+    let mut h = Harness::new();
+    h.add(
+        "collab",
+        Format::Flac,
+        Tags {
+            title: "Collab",
+            artist: "Alpha; Beta feat. Gamma",
+            album: Some("Joint"),
+            album_artist: Some("Alpha"),
+            composer: Some("Delta"),
+            remixer: Some("Epsilon"),
+            genre: Some("Rock"),
+            extra_genres: &["Pop"],
+            ..Tags::default()
+        },
+    );
+    h.add(
+        "frames",
+        Format::Mp3,
+        Tags {
+            title: "Frames",
+            artist: "Alpha",
+            extra_artists: &["Zeta"],
+            ..Tags::default()
+        },
+    );
+    h.scan();
+
+    let collab = h.track("Collab");
+    let credits: Vec<(&str, ArtistRole)> = collab
+        .credits()
+        .iter()
+        .map(|c| (c.name.as_str(), c.role))
+        .collect();
+    assert_eq!(
+        credits,
+        [
+            ("Alpha", ArtistRole::Primary),
+            ("Beta", ArtistRole::Primary),
+            ("Gamma", ArtistRole::Featured),
+            ("Alpha", ArtistRole::AlbumArtist),
+            ("Epsilon", ArtistRole::Remixer),
+            ("Delta", ArtistRole::Composer),
+        ]
+    );
+    assert_eq!(collab.genres(), ["Rock", "Pop"]);
+    assert_eq!(h.track("Frames").track_artist(), Some("Alpha; Zeta"));
+
+    assert_eq!(titles_by_artist(&h.library, "alpha"), ["Collab", "Frames"]);
+    assert_eq!(titles_by_artist(&h.library, "Gamma"), ["Collab"]);
+    assert_eq!(titles_by_artist(&h.library, "Delta"), ["Collab"]);
+    assert_eq!(titles_by_artist(&h.library, "Zeta"), ["Frames"]);
+    assert_eq!(h.library.query_tracks_by_genre("pop").unwrap().len(), 1);
+
+    h.library.set_tag_separators(TagSeparators {
+        values: Vec::new(),
+        featuring: Vec::new(),
+    });
+    h.scan();
+    assert_eq!(
+        h.track("Collab").credits()[0].name,
+        "Alpha; Beta feat. Gamma"
+    );
+    assert!(titles_by_artist(&h.library, "Gamma").is_empty());
+}
+
+#[test]
+fn classical_tags_browse_by_composer_and_work() {
+    let mut h = Harness::new();
+    let movements = [
+        (2, "Andante con moto"),
+        (1, "Allegro con brio"),
+        (3, "Allegro"),
+    ];
+    for (number, movement) in movements {
+        h.add(
+            &format!("fifth/{number}"),
+            Format::Flac,
+            Tags {
+                title: movement,
+                artist: "Wiener Philharmoniker",
+                album: Some("Symphonies 5 & 7"),
+                composer: Some("Ludwig van Beethoven"),
+                conductor: Some("Carlos Kleiber"),
+                work: Some("Symphony No. 5 in C minor, Op. 67"),
+                movement: Some(movement),
+                movement_number: Some(number),
+                track: Some(number + 2),
+                ..Tags::default()
+            },
+        );
+    }
+    h.add(
+        "seventh/1",
+        Format::Mp3,
+        Tags {
+            title: "Poco sostenuto",
+            artist: "Wiener Philharmoniker",
+            album: Some("Symphonies 5 & 7"),
+            composer: Some("Ludwig van Beethoven"),
+            work: Some("Symphony No. 7 in A major, Op. 92"),
+            ..Tags::default()
+        },
+    );
+    h.scan();
+
+    let track = h.track("Andante con moto");
+    assert_eq!(track.composer(), Some("Ludwig van Beethoven"));
+    assert_eq!(track.conductor(), Some("Carlos Kleiber"));
+    assert_eq!(track.movement_number(), Some(2));
+    assert_eq!(
+        track.work_title().as_deref(),
+        Some("Symphony No. 5 in C minor, Op. 67: II. Andante con moto")
+    );
+    assert_eq!(
+        h.track("Poco sostenuto").work_title().as_deref(),
+        Some("Symphony No. 7 in A major, Op. 92: Poco sostenuto")
+    );
+    assert!(
+        track
+            .credits()
+            .iter()
+            .any(|c| c.role == ArtistRole::Conductor && c.name == "Carlos Kleiber")
+    );
+
+    let composers = h.library.query_composers().unwrap();
+    assert_eq!(composers.len(), 1);
+    assert_eq!(composers[0].name, "Ludwig van Beethoven");
+    assert_eq!(composers[0].track_count, 4);
+
+    let works = h.library.query_works_by_composer(composers[0].id).unwrap();
+    let names: Vec<&str> = works.iter().map(|w| w.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "Symphony No. 5 in C minor, Op. 67",
+            "Symphony No. 7 in A major, Op. 92"
+        ]
+    );
+    assert_eq!(works[0].track_count, 3);
+
+    let movements: Vec<Option<u32>> = h
+        .library
+        .query_tracks_by_work(composers[0].id, &works[0].name)
+        .unwrap()
+        .iter()
+        .map(Track::movement_number)
+        .collect();
+    assert_eq!(movements, [Some(1), Some(2), Some(3)]);
+}
+
+#[test]
+fn genres_and_decades_aggregate_with_normalized_names() {
+    let mut h = Harness::new();
+    let tracks = [
+        ("One", "Alpha", "First", "hip hop", 1994),
+        ("Two", "Alpha", "First", "Hip-Hop", 1994),
+        ("Three", "Beta", "Second", "HIPHOP", 1998),
+        ("Four", "Gamma", "Third", "ROCK", 2003),
+    ];
+    for (title, artist, album, genre, year) in tracks {
+        h.add(
+            title,
+            Format::Flac,
+            Tags {
+                title,
+                artist,
+                album: Some(album),
+                album_artist: Some(artist),
+                genre: Some(genre),
+                year: Some(year),
+                ..Tags::default()
+            },
+        );
+    }
+    h.scan();
+
+    assert_eq!(h.track("One").genres(), ["Hip-Hop"]);
+
+    let genres = h.library.query_all_genres().unwrap();
+    let names: Vec<&str> = genres.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(names, ["Hip-Hop", "Rock"]);
+    assert_eq!(genres[0].track_count, 3);
+    assert_eq!(genres[0].album_count, 2);
+    assert_eq!(genres[0].top_artists, ["Alpha", "Beta"]);
+
+    let albums = h.library.query_albums_by_genre(genres[0].id).unwrap();
+    let names: Vec<&str> = albums.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["First", "Second"]);
+    assert_eq!(h.library.query_tracks_by_genre("hip-hop").unwrap().len(), 3);
+
+    let decades = h.library.query_decades().unwrap();
+    let labels: Vec<String> = decades.iter().map(|d| d.label()).collect();
+    assert_eq!(labels, ["1990s", "2000s"]);
+    assert_eq!(decades[0].track_count, 3);
+    assert_eq!(decades[1].top_artists, ["Gamma"]);
+
+    let albums = h.library.query_albums_by_decade(1990).unwrap();
+    assert_eq!(albums.len(), 2);
+    assert_eq!(h.library.query_tracks_by_decade(2000).unwrap().len(), 1);
+}
+
+const CUE_SHEET: &str = r#"REM GENRE "Jazz"
+REM DATE 1961
+PERFORMER "The Trio"
+TITLE "Live Set"
+FILE "live.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "Opener"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Ballad"
+    PERFORMER "The Trio feat. Guest"
+    INDEX 00 00:00:20
+    INDEX 01 00:00:30
+  TRACK 03 AUDIO
+    TITLE "Closer"
+    INDEX 01 00:00:60
+"#;
+
+#[test]
+fn cue_sheets_split_files_into_stable_virtual_tracks() {
+    let mut h = Harness::new();
+    h.add(
+        "live",
+        Format::Flac,
+        Tags {
+            title: "Whole Set",
+            artist: "Someone Else",
+            ..Tags::default()
+        },
+    );
+    fs::write(h.music.path().join("live.cue"), CUE_SHEET).unwrap();
+    h.add(
+        "embedded",
+        Format::Flac,
+        Tags {
+            title: "Embedded",
+            artist: "Solo",
+            album: Some("Embedded Album"),
+            cue_sheet: Some(
+                "TRACK 01 AUDIO\nINDEX 01 00:00:00\nTRACK 02 AUDIO\nINDEX 01 00:00:50\n",
+            ),
+            ..Tags::default()
+        },
+    );
+    h.scan();
+
+    let opener = h.track("Opener");
+    assert!(opener.is_virtual());
+    assert_eq!(opener.track_artist(), Some("The Trio"));
+    assert_eq!(opener.album(), Some("Live Set"));
+    assert_eq!(opener.genre(), Some("Jazz"));
+    assert_eq!(opener.year(), Some(1961));
+    assert_eq!(opener.cue_start(), Some(0.0));
+    assert_eq!(opener.cue_end(), Some(0.4));
+
+    let ballad = h.track("Ballad");
+    assert_eq!(ballad.path(), opener.path());
+    assert_eq!(ballad.track_number(), Some(2));
+    assert!((ballad.duration() - 0.4).abs() < 0.01);
+    assert!(
+        ballad
+            .credits()
+            .iter()
+            .any(|c| c.role == ArtistRole::Featured && c.name == "Guest")
+    );
+
+    let closer = h.track("Closer");
+    assert_eq!(closer.cue_end(), None);
+    assert!((closer.duration() - 0.2).abs() < 0.01);
+
+    let album = h.library.query_tracks_by_album("Live Set", None).unwrap();
+    let titles: Vec<&str> = album.iter().filter_map(|t| t.title()).collect();
+    assert_eq!(titles, ["Opener", "Ballad", "Closer"]);
+    assert!(
+        h.library
+            .query_all_tracks()
+            .unwrap()
+            .iter()
+            .all(|t| t.title() != Some("Whole Set"))
+    );
+
+    let embedded = h
+        .library
+        .query_tracks_by_album("Embedded Album", None)
+        .unwrap();
+    assert_eq!(embedded.len(), 2);
+    assert_eq!(embedded[1].cue_start(), Some(50.0 / 75.0));
+
+    let ids: Vec<Option<i64>> = album.iter().map(|t| t.id()).collect();
+    h.scan();
+    let rescanned = h.library.query_tracks_by_album("Live Set", None).unwrap();
+    assert_eq!(rescanned.iter().map(|t| t.id()).collect::<Vec<_>>(), ids);
+}
+
+#[test]
+fn cue_tracks_reject_tag_edits_and_partial_deletes() {
+    let mut h = Harness::new();
+    h.add(
+        "live",
+        Format::Flac,
+        Tags {
+            title: "Whole Set",
+            artist: "Someone Else",
+            ..Tags::default()
+        },
+    );
+    fs::write(h.music.path().join("live.cue"), CUE_SHEET).unwrap();
+    h.scan();
+
+    let opener = h.track("Opener");
+    let edit = TagEdit {
+        title: Some("Renamed".into()),
+        ..TagEdit::default()
+    };
+    assert!(matches!(
+        h.library.edit_tags(&[opener.id().unwrap()], &edit),
+        Err(LibraryError::VirtualTrackEdit)
+    ));
+    assert_eq!(h.track("Ballad").title(), Some("Ballad"));
+    assert_eq!(
+        Track::from_path(opener.path()).unwrap().title(),
+        Some("Whole Set")
+    );
+
+    assert!(matches!(
+        h.library.delete_tracks(&[opener.id().unwrap()]),
+        Err(LibraryError::PartialVirtualDelete)
+    ));
+    assert!(opener.path().exists());
+    assert_eq!(h.library.query_track_count().unwrap(), 3);
+
+    let ids: Vec<i64> = h
+        .library
+        .query_tracks_by_album("Live Set", None)
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.id())
+        .collect();
+    h.library.delete_tracks(&ids).unwrap();
+    assert!(!opener.path().exists());
+    assert_eq!(h.library.query_track_count().unwrap(), 0);
+}
+
+#[test]
+fn chapters_are_read_from_id3_and_mp4_tags() {
+    let mut h = Harness::new();
+    let chapters = [("Opening", 0), ("Middle", 300), ("", 600)];
+    h.add(
+        "book",
+        Format::M4a,
+        Tags {
+            title: "Audiobook",
+            artist: "Narrator",
+            chapters: &chapters,
+            ..Tags::default()
+        },
+    );
+    h.add(
+        "mix",
+        Format::Mp3,
+        Tags {
+            title: "Mix",
+            artist: "DJ",
+            chapters: &chapters,
+            ..Tags::default()
+        },
+    );
+    h.add(
+        "plain",
+        Format::Flac,
+        Tags {
+            title: "Plain",
+            artist: "Band",
+            ..Tags::default()
+        },
+    );
+    h.scan();
+
+    for title in ["Audiobook", "Mix"] {
+        let track = h.track(title);
+        let chapters = track.chapters();
+        let names: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(names, ["Opening", "Middle", "Chapter 3"]);
+        assert_eq!((chapters[1].start, chapters[1].end), (0.3, 0.6));
+        assert!((chapters[2].end - track.duration()).abs() < 0.01);
+    }
+    assert!(h.track("Plain").chapters().is_empty());
+
+    h.scan();
+    assert_eq!(h.track("Mix").chapters().len(), 3);
+}
+
+#[test]
+fn rescan_is_idempotent() {
+    let mut h = Harness::new();
+    h.add("one", Format::Flac, album_track("One", "Album", 1));
+    h.add(
+        "loose",
+        Format::Mp3,
+        Tags {
+            title: "Loose",
+            artist: "Nobody",
+            album: Some("No Album Artist"),
+            ..Tags::default()
+        },
+    );
+    h.scan();
+    let id = h.track("One").id();
+    let added_at = h.track("One").added_at();
+    assert!(added_at.is_some());
+    h.scan();
+
+    assert_eq!(h.library.query_track_count().unwrap(), 2);
+    assert_eq!(h.library.query_all_albums().unwrap().len(), 2);
+    assert_eq!(h.track("One").id(), id);
+    assert_eq!(h.track("One").added_at(), added_at);
+}
+
+#[test]
+fn rescan_flags_missing_and_restored_files() {
+    let mut h = Harness::new();
+    h.add("keep", Format::Flac, album_track("Keep", "Album", 1));
+    h.add("gone", Format::M4a, album_track("Gone", "Album", 2));
+    h.scan();
+
+    let gone = h.track("Gone");
+    let bytes = fs::read(gone.path()).unwrap();
+    fs::remove_file(gone.path()).unwrap();
+    h.scan();
+
+    assert!(h.track("Gone").missing());
+    assert!(!h.track("Keep").missing());
+    assert_eq!(h.track("Gone").id(), gone.id());
+
+    fs::write(gone.path(), bytes).unwrap();
+    h.scan();
+    assert!(!h.track("Gone").missing());
+}
+
+#[test]
+fn rescan_picks_up_tag_changes() {
+    let mut h = Harness::new();
+    h.add("song", Format::Ogg, album_track("Before", "Album", 1));
+    h.scan();
+    h.add("song", Format::Ogg, album_track("After", "Album", 3));
+    h.scan();
+
+    assert_eq!(h.library.query_track_count().unwrap(), 1);
+    assert_eq!(h.track("After").track_number(), Some(3));
+}
+
+#[test]
+fn playlist_keeps_insertion_order() {
+    let mut h = Harness::new();
+    for (i, title) in ["A", "B", "C", "D"].into_iter().enumerate() {
+        h.add(
+            title,
+            Format::Flac,
+            album_track(title, "Album", i as u32 + 1),
+        );
+    }
+    h.scan();
+
+    let ids: Vec<i64> = ["C", "A", "D", "B"]
+        .into_iter()
+        .map(|t| h.track(t).id().unwrap())
+        .collect();
+    let playlist = h.library.create_playlist("Mix").unwrap();
+    for &id in &ids {
+        h.library.add_track_to_playlist(playlist, id).unwrap();
+    }
+    h.library.add_track_to_playlist(playlist, ids[0]).unwrap();
+
+    let titles = |h: &Harness| -> Vec<String> {
+        h.library
+            .get_tracks_in_playlist(playlist)
+            .unwrap()
+            .iter()
+            .filter_map(|t| t.title().map(str::to_string))
+            .collect()
+    };
+    assert_eq!(titles(&h), ["C", "A", "D", "B"]);
+
+    h.library
+        .remove_track_from_playlist(playlist, ids[1])
+        .unwrap();
+    h.library.add_track_to_playlist(playlist, ids[1]).unwrap();
+    assert_eq!(titles(&h), ["C", "D", "B", "A"]);
+
+    let playlists = h.library.get_all_playlists().unwrap();
+    assert_eq!(playlists.len(), 1);
+    assert_eq!(playlists[0].name, "Mix");
+    assert_eq!(playlists[0].track_count, 4);
+    assert_eq!(playlists[0].cover_track_id, ids.iter().min().copied());
+}
+
+#[test]
+fn edit_tags_writes_files_and_database() {
+    let mut h = Harness::new();
+    h.add("one", Format::Flac, album_track("One", "Old", 1));
+    h.add("two", Format::Mp3, album_track("Two", "Old", 2));
+    h.scan();
+
+    let ids = [h.track("One").id().unwrap(), h.track("Two").id().unwrap()];
+    let edit = TagEdit {
+        album: Some("New".into()),
+        year: Some(2001),
+        ..TagEdit::default()
+    };
+    h.library.edit_tags(&ids, &edit).unwrap();
+
+    for title in ["One", "Two"] {
+        let track = h.track(title);
+        assert_eq!(track.album(), Some("New"));
+        assert_eq!(track.year(), Some(2001));
+        assert_eq!(Track::from_path(track.path()).unwrap().album(), Some("New"));
+    }
+    let albums = h.library.query_all_albums().unwrap();
+    assert!(albums.iter().any(|a| a.name == "New"));
+}
+
+#[test]
+fn delete_tracks_removes_files_and_references() {
+    let mut h = Harness::new();
+    h.add("keep", Format::Flac, album_track("Keep", "Kept", 1));
+    h.add("drop", Format::Ogg, album_track("Drop", "Dropped", 1));
+    h.scan();
+
+    let keep = h.track("Keep");
+    let drop = h.track("Drop");
+    let playlist = h.library.create_playlist("Mix").unwrap();
+    h.library
+        .add_track_to_playlist(playlist, keep.id().unwrap())
+        .unwrap();
+    h.library
+        .add_track_to_playlist(playlist, drop.id().unwrap())
+        .unwrap();
+
+    h.library.delete_tracks(&[drop.id().unwrap()]).unwrap();
+
+    assert!(!drop.path().exists());
+    assert!(keep.path().exists());
+    assert_eq!(h.library.query_track_count().unwrap(), 1);
+    assert_eq!(h.library.get_tracks_in_playlist(playlist).unwrap().len(), 1);
+    let albums = h.library.query_all_albums().unwrap();
+    assert_eq!(albums.len(), 1);
+    assert_eq!(albums[0].name, "Kept");
+}
+
+#[test]
+fn service_scans_and_queries_on_worker() {
+    let h = Harness::new();
+    h.add("One", Format::Flac, album_track("One", "Album", 1));
+    h.add("Two", Format::Mp3, album_track("Two", "Album", 2));
+
+    let data = TempDir::new().unwrap();
+    let service = LibraryService::spawn(&data.path().join("library.db")).unwrap();
+    block_on(service.scan(h.music.path().to_path_buf())).unwrap();
+
+    let tracks = block_on(service.all_tracks()).unwrap();
+    assert_eq!(tracks.len(), 2);
+    let albums = block_on(service.all_albums()).unwrap();
+    assert_eq!(albums.len(), 1);
+    assert!(block_on(service.all_playlists()).unwrap().is_empty());
+
+    let count = block_on(service.run(|library| library.query_track_count())).unwrap();
+    assert_eq!(count, 2);
+}
+
+fn legacy_database(path: &Path) -> rusqlite::Connection {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE tracks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path TEXT NOT NULL UNIQUE,
+            title TEXT,
+            track_artist TEXT,
+            album TEXT,
+            album_artist TEXT,
+            genre TEXT,
+            year INTEGER,
+            track_number INTEGER,
+            disc_number INTEGER,
+            comment TEXT,
+            duration REAL NOT NULL,
+            bit_rate INTEGER,
+            sample_rate INTEGER,
+            bit_depth INTEGER,
+            channels INTEGER,
+            missing INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE playlists (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE playlist_tracks (
+            playlist_id INTEGER NOT NULL,
+            track_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            PRIMARY KEY (playlist_id, track_id),
+            FOREIGN KEY (playlist_id) REFERENCES playlists(id) ON DELETE CASCADE,
+            FOREIGN KEY (track_id) REFERENCES tracks(id) ON DELETE CASCADE
+        );
+        CREATE TABLE track_tempo (
+            track_id INTEGER PRIMARY KEY,
+            speed REAL NOT NULL,
+            semitones REAL NOT NULL,
+            preserve_pitch INTEGER NOT NULL,
+            FOREIGN KEY (track_id) REFERENCES tracks(id) ON DELETE CASCADE
+        );
+        INSERT INTO tracks (id, path, title, track_artist, album, duration, created_at, updated_at)
+            VALUES (3, '/music/legacy.flac', 'Legacy', 'Alpha + Beta', 'Old Album', 1.0, 0, 0),
+                   (7, '/music/other.flac', 'Other', 'Gamma', 'Old Album', 2.0, 0, 0);
+        INSERT INTO playlists (id, name, created_at, updated_at) VALUES (1, 'Mix', 0, 0);
+        INSERT INTO playlist_tracks (playlist_id, track_id, position) VALUES (1, 7, 0), (1, 3, 1);
+        INSERT INTO track_tempo (track_id, speed, semitones, preserve_pitch)
+            VALUES (7, 1.25, -2.0, 1);",
+    )
+    .unwrap();
+    conn
+}
+
+#[test]
+fn migration_rebuild_uses_configured_separators() {
+    let data = TempDir::new().unwrap();
+    let db = data.path().join("library.db");
+    drop(legacy_database(&db));
+
+    let separators = TagSeparators {
+        values: vec!["+".into()],
+        ..TagSeparators::default()
+    };
+    let library = Library::open_with_separators(&db, separators).unwrap();
+    assert_eq!(titles_by_artist(&library, "Alpha"), ["Legacy"]);
+    assert_eq!(titles_by_artist(&library, "Beta"), ["Legacy"]);
+    assert_eq!(library.query_all_albums().unwrap().len(), 1);
+}
+
+#[test]
+fn cue_index_migration_keeps_tracks_playlists_and_tempo() {
+    let data = TempDir::new().unwrap();
+    let db = data.path().join("library.db");
+    drop(legacy_database(&db));
+
+    let library = Library::open(&db).unwrap();
+    let mut ids: Vec<(i64, String)> = library
+        .query_all_tracks()
+        .unwrap()
+        .iter()
+        .map(|t| (t.id().unwrap(), t.title().unwrap().to_string()))
+        .collect();
+    ids.sort();
+    assert_eq!(ids, [(3, "Legacy".into()), (7, "Other".into())]);
+
+    let playlist = &library.get_all_playlists().unwrap()[0];
+    assert_eq!(playlist.name, "Mix");
+    let titles: Vec<_> = library
+        .get_tracks_in_playlist(playlist.id)
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.title().map(str::to_string))
+        .collect();
+    assert_eq!(titles, ["Other", "Legacy"]);
+
+    let tempo = library.query_tempo(7).unwrap().unwrap();
+    assert_eq!(tempo.speed, 1.25);
+    assert_eq!(tempo.semitones, -2.0);
+    assert!(tempo.preserve_pitch);
+    assert!(library.query_tempo(3).unwrap().is_none());
+    drop(library);
+
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let mut check = conn.prepare("PRAGMA foreign_key_check").unwrap();
+    assert!(check.query([]).unwrap().next().unwrap().is_none());
+}
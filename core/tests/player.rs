@@ -0,0 +1,146 @@
+mod fixtures;
+
+use std::{fs, thread, time::Duration};
+
+use tempfile::TempDir;
+use verse_core::{HeadlessOutput, Paths, Player, PlayerBuilder};
+
+fn headless(home: &TempDir) -> (Player, HeadlessOutput) {
+    PlayerBuilder::new()
+        .home(home.path())
+        .build_headless()
+        .unwrap()
+}
+
+fn render_until(output: &HeadlessOutput, player: &Player, done: impl Fn(&Player) -> bool) {
+    let mut rendered = Duration::ZERO;
+    while !done(player) && rendered < Duration::from_secs(5) {
+        output.render(Duration::from_millis(20));
+        rendered += Duration::from_millis(20);
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn players_with_separate_homes_are_isolated() {
+    let (home_a, home_b, music) = (
+        TempDir::new().unwrap(),
+        TempDir::new().unwrap(),
+        TempDir::new().unwrap(),
+    );
+    let (mut a, output) = headless(&home_a);
+    let (mut b, _) = headless(&home_b);
+
+    fixtures::tone(&output, &music.path().join("a/one.wav"), 0.2, 440.0);
+    fixtures::tone(&output, &music.path().join("a/two.wav"), 0.2, 440.0);
+    fixtures::tone(&output, &music.path().join("b/three.wav"), 0.2, 440.0);
+
+    a.populate_library(&music.path().join("a")).unwrap();
+    b.populate_library(&music.path().join("b")).unwrap();
+
+    assert_eq!(a.query_track_count().unwrap(), 2);
+    assert_eq!(b.query_track_count().unwrap(), 1);
+    assert_eq!(a.config().root(), Some(music.path().join("a").as_path()));
+    assert_eq!(b.config().root(), Some(music.path().join("b").as_path()));
+    assert!(home_a.path().join("config.toml").exists());
+    assert!(home_a.path().join("library.db").exists());
+}
+
+#[test]
+fn config_and_data_dirs_can_differ() {
+    let (config, data, music) = (
+        TempDir::new().unwrap(),
+        TempDir::new().unwrap(),
+        TempDir::new().unwrap(),
+    );
+    let paths = Paths::new(config.path(), data.path().join("nested"));
+
+    let (mut player, output) = PlayerBuilder::new()
+        .paths(paths.clone())
+        .build_headless()
+        .unwrap();
+    fixtures::tone(&output, &music.path().join("song.wav"), 0.2, 440.0);
+    player.populate_library(music.path()).unwrap();
+    drop(player);
+
+    assert!(paths.config_file().exists());
+    assert!(paths.database().exists());
+    assert!(!config.path().join("library.db").exists());
+    assert!(
+        !fs::read_to_string(paths.config_file())
+            .unwrap()
+            .contains("song.wav")
+    );
+}
+
+#[test]
+fn settings_persist_across_rebuilds() {
+    let (home, music) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let (mut player, output) = headless(&home);
+    fixtures::tone(&output, &music.path().join("song.wav"), 0.2, 440.0);
+    player.populate_library(music.path()).unwrap();
+    drop(player);
+
+    let (mut player, _) = headless(&home);
+    assert_eq!(player.config().root(), Some(music.path()));
+    player.reload_library().unwrap();
+    assert_eq!(player.query_track_count().unwrap(), 1);
+}
+
+#[test]
+fn queue_advances_when_track_finishes() {
+    let (home, music) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let (mut player, output) = headless(&home);
+    fixtures::tone(&output, &music.path().join("first.wav"), 0.3, 440.0);
+    fixtures::tone(&output, &music.path().join("second.wav"), 0.3, 880.0);
+    player.populate_library(music.path()).unwrap();
+    player.queue_library().unwrap();
+
+    let first = player.queue().current_id();
+    player.play().unwrap();
+
+    let mut rendered = Duration::ZERO;
+    while player.queue().current_id() == first && rendered < Duration::from_secs(5) {
+        output.render(Duration::from_millis(20));
+        rendered += Duration::from_millis(20);
+        player.update().unwrap();
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_ne!(player.queue().current_id(), first);
+    assert_eq!(player.queue().history().len(), 1);
+    assert!(player.is_playing());
+}
+
+#[test]
+fn chapter_navigation_seeks_within_track() {
+    let (home, music) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let (mut player, output) = headless(&home);
+    let path = music.path().join("book.wav");
+    fixtures::tone(&output, &path, 3.0, 440.0);
+    fixtures::write_chapters(&path, &[("One", 0), ("Two", 1000), ("Three", 2000)]);
+    player.populate_library(music.path()).unwrap();
+    player.queue_library().unwrap();
+    player.play().unwrap();
+    render_until(&output, &player, |p| p.position() > 0.0);
+
+    assert_eq!(player.chapters().len(), 3);
+    assert_eq!(player.current_chapter(), Some(0));
+
+    player.next_chapter();
+    render_until(&output, &player, |p| p.current_chapter() == Some(1));
+    assert_eq!(player.current_chapter(), Some(1));
+
+    player.jump_to_chapter(2);
+    render_until(&output, &player, |p| p.current_chapter() == Some(2));
+    assert_eq!(player.current_chapter(), Some(2));
+
+    player.previous_chapter();
+    render_until(&output, &player, |p| p.current_chapter() == Some(1));
+    assert_eq!(player.current_chapter(), Some(1));
+
+    player.jump_to_chapter(2);
+    render_until(&output, &player, |p| p.current_chapter() == Some(2));
+    player.next_chapter();
+    assert_eq!(player.current_chapter(), Some(2));
+}
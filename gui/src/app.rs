@@ -4,19 +4,30 @@ use iced::widget::pane_grid::{self, PaneGrid};
 use iced::widget::{column, container, space};
 use iced::window;
 use iced::{Element, Event, Length, Subscription, Task, Theme};
-use verse_core::{Album, Player, Playlist, Track};
-use std::cell::RefCell;
-use std::path::PathBuf;
+use verse_core::{
+    Album, Artist, Decade, EqBand, EqPreset, FilterKind, Folder, Genre, Library, LibraryError,
+    MAX_PARAMETRIC_BANDS, Paths, Player, Playlist, Tempo, Track, Work,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::art_cache::ArtCache;
 use crate::components::bottom_bar::{self, Message as BottomBarMessage};
+use crate::components::confirm_delete::confirm_delete;
 use crate::components::preferences::{self as preferences, PreferenceMessage};
+use crate::components::tag_editor::{TagEditor, TagEditorMessage};
 use crate::config::{Config, LayoutAxis, LayoutNode};
+use crate::file_manager;
 use crate::media_controls::MediaSession;
 use crate::pane::{Pane, PaneType};
 use crate::panes::collections::{CollectionsPane, ExpandedItem};
-use crate::panes::{CollectionsMessage, ControlsMessage};
+use crate::panes::library::resize_width;
+use crate::panes::{
+    CollectionsMessage, ControlsMessage, EqualizerMessage, EqualizerPane, FoldersMessage,
+    FoldersPane, LibraryMessage, LibraryPane, Navigation, QueueMessage, QueuePane, TimelineMessage,
+    TrackInfoMessage,
+};
 use crate::styles::set_radius;
 use crate::window_handle;
 
@@ -33,14 +44,24 @@ pub struct App {
     hovered_track: Option<i64>,
     hovered_card: Option<(bool, i64)>,
     media_session: Option<MediaSession>,
-    cached_tracks: RefCell<Option<Vec<Track>>>,
-    cached_albums: RefCell<Option<Vec<Album>>>,
-    cached_playlists: RefCell<Option<Vec<Playlist>>>,
+    cached_tracks: Option<Vec<Track>>,
+    cached_albums: Option<Vec<Album>>,
+    cached_artists: Option<Vec<Artist>>,
+    cached_composers: Option<Vec<Artist>>,
+    cached_playlists: Option<Vec<Playlist>>,
+    cached_genres: Option<Vec<Genre>>,
+    cached_decades: Option<Vec<Decade>>,
+    cached_folders: Option<Vec<Folder>>,
     art_cache: ArtCache,
     is_minimized: bool,
     config: Config,
+    config_path: PathBuf,
     editing_config: Option<Config>,
     confirming_clear: bool,
+    output_devices: Vec<String>,
+    modifiers: keyboard::Modifiers,
+    tag_editor: Option<TagEditor>,
+    confirming_delete: Option<Vec<i64>>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,22 +73,69 @@ pub enum Message {
     Dragged(pane_grid::DragEvent),
     Resized(pane_grid::ResizeEvent),
     Controls(ControlsMessage),
+    Timeline(TimelineMessage),
+    TrackInfo(TrackInfoMessage),
+    Equalizer(EqualizerMessage),
     Collections(CollectionsMessage),
+    Library(LibraryMessage),
+    Queue(QueueMessage),
+    Folders(FoldersMessage),
     LibraryPathSelected(Option<PathBuf>),
+    LibraryScanned,
+    LibraryLoaded(
+        Vec<Track>,
+        Vec<Album>,
+        Vec<Artist>,
+        Vec<Artist>,
+        Vec<Playlist>,
+    ),
+    PlaylistsLoaded(Vec<Playlist>),
+    GenresLoaded(Vec<Genre>, Vec<Decade>),
+    FoldersLoaded(Vec<Folder>),
+    TracksFetched(Enqueue, Vec<i64>),
+    ExpandedTracksLoaded(ExpandedItem, Vec<Track>),
+    ExpandedAlbumsLoaded(ExpandedItem, Vec<Album>),
+    ExpandedWorksLoaded(ExpandedItem, Vec<Work>),
+    HeadphoneProfileSelected(Option<PathBuf>),
     SetLibrary,
     PaneTypeChanged(pane_grid::Pane, PaneType),
     BottomBar(bottom_bar::Message),
     ClearQueue,
-    RemoveFromQueue(usize),
+    RemoveFromQueue(Vec<usize>),
     MoveToQueueFront(usize),
     PlayTrack(i64),
     QueueLibrary,
     QueueBack(i64),
     QueueFront(i64),
-    PlayAlbum(String, Option<String>),
-    QueueAlbumNext(String, Option<String>),
-    QueueAlbumBack(String, Option<String>),
-    AddTrackToPlaylist(i64, i64),
+    PlayTracks(Vec<i64>),
+    QueueTracksBack(Vec<i64>),
+    QueueTracksFront(Vec<i64>),
+    AddTracksToPlaylist(Vec<i64>, i64),
+    EditTags(Vec<i64>),
+    TagEditor(TagEditorMessage),
+    RevealTracks(Vec<i64>),
+    DeleteTracks(Vec<i64>),
+    ConfirmDeleteTracks,
+    CancelDeleteTracks,
+    TracksChanged,
+    PlayAlbum(i64),
+    QueueAlbumNext(i64),
+    QueueAlbumBack(i64),
+    PlayArtist(i64),
+    QueueArtistNext(i64),
+    QueueArtistBack(i64),
+    PlayFolder(PathBuf),
+    QueueFolderNext(PathBuf),
+    QueueFolderBack(PathBuf),
+    PlayWork(i64, String),
+    QueueWorkNext(i64, String),
+    QueueWorkBack(i64, String),
+    PlayGenre(String),
+    QueueGenreNext(String),
+    QueueGenreBack(String),
+    PlayDecade(u32),
+    QueueDecadeNext(u32),
+    QueueDecadeBack(u32),
     CardHovered(bool, i64),
     CardUnhovered,
     TrackHovered(i64),
@@ -77,9 +145,17 @@ pub enum Message {
     Event(Event),
 }
 
-impl Default for App {
-    fn default() -> Self {
-        let config = Config::load();
+#[derive(Debug, Clone, Copy)]
+pub enum Enqueue {
+    Play,
+    Next,
+    Back,
+}
+
+impl App {
+    pub fn new(paths: Paths, legacy_config: Option<PathBuf>) -> (Self, Task<Message>) {
+        let config_path = paths.config_dir.join("gui.toml");
+        let config = Config::load_or_migrate(&config_path, legacy_config.as_deref());
         set_radius(config.rounded);
 
         let default_layout = pane_grid::Configuration::Pane(Pane::new(PaneType::Library));
@@ -95,10 +171,13 @@ impl Default for App {
             .min(layout_presets.len().saturating_sub(1));
 
         let panes = pane_grid::State::with_configuration(layout_presets[current_preset].clone());
-        let mut player = Player::new().expect("Failed to initialize player");
+        let mut player = Player::builder()
+            .paths(paths)
+            .build()
+            .expect("Failed to initialize player");
         player.set_volume(config.volume);
 
-        Self {
+        let mut app = Self {
             panes,
             focus: None,
             edit_mode: false,
@@ -111,74 +190,192 @@ impl Default for App {
             hovered_track: None,
             hovered_card: None,
             media_session: None,
-            cached_tracks: RefCell::new(None),
-            cached_albums: RefCell::new(None),
-            cached_playlists: RefCell::new(None),
+            cached_tracks: None,
+            cached_albums: None,
+            cached_artists: None,
+            cached_composers: None,
+            cached_playlists: None,
+            cached_genres: None,
+            cached_decades: None,
+            cached_folders: None,
             art_cache: ArtCache::new(),
             is_minimized: false,
             config,
+            config_path,
             editing_config: None,
             confirming_clear: false,
-        }
+            output_devices: Vec::new(),
+            modifiers: keyboard::Modifiers::default(),
+            tag_editor: None,
+            confirming_delete: None,
+        };
+        let task = app.invalidate_library_cache();
+        (app, task)
     }
-}
 
-impl App {
     pub fn theme(&self) -> Theme {
         self.config.theme.clone()
     }
 
-    fn invalidate_library_cache(&mut self) {
-        *self.cached_tracks.borrow_mut() = None;
-        *self.cached_albums.borrow_mut() = None;
-        *self.cached_playlists.borrow_mut() = None;
+    fn invalidate_library_cache(&mut self) -> Task<Message> {
+        self.clear_library_cache();
+        self.load_library()
+    }
+
+    fn clear_library_cache(&mut self) {
+        self.cached_tracks = None;
+        self.cached_albums = None;
+        self.cached_artists = None;
+        self.cached_composers = None;
+        self.cached_playlists = None;
+        self.cached_genres = None;
+        self.cached_decades = None;
+        self.cached_folders = None;
         self.art_cache.invalidate();
         for (_, pane) in self.panes.iter_mut() {
             pane.invalidate_cache();
         }
     }
 
-    fn invalidate_playlist_cache(&mut self) {
-        *self.cached_playlists.borrow_mut() = None;
+    fn invalidate_playlist_cache(&mut self) -> Task<Message> {
         for (_, pane) in self.panes.iter_mut() {
             if let Some(cp) = pane.content.as_any_mut().downcast_mut::<CollectionsPane>() {
-                cp.playlist_art_keys.clear();
-                cp.playlists_initialized = false;
                 if matches!(cp.expanded, Some(ExpandedItem::Playlist(_))) {
                     cp.expanded = None;
-                    cp.expanded_tracks.clear();
+                    cp.expanded_tracks = None;
                     cp.expanded_cover = None;
                 }
             }
         }
+        let service = self.player.library_service();
+        Task::perform(
+            async move { service.all_playlists().await.unwrap_or_default() },
+            Message::PlaylistsLoaded,
+        )
     }
 
-    fn ensure_cached_playlists(&self) {
-        let mut cache = self.cached_playlists.borrow_mut();
-        if cache.is_none() {
-            *cache = Some(self.player.get_all_playlists().unwrap_or_default());
-        }
+    fn load_library(&self) -> Task<Message> {
+        Task::batch([self.load_tracks(), self.load_genres(), self.load_folders()])
     }
 
-    fn ensure_cached_tracks(&self) {
-        let mut cache = self.cached_tracks.borrow_mut();
-        if cache.is_none() {
-            let mut tracks = self.player.query_all_tracks().unwrap_or_default();
-            tracks.sort_by(|a, b| {
-                a.track_artist()
-                    .unwrap_or("-")
-                    .cmp(b.track_artist().unwrap_or("-"))
-                    .then_with(|| a.album().unwrap_or("-").cmp(b.album().unwrap_or("-")))
-                    .then_with(|| a.title().unwrap_or("-").cmp(b.title().unwrap_or("-")))
-            });
-            *cache = Some(tracks);
-        }
+    fn load_folders(&self) -> Task<Message> {
+        let service = self.player.library_service();
+        let roots: Vec<PathBuf> = self
+            .player
+            .library_root()
+            .map(Path::to_path_buf)
+            .into_iter()
+            .collect();
+        Task::perform(
+            service.run(move |library| {
+                let roots: Vec<&Path> = roots.iter().map(PathBuf::as_path).collect();
+                library.query_folder_tree(&roots)
+            }),
+            |folders| Message::FoldersLoaded(folders.unwrap_or_default()),
+        )
+    }
+
+    fn load_genres(&self) -> Task<Message> {
+        let service = self.player.library_service();
+        Task::perform(
+            async move {
+                let genres = service.genres().await.unwrap_or_default();
+                let decades = service.decades().await.unwrap_or_default();
+                (genres, decades)
+            },
+            |(genres, decades)| Message::GenresLoaded(genres, decades),
+        )
+    }
+
+    fn load_tracks(&self) -> Task<Message> {
+        let service = self.player.library_service();
+        Task::perform(
+            async move {
+                let mut tracks = service.all_tracks().await.unwrap_or_default();
+                tracks.sort_by(|a, b| {
+                    a.track_artist()
+                        .unwrap_or("-")
+                        .cmp(b.track_artist().unwrap_or("-"))
+                        .then_with(|| a.album().unwrap_or("-").cmp(b.album().unwrap_or("-")))
+                        .then_with(|| a.title().unwrap_or("-").cmp(b.title().unwrap_or("-")))
+                });
+                let albums = service.all_albums().await.unwrap_or_default();
+                let artists = service.all_artists().await.unwrap_or_default();
+                let composers = service.composers().await.unwrap_or_default();
+                let playlists = service.all_playlists().await.unwrap_or_default();
+                (tracks, albums, artists, composers, playlists)
+            },
+            |(tracks, albums, artists, composers, playlists)| {
+                Message::LibraryLoaded(tracks, albums, artists, composers, playlists)
+            },
+        )
+    }
+
+    fn fetch_tracks<F>(&self, enqueue: Enqueue, query: F) -> Task<Message>
+    where
+        F: FnOnce(&mut Library) -> Result<Vec<Track>, LibraryError> + Send + 'static,
+    {
+        let service = self.player.library_service();
+        Task::perform(service.run(query), move |tracks| {
+            let track_ids = tracks
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|t| t.id())
+                .collect();
+            Message::TracksFetched(enqueue, track_ids)
+        })
+    }
+
+    fn expand_tracks<F>(&self, key: ExpandedItem, query: F) -> Task<Message>
+    where
+        F: FnOnce(&mut Library) -> Result<Vec<Track>, LibraryError> + Send + 'static,
+    {
+        let service = self.player.library_service();
+        Task::perform(service.run(query), move |tracks| {
+            Message::ExpandedTracksLoaded(key.clone(), tracks.unwrap_or_default())
+        })
+    }
+
+    fn expand_albums<F>(&self, key: ExpandedItem, query: F) -> Task<Message>
+    where
+        F: FnOnce(&mut Library) -> Result<Vec<Album>, LibraryError> + Send + 'static,
+    {
+        let service = self.player.library_service();
+        Task::perform(service.run(query), move |albums| {
+            Message::ExpandedAlbumsLoaded(key.clone(), albums.unwrap_or_default())
+        })
+    }
+
+    fn expand_works(&self, key: ExpandedItem, composer_id: i64) -> Task<Message> {
+        let service = self.player.library_service();
+        Task::perform(
+            service.run(move |library| library.query_works_by_composer(composer_id)),
+            move |works| Message::ExpandedWorksLoaded(key.clone(), works.unwrap_or_default()),
+        )
     }
 
-    fn ensure_cached_albums(&self) {
-        let mut cache = self.cached_albums.borrow_mut();
-        if cache.is_none() {
-            *cache = Some(self.player.query_all_albums().unwrap_or_default());
+    fn scan_library(&mut self, root: PathBuf) -> Task<Message> {
+        self.clear_library_cache();
+        let service = self.player.library_service();
+        Task::perform(
+            async move {
+                let _ = service.scan(root).await;
+            },
+            |_| Message::LibraryScanned,
+        )
+    }
+
+    fn library_loaded(&mut self) {
+        let (Some(tracks), Some(albums), Some(artists), Some(playlists)) = (
+            &self.cached_tracks,
+            &self.cached_albums,
+            &self.cached_artists,
+            &self.cached_playlists,
+        ) else {
+            return;
+        };
+        for (_, pane) in self.panes.iter_mut() {
+            pane.library_loaded(tracks, albums, artists, playlists);
         }
     }
 
@@ -194,7 +391,7 @@ impl App {
             .map(pane_config_to_node)
             .collect();
         self.config.current_layout = self.current_preset;
-        self.config.save();
+        self.config.save(&self.config_path);
     }
 
     fn layout_to_configuration(
@@ -216,6 +413,25 @@ impl App {
         }
     }
 
+    fn tracks_by_id(&self, track_ids: &[i64]) -> Vec<&Track> {
+        let wanted: HashSet<i64> = track_ids.iter().copied().collect();
+        self.cached_tracks
+            .iter()
+            .flatten()
+            .filter(|t| t.id().is_some_and(|id| wanted.contains(&id)))
+            .collect()
+    }
+
+    fn edit_equalizer(&mut self, edit: impl FnOnce(&mut EqPreset)) {
+        let mut preset = self
+            .player
+            .equalizer()
+            .cloned()
+            .unwrap_or_else(|| EqPreset::flat("Custom"));
+        edit(&mut preset);
+        self.player.set_equalizer(Some(preset));
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::QueueLibrary => {
@@ -296,6 +512,8 @@ impl App {
                 if let Some(session) = &mut self.media_session {
                     if self.player.is_playing() {
                         session.set_playback(souvlaki::MediaPlayback::Playing { progress: None });
+                    } else if self.player.is_stopped() {
+                        session.set_playback(souvlaki::MediaPlayback::Stopped);
                     } else {
                         session.set_playback(souvlaki::MediaPlayback::Paused { progress: None });
                     }
@@ -312,12 +530,91 @@ impl App {
             }
             Message::LibraryPathSelected(path) => {
                 if let Some(path) = path {
-                    let _ = self.player.populate_library(&path);
-                    self.invalidate_library_cache();
+                    if self.player.set_library_root(&path).is_ok() {
+                        return self.scan_library(path);
+                    }
+                }
+            }
+            Message::LibraryScanned => {
+                return self.load_library();
+            }
+            Message::LibraryLoaded(tracks, albums, artists, composers, playlists) => {
+                self.cached_tracks = Some(tracks);
+                self.cached_albums = Some(albums);
+                self.cached_artists = Some(artists);
+                self.cached_composers = Some(composers);
+                self.cached_playlists = Some(playlists);
+                self.library_loaded();
+            }
+            Message::PlaylistsLoaded(playlists) => {
+                self.cached_playlists = Some(playlists);
+                self.library_loaded();
+            }
+            Message::GenresLoaded(genres, decades) => {
+                self.cached_genres = Some(genres);
+                self.cached_decades = Some(decades);
+            }
+            Message::FoldersLoaded(folders) => {
+                for (_, pane) in self.panes.iter_mut() {
+                    if let Some(fp) = pane.content.as_any_mut().downcast_mut::<FoldersPane>() {
+                        fp.tree_loaded(&folders);
+                    }
+                }
+                self.cached_folders = Some(folders);
+            }
+            Message::TracksFetched(enqueue, track_ids) => match enqueue {
+                Enqueue::Play => {
+                    if !track_ids.is_empty() {
+                        self.player.clear_queue();
+                        self.player.queue_many(track_ids.into_iter());
+                        let _ = self.player.play();
+                    }
+                }
+                Enqueue::Next => self.player.queue_many_front(track_ids.into_iter()),
+                Enqueue::Back => self.player.queue_many(track_ids.into_iter()),
+            },
+            Message::ExpandedTracksLoaded(key, tracks) => {
+                for (_, pane) in self.panes.iter_mut() {
+                    if let Some(cp) = pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                        && cp.expanded.as_ref() == Some(&key)
+                    {
+                        cp.expanded_tracks = Some(tracks.clone());
+                    }
+                }
+            }
+            Message::ExpandedWorksLoaded(key, works) => {
+                for (_, pane) in self.panes.iter_mut() {
+                    if let Some(cp) = pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                        && cp.expanded.as_ref() == Some(&key)
+                    {
+                        cp.expanded_works = works.clone();
+                    }
+                }
+            }
+            Message::ExpandedAlbumsLoaded(key, albums) => {
+                for (_, pane) in self.panes.iter_mut() {
+                    if let Some(cp) = pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                        && cp.expanded.as_ref() == Some(&key)
+                    {
+                        if matches!(key, ExpandedItem::Genre(_) | ExpandedItem::Decade(_)) {
+                            cp.expanded_cover = albums
+                                .first()
+                                .and_then(|album| cp.album_art_keys.get(&album.id))
+                                .map(|(tid, path)| (*tid, path.clone()));
+                        }
+                        cp.expanded_albums = albums.clone();
+                    }
+                }
+            }
+            Message::HeadphoneProfileSelected(path) => {
+                if let Some(path) = path {
+                    let _ = self.player.import_headphone_profile(&path);
                 }
             }
             Message::SetLibrary => {
-                if self.player.reload_library().is_err() {
+                if let Some(root) = self.player.library_root().map(Path::to_path_buf) {
+                    return self.scan_library(root);
+                } else {
                     return Task::perform(
                         async {
                             rfd::AsyncFileDialog::new()
@@ -328,8 +625,6 @@ impl App {
                         },
                         Message::LibraryPathSelected,
                     );
-                } else {
-                    self.invalidate_library_cache();
                 }
             }
             Message::Controls(msg) => match msg {
@@ -350,7 +645,7 @@ impl App {
                     self.volume = vol;
                     self.player.set_volume(vol);
                     self.config.volume = vol;
-                    self.config.save();
+                    self.config.save(&self.config_path);
                 }
                 ControlsMessage::ToggleMute => {
                     if self.volume > 0.0 {
@@ -361,7 +656,7 @@ impl App {
                     }
                     self.player.set_volume(self.volume);
                     self.config.volume = self.volume;
-                    self.config.save();
+                    self.config.save(&self.config_path);
                 }
                 ControlsMessage::SeekChanged(pos) => {
                     self.seeking_position = Some(pos);
@@ -377,14 +672,135 @@ impl App {
                 ControlsMessage::CycleLoopMode => {
                     self.player.cycle_loop_mode();
                 }
+                ControlsMessage::SetStopAfter(stop_after) => {
+                    self.player.set_stop_after(Some(stop_after));
+                }
+                ControlsMessage::StartSleepTimer(minutes) => {
+                    self.player
+                        .start_sleep_timer(Duration::from_secs(minutes * 60));
+                }
+                ControlsMessage::CancelPendingStop => {
+                    self.player.cancel_pending_stop();
+                }
+                ControlsMessage::SpeedChanged(speed) => {
+                    let tempo = self.player.tempo();
+                    self.player.set_tempo(Tempo {
+                        speed: speed as f64,
+                        ..tempo
+                    });
+                }
+                ControlsMessage::Transpose(semitones) => {
+                    let tempo = self.player.tempo();
+                    self.player.set_tempo(Tempo {
+                        semitones: tempo.semitones + semitones,
+                        ..tempo
+                    });
+                }
+                ControlsMessage::TogglePreservePitch => {
+                    let tempo = self.player.tempo();
+                    self.player.set_tempo(Tempo {
+                        preserve_pitch: !tempo.preserve_pitch,
+                        ..tempo
+                    });
+                }
+                ControlsMessage::ResetTempo => {
+                    self.player.set_tempo(Tempo::default());
+                }
+                ControlsMessage::RememberTempo(scope) => {
+                    let _ = self.player.remember_tempo(scope);
+                }
+                ControlsMessage::ForgetTempo(scope) => {
+                    let _ = self.player.forget_tempo(scope);
+                }
+            },
+            Message::Timeline(msg) => match msg {
+                TimelineMessage::Seek(pos) => {
+                    self.player.seek(pos);
+                }
+                TimelineMessage::SetLoop(region) => {
+                    self.player.set_loop_region(Some(region));
+                }
+                TimelineMessage::ClearLoop => {
+                    self.player.clear_loop_region();
+                }
+            },
+            Message::TrackInfo(msg) => match msg {
+                TrackInfoMessage::Previous => {
+                    self.player.previous_chapter();
+                }
+                TrackInfoMessage::Next => {
+                    self.player.next_chapter();
+                }
+                TrackInfoMessage::JumpTo(index) => {
+                    self.player.jump_to_chapter(index);
+                }
+            },
+            Message::Equalizer(msg) => match msg {
+                EqualizerMessage::SelectPreset(name) => {
+                    let _ = self.player.select_eq_preset(name.as_deref());
+                }
+                EqualizerMessage::PreampChanged(gain) => {
+                    self.edit_equalizer(|preset| preset.preamp_db = gain);
+                }
+                EqualizerMessage::GraphicChanged(index, gain) => {
+                    self.edit_equalizer(|preset| preset.graphic[index] = gain);
+                }
+                EqualizerMessage::BandChanged(index, band) => {
+                    self.edit_equalizer(|preset| {
+                        if let Some(b) = preset.bands.get_mut(index) {
+                            *b = band;
+                        }
+                    });
+                }
+                EqualizerMessage::AddBand => {
+                    self.edit_equalizer(|preset| {
+                        if preset.bands.len() < MAX_PARAMETRIC_BANDS {
+                            preset
+                                .bands
+                                .push(EqBand::new(FilterKind::Peaking, 1000.0));
+                        }
+                    });
+                }
+                EqualizerMessage::RemoveBand(index) => {
+                    self.edit_equalizer(|preset| {
+                        if index < preset.bands.len() {
+                            preset.bands.remove(index);
+                        }
+                    });
+                }
+                EqualizerMessage::PresetNameChanged(name) => {
+                    for (_, pane) in self.panes.iter_mut() {
+                        if let Some(ep) = pane.content.as_any_mut().downcast_mut::<EqualizerPane>() {
+                            ep.preset_name = name.clone();
+                        }
+                    }
+                }
+                EqualizerMessage::SavePreset(name) => {
+                    if !name.is_empty() {
+                        if self.player.equalizer().is_none() {
+                            self.player.set_equalizer(Some(EqPreset::flat(name.as_str())));
+                        }
+                        let _ = self.player.save_eq_preset(&name);
+                        for (_, pane) in self.panes.iter_mut() {
+                            if let Some(ep) = pane.content.as_any_mut().downcast_mut::<EqualizerPane>() {
+                                ep.preset_name.clear();
+                            }
+                        }
+                    }
+                }
+                EqualizerMessage::DeletePreset(name) => {
+                    let _ = self.player.delete_eq_preset(&name);
+                }
             },
             Message::PaneTypeChanged(pane_id, new_type) => {
                 if let Some(pane) = self.panes.get_mut(pane_id) {
                     pane.set_content(new_type);
                 }
+                self.library_loaded();
             }
             Message::OpenPreferences => {
                 self.editing_config = Some(self.config.clone());
+                self.output_devices = self.player.output_devices();
             }
             Message::Preference(msg) => match msg {
                 PreferenceMessage::SetTheme(t) => {
@@ -402,12 +818,18 @@ impl App {
                         c.preset_indicator = v;
                     }
                 }
+                PreferenceMessage::SetWorkTitles(v) => {
+                    if let Some(c) = &mut self.editing_config {
+                        c.work_titles = v;
+                    }
+                }
                 PreferenceMessage::Save => {
                     if let Some(mut c) = self.editing_config.take() {
                         c.layouts = self.config.layouts.clone();
                         c.current_layout = self.config.current_layout;
+                        c.library_columns = self.config.library_columns.clone();
                         set_radius(c.rounded);
-                        c.save();
+                        c.save(&self.config_path);
                         self.config = c;
                         self.confirming_clear = false;
                     }
@@ -440,15 +862,41 @@ impl App {
                 PreferenceMessage::CancelClearLibrary => {
                     self.confirming_clear = false;
                 }
+                PreferenceMessage::SetOutputDevice(name) => {
+                    let _ = self.player.set_output_device(name.as_deref());
+                }
+                PreferenceMessage::SetHeadphoneProfile(name) => {
+                    let _ = self.player.select_headphone_profile(name.as_deref());
+                }
+                PreferenceMessage::ImportHeadphoneProfile => {
+                    return Task::perform(
+                        async {
+                            rfd::AsyncFileDialog::new()
+                                .set_title("Select Headphone Correction Profile")
+                                .add_filter("EqualizerAPO profile", &["txt"])
+                                .pick_file()
+                                .await
+                                .map(|handle| handle.path().to_path_buf())
+                        },
+                        Message::HeadphoneProfileSelected,
+                    );
+                }
+                PreferenceMessage::RemoveHeadphoneProfile(name) => {
+                    let _ = self.player.remove_headphone_profile(&name);
+                }
+                PreferenceMessage::SetAudioSettings(settings) => {
+                    let _ = self.player.set_audio_settings(settings);
+                }
                 PreferenceMessage::ConfirmClearLibrary => {
                     self.confirming_clear = false;
                     let _ = self.player.clear_library();
-                    self.invalidate_library_cache();
+                    return self.invalidate_library_cache();
                 }
             },
             Message::BottomBar(msg) => match msg {
                 BottomBarMessage::OpenPreferences => {
                     self.editing_config = Some(self.config.clone());
+                    self.output_devices = self.player.output_devices();
                 }
                 BottomBarMessage::ToggleEditMode => {
                     if self.edit_mode {
@@ -494,8 +942,8 @@ impl App {
             Message::ClearQueue => {
                 self.player.clear_queue();
             }
-            Message::RemoveFromQueue(index) => {
-                self.player.remove_from_queue(index);
+            Message::RemoveFromQueue(indices) => {
+                self.player.remove_many_from_queue(&indices);
             }
             Message::MoveToQueueFront(index) => {
                 self.player.move_to_queue_front(index);
@@ -534,6 +982,80 @@ impl App {
                     self.player.queue_front(track_id);
                 }
             }
+            Message::PlayTracks(track_ids) => {
+                if !track_ids.is_empty() {
+                    self.player.queue_many_front(track_ids.into_iter());
+                    let _ = self.player.start_next();
+                }
+            }
+            Message::QueueTracksBack(track_ids) => {
+                self.player.queue_many(track_ids.into_iter());
+            }
+            Message::QueueTracksFront(track_ids) => {
+                self.player.queue_many_front(track_ids.into_iter());
+            }
+            Message::AddTracksToPlaylist(track_ids, playlist_id) => {
+                for track_id in track_ids {
+                    let _ = self.player.add_track_to_playlist(playlist_id, track_id);
+                }
+                return self.invalidate_playlist_cache();
+            }
+            Message::EditTags(track_ids) => {
+                let tracks = self.tracks_by_id(&track_ids);
+                if !tracks.is_empty() {
+                    self.tag_editor = Some(TagEditor::new(&tracks));
+                }
+            }
+            Message::TagEditor(msg) => match msg {
+                TagEditorMessage::Changed(field, value) => {
+                    if let Some(editor) = &mut self.tag_editor {
+                        editor.set(field, value);
+                    }
+                }
+                TagEditorMessage::Save => {
+                    if let Some(editor) = self.tag_editor.take() {
+                        let edit = editor.edit();
+                        if !edit.is_empty() {
+                            let service = self.player.library_service();
+                            return Task::perform(
+                                service.edit_tags(editor.track_ids, edit),
+                                |_| Message::TracksChanged,
+                            );
+                        }
+                    }
+                }
+                TagEditorMessage::Cancel => {
+                    self.tag_editor = None;
+                }
+            },
+            Message::RevealTracks(track_ids) => {
+                let mut folders = HashSet::new();
+                for track in self.tracks_by_id(&track_ids) {
+                    if folders.insert(track.path().parent().map(Path::to_path_buf)) {
+                        let _ = file_manager::reveal(track.path());
+                    }
+                }
+            }
+            Message::DeleteTracks(track_ids) => {
+                if !track_ids.is_empty() {
+                    self.confirming_delete = Some(track_ids);
+                }
+            }
+            Message::ConfirmDeleteTracks => {
+                if let Some(track_ids) = self.confirming_delete.take() {
+                    self.player.remove_tracks_from_queue(&track_ids);
+                    let service = self.player.library_service();
+                    return Task::perform(service.delete_tracks(track_ids), |_| {
+                        Message::TracksChanged
+                    });
+                }
+            }
+            Message::CancelDeleteTracks => {
+                self.confirming_delete = None;
+            }
+            Message::TracksChanged => {
+                return self.load_library();
+            }
             Message::Collections(msg) => {
                 for (_, pane) in self.panes.iter_mut() {
                     if let Some(cp) = pane.content.as_any_mut().downcast_mut::<CollectionsPane>() {
@@ -558,118 +1080,380 @@ impl App {
                     CollectionsMessage::Confirm(name) => {
                         if !name.is_empty() {
                             let _ = self.player.create_playlist(&name);
-                            self.invalidate_playlist_cache();
+                            return self.invalidate_playlist_cache();
                         }
                     }
                     CollectionsMessage::DeletePlaylist(id) => {
                         let _ = self.player.delete_playlist(id);
-                        self.invalidate_playlist_cache();
+                        return self.invalidate_playlist_cache();
                     }
                     CollectionsMessage::PlayPlaylist(id) => {
-                        if let Ok(tracks) = self.player.get_tracks_in_playlist(id) {
-                            self.player.clear_queue();
-                            self.player.queue_many(tracks.iter().filter_map(|t| t.id()));
-                            let _ = self.player.play();
-                        }
+                        return self.fetch_tracks(Enqueue::Play, move |library| {
+                            library.get_tracks_in_playlist(id)
+                        });
                     }
                     CollectionsMessage::QueuePlaylistNext(id) => {
-                        if let Ok(tracks) = self.player.get_tracks_in_playlist(id) {
-                            self.player
-                                .queue_many_front(tracks.iter().filter_map(|t| t.id()));
-                        }
+                        return self.fetch_tracks(Enqueue::Next, move |library| {
+                            library.get_tracks_in_playlist(id)
+                        });
                     }
                     CollectionsMessage::QueuePlaylistBack(id) => {
-                        if let Ok(tracks) = self.player.get_tracks_in_playlist(id) {
-                            self.player.queue_many(tracks.iter().filter_map(|t| t.id()));
-                        }
-                    }
-                    CollectionsMessage::ToggleAlbum(name, artist) => {
-                        let new_key = ExpandedItem::Album(name.clone(), artist.clone());
-                        let fetched = self
-                            .player
-                            .query_tracks_by_album(&name, artist.as_deref())
-                            .unwrap_or_default();
-                        let album_id = {
-                            let cache = self.cached_albums.borrow();
-                            cache
-                                .as_ref()
-                                .and_then(|albums| albums.iter().find(|a| a.name == name))
-                                .map(|a| a.id)
-                        };
+                        return self.fetch_tracks(Enqueue::Back, move |library| {
+                            library.get_tracks_in_playlist(id)
+                        });
+                    }
+                    CollectionsMessage::ToggleAlbum(album_id) => {
+                        let new_key = ExpandedItem::Album(album_id);
+                        let mut expanding = false;
+                        for (_, pane) in self.panes.iter_mut() {
+                            if let Some(cp) =
+                                pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                            {
+                                if cp.expanded.as_ref() == Some(&new_key) {
+                                    cp.expanded = None;
+                                    cp.expanded_tracks = None;
+                                    cp.expanded_cover = None;
+                                } else {
+                                    cp.expanded = Some(new_key.clone());
+                                    cp.expanded_tracks = None;
+                                    cp.expanded_cover = cp
+                                        .album_art_keys
+                                        .get(&album_id)
+                                        .map(|(tid, path)| (*tid, path.clone()));
+                                    expanding = true;
+                                }
+                            }
+                        }
+                        if expanding {
+                            return self.expand_tracks(new_key, move |library| {
+                                library.query_tracks_by_album_id(album_id)
+                            });
+                        }
+                    }
+                    CollectionsMessage::ToggleArtist(artist_id) => {
+                        let new_key = ExpandedItem::Artist(artist_id);
+                        let mut expanding = false;
+                        for (_, pane) in self.panes.iter_mut() {
+                            if let Some(cp) =
+                                pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                            {
+                                if cp.expanded.as_ref() == Some(&new_key) {
+                                    cp.expanded = None;
+                                    cp.expanded_albums.clear();
+                                    cp.expanded_cover = None;
+                                } else {
+                                    cp.expanded = Some(new_key.clone());
+                                    cp.expanded_tracks = None;
+                                    cp.expanded_albums.clear();
+                                    cp.expanded_cover = cp
+                                        .artist_art_keys
+                                        .get(&artist_id)
+                                        .map(|(tid, path)| (*tid, path.clone()));
+                                    expanding = true;
+                                }
+                            }
+                        }
+                        if expanding {
+                            return self.expand_albums(new_key, move |library| {
+                                library.query_albums_by_artist(artist_id)
+                            });
+                        }
+                    }
+                    CollectionsMessage::ToggleComposer(composer_id) => {
+                        let new_key = ExpandedItem::Composer(composer_id);
+                        let mut expanding = false;
                         for (_, pane) in self.panes.iter_mut() {
                             if let Some(cp) =
                                 pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
                             {
                                 if cp.expanded.as_ref() == Some(&new_key) {
                                     cp.expanded = None;
-                                    cp.expanded_tracks.clear();
+                                    cp.expanded_works.clear();
                                     cp.expanded_cover = None;
                                 } else {
                                     cp.expanded = Some(new_key.clone());
-                                    cp.expanded_tracks = fetched.clone();
-                                    cp.expanded_cover = album_id
-                                        .and_then(|aid| cp.album_art_keys.get(&aid))
+                                    cp.expanded_tracks = None;
+                                    cp.expanded_albums.clear();
+                                    cp.expanded_works.clear();
+                                    cp.expanded_cover = cp
+                                        .artist_art_keys
+                                        .get(&composer_id)
                                         .map(|(tid, path)| (*tid, path.clone()));
+                                    expanding = true;
+                                }
+                            }
+                        }
+                        if expanding {
+                            return self.expand_works(new_key, composer_id);
+                        }
+                    }
+                    CollectionsMessage::ToggleGenre(genre_id) => {
+                        let new_key = ExpandedItem::Genre(genre_id);
+                        let mut expanding = false;
+                        for (_, pane) in self.panes.iter_mut() {
+                            if let Some(cp) =
+                                pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                            {
+                                if cp.expanded.as_ref() == Some(&new_key) {
+                                    cp.expanded = None;
+                                    cp.expanded_albums.clear();
+                                    cp.expanded_cover = None;
+                                } else {
+                                    cp.expanded = Some(new_key.clone());
+                                    cp.expanded_tracks = None;
+                                    cp.expanded_works.clear();
+                                    cp.expanded_albums.clear();
+                                    cp.expanded_cover = None;
+                                    expanding = true;
                                 }
                             }
                         }
+                        if expanding {
+                            return self.expand_albums(new_key, move |library| {
+                                library.query_albums_by_genre(genre_id)
+                            });
+                        }
+                    }
+                    CollectionsMessage::ToggleDecade(start) => {
+                        let new_key = ExpandedItem::Decade(start);
+                        let mut expanding = false;
+                        for (_, pane) in self.panes.iter_mut() {
+                            if let Some(cp) =
+                                pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                            {
+                                if cp.expanded.as_ref() == Some(&new_key) {
+                                    cp.expanded = None;
+                                    cp.expanded_albums.clear();
+                                    cp.expanded_cover = None;
+                                } else {
+                                    cp.expanded = Some(new_key.clone());
+                                    cp.expanded_tracks = None;
+                                    cp.expanded_works.clear();
+                                    cp.expanded_albums.clear();
+                                    cp.expanded_cover = None;
+                                    expanding = true;
+                                }
+                            }
+                        }
+                        if expanding {
+                            return self.expand_albums(new_key, move |library| {
+                                library.query_albums_by_decade(start)
+                            });
+                        }
+                    }
+                    CollectionsMessage::CycleCompilationFilter => {
+                        for (_, pane) in self.panes.iter_mut() {
+                            if let Some(cp) =
+                                pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
+                            {
+                                cp.compilation_filter = cp.compilation_filter.next();
+                            }
+                        }
                     }
                     CollectionsMessage::TogglePlaylist(id) => {
                         let new_key = ExpandedItem::Playlist(id);
-                        let fetched = self.player.get_tracks_in_playlist(id).unwrap_or_default();
+                        let mut expanding = false;
                         for (_, pane) in self.panes.iter_mut() {
                             if let Some(cp) =
                                 pane.content.as_any_mut().downcast_mut::<CollectionsPane>()
                             {
                                 if cp.expanded.as_ref() == Some(&new_key) {
                                     cp.expanded = None;
-                                    cp.expanded_tracks.clear();
+                                    cp.expanded_tracks = None;
                                     cp.expanded_cover = None;
                                 } else {
                                     cp.expanded = Some(new_key.clone());
-                                    cp.expanded_tracks = fetched.clone();
+                                    cp.expanded_tracks = None;
                                     cp.expanded_cover = cp
                                         .playlist_art_keys
                                         .get(&id)
                                         .map(|(tid, path)| (*tid, path.clone()));
+                                    expanding = true;
                                 }
                             }
                         }
+                        if expanding {
+                            return self.expand_tracks(new_key, move |library| {
+                                library.get_tracks_in_playlist(id)
+                            });
+                        }
                     }
                     _ => {}
                 }
             }
-            Message::AddTrackToPlaylist(track_id, playlist_id) => {
-                let _ = self.player.add_track_to_playlist(playlist_id, track_id);
-                self.invalidate_playlist_cache();
-            }
-            Message::PlayAlbum(album_name, artist) => {
-                if let Ok(tracks) = self
-                    .player
-                    .query_tracks_by_album(&album_name, artist.as_deref())
-                {
-                    self.player.clear_queue();
-                    self.player.queue_many(tracks.iter().filter_map(|t| t.id()));
-                    let _ = self.player.play();
+            Message::Library(msg) => match msg {
+                LibraryMessage::HeaderMoved(x) => {
+                    let resizing = self.panes.iter_mut().find_map(|(_, pane)| {
+                        pane.content
+                            .as_any_mut()
+                            .downcast_mut::<LibraryPane>()
+                            .and_then(|lp| lp.resizing)
+                    });
+                    if let Some(column) = resizing {
+                        let width = resize_width(&self.config.library_columns, column, x);
+                        if let Some(c) = self
+                            .config
+                            .library_columns
+                            .iter_mut()
+                            .find(|c| c.column == column)
+                        {
+                            c.width = width;
+                        }
+                    }
                 }
-            }
-            Message::QueueAlbumNext(album_name, artist) => {
-                if let Ok(tracks) = self
-                    .player
-                    .query_tracks_by_album(&album_name, artist.as_deref())
-                {
-                    self.player
-                        .queue_many_front(tracks.iter().filter_map(|t| t.id()));
+                LibraryMessage::ResizeEnd => {
+                    let mut resized = false;
+                    for (_, pane) in self.panes.iter_mut() {
+                        if let Some(lp) = pane.content.as_any_mut().downcast_mut::<LibraryPane>() {
+                            resized |= lp.resizing.take().is_some();
+                        }
+                    }
+                    if resized {
+                        self.config.save(&self.config_path);
+                    }
+                }
+                LibraryMessage::ToggleColumn(column) => {
+                    let columns = &mut self.config.library_columns;
+                    let visible = columns.iter().filter(|c| c.visible).count();
+                    if let Some(c) = columns.iter_mut().find(|c| c.column == column) {
+                        if !c.visible || visible > 1 {
+                            c.visible = !c.visible;
+                            self.config.save(&self.config_path);
+                        }
+                    }
+                }
+                msg => {
+                    let tracks = self.cached_tracks.as_deref().unwrap_or_default();
+                    for (_, pane) in self.panes.iter_mut() {
+                        if let Some(lp) = pane.content.as_any_mut().downcast_mut::<LibraryPane>() {
+                            match &msg {
+                                LibraryMessage::Sort(column) => lp.sort_by(*column, tracks),
+                                LibraryMessage::Scrolled(delta) => lp.scroll(*delta),
+                                LibraryMessage::Select(position) => {
+                                    lp.select(*position, self.modifiers, tracks)
+                                }
+                                LibraryMessage::SelectAll => lp.select_all(tracks),
+                                LibraryMessage::Navigate(navigation) => {
+                                    lp.navigate(*navigation, self.modifiers.shift(), tracks)
+                                }
+                                LibraryMessage::ResizeStart(column) => lp.resizing = Some(*column),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            },
+            Message::Queue(msg) => {
+                for (_, pane) in self.panes.iter_mut() {
+                    if let Some(qp) = pane.content.as_any_mut().downcast_mut::<QueuePane>() {
+                        match msg {
+                            QueueMessage::Select(index) => qp.select(index, self.modifiers),
+                            QueueMessage::SelectAll => qp.select_all(),
+                        }
+                    }
                 }
             }
-            Message::QueueAlbumBack(album_name, artist) => {
-                if let Ok(tracks) = self
-                    .player
-                    .query_tracks_by_album(&album_name, artist.as_deref())
-                {
-                    self.player.queue_many(tracks.iter().filter_map(|t| t.id()));
+            Message::Folders(msg) => {
+                let tree = self.cached_folders.as_deref().unwrap_or_default();
+                for (_, pane) in self.panes.iter_mut() {
+                    if let Some(fp) = pane.content.as_any_mut().downcast_mut::<FoldersPane>() {
+                        match msg.clone() {
+                            FoldersMessage::Open(path) => fp.open(path),
+                            FoldersMessage::Up => fp.up(tree),
+                        }
+                    }
                 }
             }
+            Message::PlayFolder(path) => {
+                return self.fetch_tracks(Enqueue::Play, move |library| {
+                    library.query_tracks_in_folder(&path)
+                });
+            }
+            Message::QueueFolderNext(path) => {
+                return self.fetch_tracks(Enqueue::Next, move |library| {
+                    library.query_tracks_in_folder(&path)
+                });
+            }
+            Message::QueueFolderBack(path) => {
+                return self.fetch_tracks(Enqueue::Back, move |library| {
+                    library.query_tracks_in_folder(&path)
+                });
+            }
+            Message::PlayAlbum(album_id) => {
+                return self.fetch_tracks(Enqueue::Play, move |library| {
+                    library.query_tracks_by_album_id(album_id)
+                });
+            }
+            Message::QueueAlbumNext(album_id) => {
+                return self.fetch_tracks(Enqueue::Next, move |library| {
+                    library.query_tracks_by_album_id(album_id)
+                });
+            }
+            Message::QueueAlbumBack(album_id) => {
+                return self.fetch_tracks(Enqueue::Back, move |library| {
+                    library.query_tracks_by_album_id(album_id)
+                });
+            }
+            Message::PlayArtist(artist_id) => {
+                return self.fetch_tracks(Enqueue::Play, move |library| {
+                    library.query_tracks_by_artist_id(artist_id)
+                });
+            }
+            Message::QueueArtistNext(artist_id) => {
+                return self.fetch_tracks(Enqueue::Next, move |library| {
+                    library.query_tracks_by_artist_id(artist_id)
+                });
+            }
+            Message::QueueArtistBack(artist_id) => {
+                return self.fetch_tracks(Enqueue::Back, move |library| {
+                    library.query_tracks_by_artist_id(artist_id)
+                });
+            }
+            Message::PlayWork(composer_id, work) => {
+                return self.fetch_tracks(Enqueue::Play, move |library| {
+                    library.query_tracks_by_work(composer_id, &work)
+                });
+            }
+            Message::QueueWorkNext(composer_id, work) => {
+                return self.fetch_tracks(Enqueue::Next, move |library| {
+                    library.query_tracks_by_work(composer_id, &work)
+                });
+            }
+            Message::QueueWorkBack(composer_id, work) => {
+                return self.fetch_tracks(Enqueue::Back, move |library| {
+                    library.query_tracks_by_work(composer_id, &work)
+                });
+            }
+            Message::PlayGenre(genre) => {
+                return self.fetch_tracks(Enqueue::Play, move |library| {
+                    library.query_tracks_by_genre(&genre)
+                });
+            }
+            Message::QueueGenreNext(genre) => {
+                return self.fetch_tracks(Enqueue::Next, move |library| {
+                    library.query_tracks_by_genre(&genre)
+                });
+            }
+            Message::QueueGenreBack(genre) => {
+                return self.fetch_tracks(Enqueue::Back, move |library| {
+                    library.query_tracks_by_genre(&genre)
+                });
+            }
+            Message::PlayDecade(start) => {
+                return self.fetch_tracks(Enqueue::Play, move |library| {
+                    library.query_tracks_by_decade(start)
+                });
+            }
+            Message::QueueDecadeNext(start) => {
+                return self.fetch_tracks(Enqueue::Next, move |library| {
+                    library.query_tracks_by_decade(start)
+                });
+            }
+            Message::QueueDecadeBack(start) => {
+                return self.fetch_tracks(Enqueue::Back, move |library| {
+                    library.query_tracks_by_decade(start)
+                });
+            }
             Message::CardHovered(is_album, id) => {
                 self.hovered_card = Some((is_album, id));
             }
@@ -692,9 +1476,10 @@ impl App {
                     }
                     _ => {}
                 },
-                Event::Keyboard(keyboard::Event::KeyPressed {
-                    key, modifiers: _, ..
-                }) => match key {
+                Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                    self.modifiers = modifiers;
+                }
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
                     Key::Named(key::Named::Space) => {
                         if self.player.is_playing() {
                             self.player.pause();
@@ -702,6 +1487,27 @@ impl App {
                             let _ = self.player.play();
                         }
                     }
+                    Key::Named(key::Named::Enter) => {
+                        let tracks = self.cached_tracks.as_deref().unwrap_or_default();
+                        let track_id = self.panes.iter_mut().find_map(|(_, pane)| {
+                            pane.content
+                                .as_any_mut()
+                                .downcast_mut::<LibraryPane>()
+                                .and_then(|lp| lp.cursor_track(tracks))
+                        });
+                        if let Some(track_id) = track_id {
+                            return self.update(Message::PlayTrack(track_id));
+                        }
+                    }
+                    Key::Named(named) => {
+                        if let Some(navigation) = Navigation::from_key(&named) {
+                            return self
+                                .update(Message::Library(LibraryMessage::Navigate(navigation)));
+                        }
+                    }
+                    Key::Character(c) if modifiers.command() && c.as_str() == "a" => {
+                        return self.update(Message::Library(LibraryMessage::SelectAll));
+                    }
                     Key::Character(c) => {
                         if let Ok(num) = c.parse::<usize>() {
                             if num >= 1 && num <= self.layout_presets.len() {
@@ -738,18 +1544,21 @@ impl App {
         let total_panes = self.panes.len();
         let edit_mode = self.edit_mode;
 
-        self.ensure_cached_tracks();
-        self.ensure_cached_albums();
-        self.ensure_cached_playlists();
-
         let player = &self.player;
         let volume = self.volume;
         let hovered_track = &self.hovered_track;
         let hovered_card = self.hovered_card;
         let seeking_position = self.seeking_position;
-        let cached_tracks = &self.cached_tracks;
-        let cached_albums = &self.cached_albums;
-        let cached_playlists = &self.cached_playlists;
+        let cached_tracks = self.cached_tracks.as_deref();
+        let cached_albums = self.cached_albums.as_deref();
+        let cached_artists = self.cached_artists.as_deref();
+        let cached_composers = self.cached_composers.as_deref();
+        let cached_playlists = self.cached_playlists.as_deref();
+        let cached_genres = self.cached_genres.as_deref();
+        let cached_decades = self.cached_decades.as_deref();
+        let cached_folders = self.cached_folders.as_deref();
+        let library_columns = &self.config.library_columns;
+        let work_titles = self.config.work_titles;
         let art_cache = &self.art_cache;
 
         let mut pane_grid = PaneGrid::new(&self.panes, move |id, pane, _is_maximized| {
@@ -764,7 +1573,14 @@ impl App {
                 seeking_position,
                 cached_tracks,
                 cached_albums,
+                cached_artists,
+                cached_composers,
                 cached_playlists,
+                cached_genres,
+                cached_decades,
+                cached_folders,
+                library_columns,
+                work_titles,
                 art_cache,
             )
         })
@@ -796,9 +1612,27 @@ impl App {
                 .height(Length::Fill)
         };
 
+        if let Some(editor) = &self.tag_editor {
+            return editor.view().map(Message::TagEditor);
+        }
+
+        if let Some(track_ids) = &self.confirming_delete {
+            return confirm_delete(
+                track_ids.len(),
+                Message::ConfirmDeleteTracks,
+                Message::CancelDeleteTracks,
+            );
+        }
+
         if let Some(pending) = &self.editing_config {
-            return preferences::view(pending, &self.config.theme, self.confirming_clear)
-                .map(Message::Preference);
+            return preferences::view(
+                pending,
+                &self.player,
+                &self.output_devices,
+                &self.config.theme,
+                self.confirming_clear,
+            )
+            .map(Message::Preference);
         }
 
         column![
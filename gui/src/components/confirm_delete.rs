@@ -0,0 +1,52 @@
+use iced::alignment::Horizontal;
+use iced::widget::{button, column, container, row, text};
+use iced::{Element, Length, Theme};
+
+use crate::styles::PAD;
+
+pub fn confirm_delete<'a, Message: Clone + 'a>(
+    count: usize,
+    confirm: Message,
+    cancel: Message,
+) -> Element<'a, Message> {
+    let prompt = if count == 1 {
+        "Delete this track from disk?".to_string()
+    } else {
+        format!("Delete {count} tracks from disk?")
+    };
+
+    container(
+        column![
+            text(prompt).size(16),
+            text("The files will be permanently removed and cannot be recovered.")
+                .size(12)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(
+                        theme
+                            .extended_palette()
+                            .background
+                            .base
+                            .text
+                            .scale_alpha(0.5)
+                    ),
+                }),
+            row![
+                button(text("Cancel").size(12))
+                    .on_press(cancel)
+                    .padding([4.0, 8.0]),
+                button(text("Delete").size(12))
+                    .on_press(confirm)
+                    .padding([4.0, 8.0])
+                    .style(button::danger),
+            ]
+            .spacing(PAD),
+        ]
+        .spacing(PAD * 3.0)
+        .align_x(Horizontal::Center),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x(Length::Fill)
+    .center_y(Length::Fill)
+    .into()
+}
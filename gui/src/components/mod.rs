@@ -1,3 +1,6 @@
 pub mod bottom_bar;
+pub mod confirm_delete;
 pub mod context_menu;
 pub mod preferences;
+pub mod tag_editor;
+pub mod track_menu;
@@ -3,9 +3,11 @@ use iced::widget::scrollable::{Direction, Scrollbar};
 use iced::widget::svg::Handle as SvgHandle;
 use iced::widget::tooltip::Position;
 use iced::widget::{
-    button, column, container, row, rule, scrollable, space, svg, text, toggler, tooltip,
+    button, column, container, pick_list, row, rule, scrollable, space, svg, text, toggler, tooltip,
 };
 use iced::{Element, Length, Theme};
+use std::fmt;
+use verse_core::{AudioSettings, BUFFER_SIZES, Player, ResamplerQuality, SAMPLE_RATES};
 
 use crate::config::{Config, PresetIndicator};
 use crate::styles::{PAD, TOOLTIP_DELAY, bar_style, svg_style};
@@ -17,6 +19,7 @@ pub enum PreferenceMessage {
     SetTheme(Theme),
     SetRounded(bool),
     SetPresetIndicator(PresetIndicator),
+    SetWorkTitles(bool),
     SetLibrary,
     Reset,
     Save,
@@ -24,6 +27,49 @@ pub enum PreferenceMessage {
     ClearLibrary,
     ConfirmClearLibrary,
     CancelClearLibrary,
+    SetOutputDevice(Option<String>),
+    SetHeadphoneProfile(Option<String>),
+    ImportHeadphoneProfile,
+    RemoveHeadphoneProfile(String),
+    SetAudioSettings(AudioSettings),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Choice<T> {
+    value: Option<T>,
+    none: &'static str,
+    unit: &'static str,
+}
+
+impl<T> Choice<T> {
+    fn new(value: Option<T>, none: &'static str) -> Self {
+        Choice {
+            value,
+            none,
+            unit: "",
+        }
+    }
+
+    fn with_unit(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    fn list(none: &'static str, values: impl Iterator<Item = T>) -> Vec<Choice<T>> {
+        std::iter::once(None)
+            .chain(values.map(Some))
+            .map(|value| Choice::new(value, none))
+            .collect()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Choice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}{}", value, self.unit),
+            None => write!(f, "{}", self.none),
+        }
+    }
 }
 
 fn section<'a>(label: &'a str, theme: &Theme) -> Element<'a, PreferenceMessage> {
@@ -61,6 +107,8 @@ fn setting<'a>(
 
 pub fn view<'a>(
     pending: &'a Config,
+    player: &Player,
+    output_devices: &[String],
     theme: &Theme,
     confirming_clear: bool,
 ) -> Element<'a, PreferenceMessage> {
@@ -147,6 +195,89 @@ pub fn view<'a>(
             .into()
     };
 
+    let device_control = pick_list(
+        Choice::list("System default", output_devices.iter().cloned()),
+        Some(Choice::new(
+            player.selected_output_device(),
+            "System default",
+        )),
+        |choice| PreferenceMessage::SetOutputDevice(choice.value),
+    )
+    .text_size(12)
+    .padding([4.0, 8.0]);
+
+    let audio = player.audio_settings();
+
+    let buffer_control = pick_list(
+        Choice::list("Default", BUFFER_SIZES.into_iter())
+            .into_iter()
+            .map(|c| c.with_unit(" frames"))
+            .collect::<Vec<_>>(),
+        Some(Choice::new(audio.buffer_size, "Default").with_unit(" frames")),
+        move |choice| {
+            PreferenceMessage::SetAudioSettings(AudioSettings {
+                buffer_size: choice.value,
+                ..audio
+            })
+        },
+    )
+    .text_size(12)
+    .padding([4.0, 8.0]);
+
+    let rate_control = pick_list(
+        Choice::list("Device default", SAMPLE_RATES.into_iter())
+            .into_iter()
+            .map(|c| c.with_unit(" Hz"))
+            .collect::<Vec<_>>(),
+        Some(Choice::new(audio.sample_rate, "Device default").with_unit(" Hz")),
+        move |choice| {
+            PreferenceMessage::SetAudioSettings(AudioSettings {
+                sample_rate: choice.value,
+                ..audio
+            })
+        },
+    )
+    .text_size(12)
+    .padding([4.0, 8.0]);
+
+    let resampler_control = pick_list(
+        ResamplerQuality::ALL,
+        Some(audio.resampler),
+        move |resampler| PreferenceMessage::SetAudioSettings(AudioSettings { resampler, ..audio }),
+    )
+    .text_size(12)
+    .padding([4.0, 8.0]);
+
+    let active_profile = player.headphone_profile().map(|p| p.name.clone());
+    let profile_choices = Choice::list(
+        "Off",
+        player.headphone_profiles().iter().map(|p| p.name.clone()),
+    );
+
+    let mut profile_control = row![
+        pick_list(
+            profile_choices,
+            Some(Choice::new(active_profile.clone(), "Off")),
+            |choice| PreferenceMessage::SetHeadphoneProfile(choice.value),
+        )
+        .text_size(12)
+        .padding([4.0, 8.0]),
+        button(text("Import").size(12))
+            .on_press(PreferenceMessage::ImportHeadphoneProfile)
+            .padding([4.0, 8.0]),
+    ]
+    .align_y(Vertical::Center)
+    .spacing(PAD);
+
+    if let Some(name) = active_profile {
+        profile_control = profile_control.push(
+            button(text("Remove").size(12))
+                .on_press(PreferenceMessage::RemoveHeadphoneProfile(name))
+                .padding([4.0, 8.0])
+                .style(button::danger),
+        );
+    }
+
     let content = column![
         container(text("Preferences").size(16))
             .width(Length::Fill)
@@ -196,6 +327,52 @@ pub fn view<'a>(
                 .into(),
             theme,
         ),
+        space::Space::new().height(PAD),
+        setting(
+            "Classical titles",
+            "Show work and movement instead of the track title",
+            toggler(pending.work_titles)
+                .on_toggle(PreferenceMessage::SetWorkTitles)
+                .into(),
+            theme,
+        ),
+        space::Space::new().height(PAD * 2.0),
+        section("Audio", theme),
+        space::Space::new().height(PAD),
+        setting(
+            "Output device",
+            "Where audio is played; follows the system default when unset",
+            device_control.into(),
+            theme,
+        ),
+        space::Space::new().height(PAD),
+        setting(
+            "Buffer size",
+            "Smaller buffers lower latency but may cause dropouts",
+            buffer_control.into(),
+            theme,
+        ),
+        space::Space::new().height(PAD),
+        setting(
+            "Sample rate",
+            "Preferred output rate; falls back to the device rate if unsupported",
+            rate_control.into(),
+            theme,
+        ),
+        space::Space::new().height(PAD),
+        setting(
+            "Resampler",
+            "Quality used when the mix rate differs from the device rate",
+            resampler_control.into(),
+            theme,
+        ),
+        space::Space::new().height(PAD),
+        setting(
+            "Headphone correction",
+            "AutoEQ or EqualizerAPO ParametricEQ.txt profile",
+            profile_control.into(),
+            theme,
+        ),
         space::Space::new().height(PAD * 2.0),
         section("Danger Zone", theme),
         space::Space::new().height(PAD),
@@ -0,0 +1,172 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::{button, column, container, row, rule, space, text, text_input};
+use iced::{Element, Length};
+use verse_core::{TagEdit, Track};
+
+use crate::styles::{PAD, bar_style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagField {
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    Genre,
+    Year,
+}
+
+impl TagField {
+    pub const ALL: [TagField; 6] = [
+        TagField::Title,
+        TagField::Artist,
+        TagField::Album,
+        TagField::AlbumArtist,
+        TagField::Genre,
+        TagField::Year,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TagField::Title => "Title",
+            TagField::Artist => "Artist",
+            TagField::Album => "Album",
+            TagField::AlbumArtist => "Album artist",
+            TagField::Genre => "Genre",
+            TagField::Year => "Year",
+        }
+    }
+
+    fn value(&self, track: &Track) -> Option<String> {
+        match self {
+            TagField::Title => track.title().map(str::to_string),
+            TagField::Artist => track.track_artist().map(str::to_string),
+            TagField::Album => track.album().map(str::to_string),
+            TagField::AlbumArtist => track.album_artist().map(str::to_string),
+            TagField::Genre => track.genre().map(str::to_string),
+            TagField::Year => track.year().map(|y| y.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TagEditorMessage {
+    Changed(TagField, String),
+    Save,
+    Cancel,
+}
+
+#[derive(Debug, Clone)]
+pub struct TagEditor {
+    pub track_ids: Vec<i64>,
+    initial: Vec<String>,
+    values: Vec<String>,
+}
+
+impl TagEditor {
+    pub fn new(tracks: &[&Track]) -> Self {
+        let initial: Vec<String> = TagField::ALL
+            .iter()
+            .map(|field| {
+                let mut values = tracks.iter().map(|t| field.value(t));
+                let first = values.next().flatten();
+                if values.all(|v| v == first) {
+                    first.unwrap_or_default()
+                } else {
+                    String::new()
+                }
+            })
+            .collect();
+
+        TagEditor {
+            track_ids: tracks.iter().filter_map(|t| t.id()).collect(),
+            values: initial.clone(),
+            initial,
+        }
+    }
+
+    pub fn set(&mut self, field: TagField, value: String) {
+        if let Some(i) = TagField::ALL.iter().position(|&f| f == field) {
+            self.values[i] = value;
+        }
+    }
+
+    pub fn edit(&self) -> TagEdit {
+        let changed = |field: TagField| {
+            let i = TagField::ALL.iter().position(|&f| f == field)?;
+            let value = self.values[i].trim();
+            (value != self.initial[i].trim()).then(|| value.to_string())
+        };
+
+        TagEdit {
+            title: changed(TagField::Title),
+            artist: changed(TagField::Artist),
+            album: changed(TagField::Album),
+            album_artist: changed(TagField::AlbumArtist),
+            genre: changed(TagField::Genre),
+            year: changed(TagField::Year).and_then(|y| y.parse().ok()),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, TagEditorMessage> {
+        let count = self.track_ids.len();
+        let title = if count == 1 {
+            "Edit Tags".to_string()
+        } else {
+            format!("Edit Tags ({count} tracks)")
+        };
+        let placeholder = if count > 1 { "Multiple values" } else { "" };
+
+        let mut fields = column![].spacing(PAD * 2.0);
+        for (i, field) in TagField::ALL.into_iter().enumerate() {
+            fields = fields.push(
+                row![
+                    text(field.label()).size(13).width(Length::Fixed(120.0)),
+                    text_input(placeholder, &self.values[i])
+                        .on_input(move |value| TagEditorMessage::Changed(field, value))
+                        .on_submit(TagEditorMessage::Save)
+                        .size(13)
+                        .padding([4.0, 8.0]),
+                ]
+                .align_y(Vertical::Center)
+                .spacing(PAD * 2.0),
+            );
+        }
+
+        let content = column![
+            container(text(title).size(16))
+                .width(Length::Fill)
+                .align_x(Horizontal::Center),
+            space::Space::new().height(PAD * 2.0),
+            fields,
+        ]
+        .spacing(PAD)
+        .padding(PAD * 3.0)
+        .width(Length::Fill);
+
+        let action_buttons = container(
+            row![
+                space::Space::new().width(Length::Fill),
+                button(text("Cancel").size(12))
+                    .on_press(TagEditorMessage::Cancel)
+                    .padding([4.0, 8.0]),
+                button(text("Save").size(12))
+                    .on_press(TagEditorMessage::Save)
+                    .padding([4.0, 8.0]),
+            ]
+            .align_y(Vertical::Center)
+            .spacing(PAD),
+        )
+        .width(Length::Fill)
+        .padding(PAD * 2.0)
+        .style(bar_style);
+
+        column![
+            container(content).height(Length::Fill),
+            rule::horizontal(1),
+            action_buttons,
+        ]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
@@ -0,0 +1,56 @@
+use verse_core::Playlist;
+
+use crate::app::Message;
+use crate::components::context_menu::MenuElement;
+
+fn counted(label: &str, count: usize) -> String {
+    if count > 1 {
+        format!("{label} ({count} tracks)")
+    } else {
+        label.to_string()
+    }
+}
+
+pub fn track_actions(track_ids: &[i64], playlists: &[Playlist]) -> Vec<MenuElement<Message>> {
+    let count = track_ids.len();
+    let mut items = Vec::new();
+
+    if playlists.is_empty() {
+        items.push(MenuElement::label("No playlists"));
+    } else {
+        for playlist in playlists {
+            items.push(MenuElement::button(
+                format!("Add to \"{}\"", playlist.name),
+                Message::AddTracksToPlaylist(track_ids.to_vec(), playlist.id),
+            ));
+        }
+    }
+
+    items.push(MenuElement::Separator);
+    items.push(MenuElement::button(
+        counted("Play", count),
+        Message::PlayTracks(track_ids.to_vec()),
+    ));
+    items.push(MenuElement::button(
+        "Queue next",
+        Message::QueueTracksFront(track_ids.to_vec()),
+    ));
+    items.push(MenuElement::button(
+        "Queue",
+        Message::QueueTracksBack(track_ids.to_vec()),
+    ));
+    items.push(MenuElement::Separator);
+    items.push(MenuElement::button(
+        counted("Edit tags", count),
+        Message::EditTags(track_ids.to_vec()),
+    ));
+    items.push(MenuElement::button(
+        "Show in file manager",
+        Message::RevealTracks(track_ids.to_vec()),
+    ));
+    items.push(MenuElement::button(
+        counted("Delete from disk", count),
+        Message::DeleteTracks(track_ids.to_vec()),
+    ));
+    items
+}
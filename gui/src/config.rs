@@ -1,6 +1,7 @@
 use iced::Theme;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use verse_core::{HOME_VAR, LaunchOptions};
 
 pub const ALL_THEMES: &[Theme] = &[
     Theme::Light,
@@ -40,6 +41,93 @@ impl Default for PresetIndicator {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackColumn {
+    Title,
+    Artist,
+    Album,
+    Duration,
+    Year,
+    DateAdded,
+}
+
+impl TrackColumn {
+    pub const ALL: [TrackColumn; 6] = [
+        TrackColumn::Title,
+        TrackColumn::Artist,
+        TrackColumn::Album,
+        TrackColumn::Duration,
+        TrackColumn::Year,
+        TrackColumn::DateAdded,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            TrackColumn::Title => "Title",
+            TrackColumn::Artist => "Artist",
+            TrackColumn::Album => "Album",
+            TrackColumn::Duration => "Duration",
+            TrackColumn::Year => "Year",
+            TrackColumn::DateAdded => "Date added",
+        }
+    }
+
+    fn default_width(&self) -> f32 {
+        match self {
+            TrackColumn::Title => 280.0,
+            TrackColumn::Artist | TrackColumn::Album => 180.0,
+            TrackColumn::Duration => 80.0,
+            TrackColumn::Year => 60.0,
+            TrackColumn::DateAdded => 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColumnConfig {
+    pub column: TrackColumn,
+    pub width: f32,
+    pub visible: bool,
+}
+
+pub const MIN_COLUMN_WIDTH: f32 = 40.0;
+
+fn default_library_columns() -> Vec<ColumnConfig> {
+    TrackColumn::ALL
+        .iter()
+        .map(|&column| ColumnConfig {
+            column,
+            width: column.default_width(),
+            visible: column != TrackColumn::DateAdded,
+        })
+        .collect()
+}
+
+fn normalize_columns(mut columns: Vec<ColumnConfig>) -> Vec<ColumnConfig> {
+    let mut seen = Vec::new();
+    columns.retain(|c| {
+        let new = !seen.contains(&c.column);
+        seen.push(c.column);
+        new
+    });
+    for default in default_library_columns() {
+        if !seen.contains(&default.column) {
+            columns.push(ColumnConfig {
+                visible: false,
+                ..default
+            });
+        }
+    }
+    for c in &mut columns {
+        c.width = c.width.max(MIN_COLUMN_WIDTH);
+    }
+    if !columns.iter().any(|c| c.visible) {
+        columns[0].visible = true;
+    }
+    columns
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LayoutAxis {
@@ -69,6 +157,8 @@ pub struct Config {
     pub layouts: Vec<LayoutNode>,
     pub current_layout: usize,
     pub volume: f32,
+    pub library_columns: Vec<ColumnConfig>,
+    pub work_titles: bool,
 }
 
 impl Default for Config {
@@ -80,6 +170,8 @@ impl Default for Config {
             layouts: vec![],
             current_layout: 0,
             volume: 0.5,
+            library_columns: default_library_columns(),
+            work_titles: false,
         }
     }
 }
@@ -97,6 +189,10 @@ struct ConfigFile {
     current_layout: usize,
     #[serde(default = "default_volume")]
     volume: f32,
+    #[serde(default = "default_library_columns")]
+    library_columns: Vec<ColumnConfig>,
+    #[serde(default)]
+    work_titles: bool,
 }
 
 fn default_volume() -> f32 {
@@ -116,6 +212,8 @@ impl From<&Config> for ConfigFile {
             layouts: c.layouts.clone(),
             current_layout: c.current_layout,
             volume: c.volume,
+            library_columns: c.library_columns.clone(),
+            work_titles: c.work_titles,
         }
     }
 }
@@ -129,6 +227,8 @@ impl From<ConfigFile> for Config {
             layouts: f.layouts,
             current_layout: f.current_layout,
             volume: f.volume.clamp(0.0, 1.0),
+            library_columns: normalize_columns(f.library_columns),
+            work_titles: f.work_titles,
         }
     }
 }
@@ -141,16 +241,28 @@ fn theme_from_str(s: &str) -> Theme {
         .unwrap_or(Theme::KanagawaDragon)
 }
 
-fn config_path() -> Option<PathBuf> {
-    dirs::config_dir().map(|d| d.join("verse").join("gui.toml"))
+pub fn legacy_path(options: &LaunchOptions) -> Option<PathBuf> {
+    let custom_home = std::env::var_os(HOME_VAR).is_some_and(|v| !v.is_empty());
+    if options.portable || options.profile.is_some() || custom_home {
+        return None;
+    }
+    directories::BaseDirs::new().map(|dirs| dirs.config_dir().join("verse").join("gui.toml"))
 }
 
 impl Config {
-    pub fn load() -> Self {
-        let Some(path) = config_path() else {
-            return Self::default();
-        };
-        let text = match std::fs::read_to_string(&path) {
+    pub fn load_or_migrate(path: &Path, legacy: Option<&Path>) -> Self {
+        match legacy {
+            Some(legacy) if !path.exists() && legacy.exists() => {
+                let config = Self::load(legacy);
+                config.save(path);
+                config
+            }
+            _ => Self::load(path),
+        }
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
             Ok(t) => t,
             Err(_) => return Self::default(),
         };
@@ -159,10 +271,7 @@ impl Config {
             .unwrap_or_default()
     }
 
-    pub fn save(&self) {
-        let Some(path) = config_path() else {
-            return;
-        };
+    pub fn save(&self, path: &Path) {
         if let Some(parent) = path.parent() {
             if let Err(e) = std::fs::create_dir_all(parent) {
                 eprintln!("verse: could not create config dir: {e}");
@@ -171,7 +280,7 @@ impl Config {
         }
         match toml::to_string_pretty(&ConfigFile::from(self)) {
             Ok(text) => {
-                let _ = std::fs::write(&path, text);
+                let _ = std::fs::write(path, text);
             }
             Err(e) => eprintln!("verse: failed to serialize config: {e}"),
         }
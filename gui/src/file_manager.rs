@@ -0,0 +1,21 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+pub fn reveal(path: &Path) -> io::Result<()> {
+    let mut arg = std::ffi::OsString::from("/select,");
+    arg.push(path);
+    Command::new("explorer").arg(arg).spawn().map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+pub fn reveal(path: &Path) -> io::Result<()> {
+    Command::new("open").arg("-R").arg(path).spawn().map(|_| ())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn reveal(path: &Path) -> io::Result<()> {
+    let dir = path.parent().unwrap_or(path);
+    Command::new("xdg-open").arg(dir).spawn().map(|_| ())
+}
@@ -40,3 +40,20 @@ pub fn format_channels(channels: Option<u8>) -> String {
         None => "-".to_string(),
     }
 }
+
+pub fn format_date(timestamp: Option<i64>) -> String {
+    let Some(timestamp) = timestamp else {
+        return "-".to_string();
+    };
+    let days = timestamp.div_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -9,6 +9,7 @@ mod app;
 mod art_cache;
 mod components;
 mod config;
+mod file_manager;
 mod formatters;
 mod image_processing;
 mod media_controls;
@@ -20,18 +21,33 @@ mod widgets;
 mod window_handle;
 
 use app::App;
+use verse_core::LaunchOptions;
 
 pub fn main() -> iced::Result {
-    iced::application(App::default, App::update, App::view)
-        .title("verse")
-        .window(Settings {
-            min_size: Some(Size::new(300., 0.)),
-            icon: from_file_data(include_bytes!("../../assets/logo.png"), None).ok(),
-            exit_on_close_request: false,
-            ..Default::default()
-        })
-        .centered()
-        .subscription(App::subscription)
-        .theme(|app: &App| app.theme())
-        .run()
+    let options = LaunchOptions::from_args(std::env::args().skip(1));
+    let (paths, legacy_config) =
+        match options.and_then(|o| Ok((o.paths()?, config::legacy_path(&o)))) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                eprintln!("verse: {e}");
+                std::process::exit(2);
+            }
+        };
+
+    iced::application(
+        move || App::new(paths.clone(), legacy_config.clone()),
+        App::update,
+        App::view,
+    )
+    .title("verse")
+    .window(Settings {
+        min_size: Some(Size::new(300., 0.)),
+        icon: from_file_data(include_bytes!("../../assets/logo.png"), None).ok(),
+        exit_on_close_request: false,
+        ..Default::default()
+    })
+    .centered()
+    .subscription(App::subscription)
+    .theme(|app: &App| app.theme())
+    .run()
 }
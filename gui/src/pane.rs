@@ -1,11 +1,11 @@
 use iced::widget::{Space, container, pane_grid, text};
 use iced::{Length, Theme};
-use verse_core::{Album, Player, Playlist, Track};
-use std::cell::RefCell;
+use verse_core::{Album, Artist, Decade, Folder, Genre, Player, Playlist, Track};
 use std::fmt::{self, Display};
 
 use crate::app::Message;
 use crate::art_cache::ArtCache;
+use crate::config::ColumnConfig;
 use crate::components::context_menu::{MenuElement, context_menu};
 use crate::pane_view::{PaneView, ViewContext};
 use crate::panes::*;
@@ -16,24 +16,28 @@ pub enum PaneType {
     Queue,
     Library,
     Collections,
+    Folders,
     Artwork,
     Timeline,
     Spectrum,
     VUMeters,
+    Equalizer,
     TrackInfo,
     Empty,
 }
 
 impl PaneType {
-    pub const ALL: [PaneType; 10] = [
+    pub const ALL: [PaneType; 12] = [
         PaneType::Controls,
         PaneType::Queue,
         PaneType::Library,
         PaneType::Collections,
+        PaneType::Folders,
         PaneType::Artwork,
         PaneType::Timeline,
         PaneType::Spectrum,
         PaneType::VUMeters,
+        PaneType::Equalizer,
         PaneType::TrackInfo,
         PaneType::Empty,
     ];
@@ -44,10 +48,12 @@ impl PaneType {
             PaneType::Queue => "Queue",
             PaneType::Library => "Library",
             PaneType::Collections => "Collections",
+            PaneType::Folders => "Folders",
             PaneType::Artwork => "Artwork",
             PaneType::Timeline => "Timeline",
             PaneType::Spectrum => "Spectrum",
             PaneType::VUMeters => "VU Meters",
+            PaneType::Equalizer => "Equalizer",
             PaneType::TrackInfo => "Track Info",
             PaneType::Empty => "Empty",
         }
@@ -67,10 +73,12 @@ impl PaneType {
             PaneType::Queue => Box::new(QueuePane::new()),
             PaneType::Library => Box::new(LibraryPane::new()),
             PaneType::Collections => Box::new(CollectionsPane::new()),
+            PaneType::Folders => Box::new(FoldersPane::new()),
             PaneType::Artwork => Box::new(ArtworkPane::new()),
             PaneType::Timeline => Box::new(TimelinePane::new()),
             PaneType::Spectrum => Box::new(SpectrumPane::new()),
             PaneType::VUMeters => Box::new(VUMetersPane::new()),
+            PaneType::Equalizer => Box::new(EqualizerPane::new()),
             PaneType::TrackInfo => Box::new(TrackInfoPane::new()),
             PaneType::Empty => Box::new(EmptyPane::new()),
         }
@@ -110,6 +118,17 @@ impl Pane {
         self.content.invalidate_cache();
     }
 
+    pub fn library_loaded(
+        &mut self,
+        tracks: &[Track],
+        albums: &[Album],
+        artists: &[Artist],
+        playlists: &[Playlist],
+    ) {
+        self.content
+            .library_loaded(tracks, albums, artists, playlists);
+    }
+
     pub fn get_type(&self) -> PaneType {
         self.pane_type
     }
@@ -124,9 +143,16 @@ impl Pane {
         hovered_track: &'a Option<i64>,
         hovered_card: Option<(bool, i64)>,
         seeking_position: Option<f32>,
-        cached_tracks: &'a RefCell<Option<Vec<Track>>>,
-        cached_albums: &'a RefCell<Option<Vec<Album>>>,
-        cached_playlists: &'a RefCell<Option<Vec<Playlist>>>,
+        cached_tracks: Option<&'a [Track]>,
+        cached_albums: Option<&'a [Album]>,
+        cached_artists: Option<&'a [Artist]>,
+        cached_composers: Option<&'a [Artist]>,
+        cached_playlists: Option<&'a [Playlist]>,
+        cached_genres: Option<&'a [Genre]>,
+        cached_decades: Option<&'a [Decade]>,
+        cached_folders: Option<&'a [Folder]>,
+        library_columns: &'a [ColumnConfig],
+        work_titles: bool,
         art: &'a ArtCache,
     ) -> pane_grid::Content<'a, Message> {
         if edit_mode {
@@ -191,7 +217,14 @@ impl Pane {
                 seeking_position,
                 cached_tracks,
                 cached_albums,
+                cached_artists,
+                cached_composers,
                 cached_playlists,
+                cached_genres,
+                cached_decades,
+                cached_folders,
+                library_columns,
+                work_titles,
                 art,
             };
 
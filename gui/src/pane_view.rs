@@ -1,10 +1,10 @@
 use iced::Element;
-use verse_core::{Album, Player, Playlist, Track};
-use std::cell::RefCell;
+use verse_core::{Album, Artist, Decade, Folder, Genre, Player, Playlist, Track};
 use std::fmt;
 
 use crate::app::Message;
 use crate::art_cache::ArtCache;
+use crate::config::ColumnConfig;
 
 pub struct ViewContext<'a> {
     pub player: &'a Player,
@@ -12,9 +12,16 @@ pub struct ViewContext<'a> {
     pub hovered_track: &'a Option<i64>,
     pub hovered_card: Option<(bool, i64)>,
     pub seeking_position: Option<f32>,
-    pub cached_tracks: &'a RefCell<Option<Vec<Track>>>,
-    pub cached_albums: &'a RefCell<Option<Vec<Album>>>,
-    pub cached_playlists: &'a RefCell<Option<Vec<Playlist>>>,
+    pub cached_tracks: Option<&'a [Track]>,
+    pub cached_albums: Option<&'a [Album]>,
+    pub cached_artists: Option<&'a [Artist]>,
+    pub cached_composers: Option<&'a [Artist]>,
+    pub cached_playlists: Option<&'a [Playlist]>,
+    pub cached_genres: Option<&'a [Genre]>,
+    pub cached_decades: Option<&'a [Decade]>,
+    pub cached_folders: Option<&'a [Folder]>,
+    pub library_columns: &'a [ColumnConfig],
+    pub work_titles: bool,
     pub art: &'a ArtCache,
 }
 
@@ -27,6 +34,15 @@ pub trait PaneView: fmt::Debug {
 
     fn invalidate_cache(&mut self) {}
 
+    fn library_loaded(
+        &mut self,
+        _tracks: &[Track],
+        _albums: &[Album],
+        _artists: &[Artist],
+        _playlists: &[Playlist],
+    ) {
+    }
+
     fn clone_box(&self) -> Box<dyn PaneView>;
 }
 
@@ -10,12 +10,7 @@ use iced::{
     Alignment, Background, Border, Color, ContentFit, Element, Font, Gradient, Length, Radians,
     Theme,
 };
-<<<<<<< HEAD
-use iced::{Color, Element, Length, Radians, Theme};
-use verse_core::{Player, Track};
-=======
-use msc_core::{Player, Track};
->>>>>>> 823ddd72eaa64cde5db469f3bfd0239bc38f69db
+use verse_core::{Album, Artist, Decade, Player, Playlist, Track, Work};
 use std::cell::Cell;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -26,7 +21,7 @@ use crate::components::context_menu::{MenuElement, context_menu};
 use crate::formatters;
 use crate::image_processing::Colors;
 use crate::pane_view::{PaneView, ViewContext};
-use crate::styles::svg_style;
+use crate::styles::{row_button_style, svg_style};
 
 type ArtKeys = HashMap<i64, (i64, PathBuf)>;
 
@@ -35,10 +30,48 @@ const PANEL_ART_PADDING: f32 = 8.0;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExpandedItem {
-    Album(String, Option<String>),
+    Album(i64),
+    Artist(i64),
+    Composer(i64),
+    Genre(i64),
+    Decade(u32),
     Playlist(i64),
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompilationFilter {
+    #[default]
+    All,
+    Hide,
+    Only,
+}
+
+impl CompilationFilter {
+    pub fn next(self) -> Self {
+        match self {
+            CompilationFilter::All => CompilationFilter::Hide,
+            CompilationFilter::Hide => CompilationFilter::Only,
+            CompilationFilter::Only => CompilationFilter::All,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            CompilationFilter::All => "All albums",
+            CompilationFilter::Hide => "Hide compilations",
+            CompilationFilter::Only => "Compilations only",
+        }
+    }
+
+    fn allows(self, album: &Album) -> bool {
+        match self {
+            CompilationFilter::All => true,
+            CompilationFilter::Hide => !album.compilation,
+            CompilationFilter::Only => album.compilation,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum CollectionsMessage {
     ToggleNewPlaylistInput,
@@ -48,24 +81,31 @@ pub enum CollectionsMessage {
     PlayPlaylist(i64),
     QueuePlaylistNext(i64),
     QueuePlaylistBack(i64),
-    ToggleAlbum(String, Option<String>),
+    ToggleAlbum(i64),
+    ToggleArtist(i64),
+    ToggleComposer(i64),
+    ToggleGenre(i64),
+    ToggleDecade(u32),
+    CycleCompilationFilter,
     TogglePlaylist(i64),
 }
 
 #[derive(Debug, Clone)]
 pub struct CollectionsPane {
     pub(crate) album_art_keys: ArtKeys,
+    pub(crate) artist_art_keys: ArtKeys,
     pub(crate) playlist_art_keys: ArtKeys,
-    albums_initialized: bool,
-    pub(crate) playlists_initialized: bool,
     thumbnail_size: Cell<u32>,
     panel_art_size: Cell<u32>,
     stable_size: u32,
     stable_ticks: u32,
     pub(crate) creating_playlist: bool,
     pub(crate) new_playlist_name: String,
+    pub(crate) compilation_filter: CompilationFilter,
     pub(crate) expanded: Option<ExpandedItem>,
-    pub(crate) expanded_tracks: Vec<Track>,
+    pub(crate) expanded_tracks: Option<Vec<Track>>,
+    pub(crate) expanded_albums: Vec<Album>,
+    pub(crate) expanded_works: Vec<Work>,
     pub(crate) expanded_cover: Option<(i64, PathBuf)>,
 }
 
@@ -73,54 +113,26 @@ impl CollectionsPane {
     pub fn new() -> Self {
         Self {
             album_art_keys: HashMap::new(),
+            artist_art_keys: HashMap::new(),
             playlist_art_keys: HashMap::new(),
-            albums_initialized: false,
-            playlists_initialized: false,
             thumbnail_size: Cell::new(0),
             panel_art_size: Cell::new(0),
             stable_size: 0,
             stable_ticks: 0,
             creating_playlist: false,
             new_playlist_name: String::new(),
+            compilation_filter: CompilationFilter::default(),
             expanded: None,
-            expanded_tracks: Vec::new(),
+            expanded_tracks: None,
+            expanded_albums: Vec::new(),
+            expanded_works: Vec::new(),
             expanded_cover: None,
         }
     }
 }
 
 impl PaneView for CollectionsPane {
-    fn update(&mut self, player: &Player, art: &mut ArtCache) {
-        if !self.albums_initialized {
-            if let Ok(albums) = player.query_all_albums() {
-                for album in &albums {
-                    if let Some(ref path_str) = album.sample_track_path {
-                        if let Ok(Some(track)) = player.query_track_from_path(path_str) {
-                            if let Some(tid) = track.id() {
-                                self.album_art_keys
-                                    .insert(album.id, (tid, track.path().to_path_buf()));
-                            }
-                        }
-                    }
-                }
-                self.albums_initialized = true;
-            }
-        }
-
-        if !self.playlists_initialized {
-            if let Ok(playlists) = player.get_all_playlists() {
-                for playlist in &playlists {
-                    if let Some(tid) = playlist.cover_track_id {
-                        if let Ok(Some(track)) = player.query_track_from_id(tid) {
-                            self.playlist_art_keys
-                                .insert(playlist.id, (tid, track.path().to_path_buf()));
-                        }
-                    }
-                }
-                self.playlists_initialized = true;
-            }
-        }
-
+    fn update(&mut self, _player: &Player, art: &mut ArtCache) {
         let size = self.thumbnail_size.get();
         if size > 0 {
             if size == self.stable_size {
@@ -130,10 +142,13 @@ impl PaneView for CollectionsPane {
                 self.stable_ticks = 0;
             }
             if self.stable_ticks >= DEBOUNCE_TICKS {
-                for (_, (tid, path)) in &self.album_art_keys {
+                for (tid, path) in self.album_art_keys.values() {
+                    art.get_or_queue(*tid, path, size, size);
+                }
+                for (tid, path) in self.artist_art_keys.values() {
                     art.get_or_queue(*tid, path, size, size);
                 }
-                for (_, (tid, path)) in &self.playlist_art_keys {
+                for (tid, path) in self.playlist_art_keys.values() {
                     art.get_or_queue(*tid, path, size, size);
                 }
                 let panel_size = self.panel_art_size.get();
@@ -148,26 +163,86 @@ impl PaneView for CollectionsPane {
 
     fn invalidate_cache(&mut self) {
         self.album_art_keys.clear();
+        self.artist_art_keys.clear();
         self.playlist_art_keys.clear();
-        self.albums_initialized = false;
-        self.playlists_initialized = false;
         self.expanded = None;
-        self.expanded_tracks.clear();
+        self.expanded_tracks = None;
+        self.expanded_albums.clear();
+        self.expanded_works.clear();
         self.expanded_cover = None;
     }
 
+    fn library_loaded(
+        &mut self,
+        tracks: &[Track],
+        albums: &[Album],
+        artists: &[Artist],
+        playlists: &[Playlist],
+    ) {
+        let by_id: HashMap<i64, &Track> =
+            tracks.iter().filter_map(|t| Some((t.id()?, t))).collect();
+        let by_path: HashMap<&str, i64> = tracks
+            .iter()
+            .filter_map(|t| Some((t.path().to_str()?, t.id()?)))
+            .collect();
+        let art_key = |tid: i64| by_id.get(&tid).map(|t| (tid, t.path().to_path_buf()));
+
+        self.album_art_keys = albums
+            .iter()
+            .filter_map(|album| {
+                let tid = by_path.get(album.sample_track_path.as_deref()?)?;
+                Some((album.id, art_key(*tid)?))
+            })
+            .collect();
+
+        self.artist_art_keys = artists
+            .iter()
+            .filter_map(|artist| {
+                let tid = by_path.get(artist.sample_track_path.as_deref()?)?;
+                Some((artist.id, art_key(*tid)?))
+            })
+            .collect();
+
+        self.playlist_art_keys = playlists
+            .iter()
+            .filter_map(|playlist| Some((playlist.id, art_key(playlist.cover_track_id?)?)))
+            .collect();
+    }
+
     fn view<'a>(&'a self, ctx: ViewContext<'a>) -> Element<'a, Message> {
         let art = ctx.art;
-        let albums = ctx.cached_albums.borrow().clone().unwrap_or_default();
-        let playlists = ctx.cached_playlists.borrow().clone().unwrap_or_default();
+        let composers = ctx.cached_composers.unwrap_or_default();
+        let genres = ctx.cached_genres.unwrap_or_default();
+        let decades = ctx.cached_decades.unwrap_or_default();
+        let (Some(albums), Some(artists), Some(playlists)) =
+            (ctx.cached_albums, ctx.cached_artists, ctx.cached_playlists)
+        else {
+            return container(
+                text("Loading collections…")
+                    .size(18)
+                    .style(|theme: &Theme| text::Style {
+                        color: Some(theme.extended_palette().background.base.text),
+                    }),
+            )
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into();
+        };
         let creating_playlist = self.creating_playlist;
         let new_playlist_name = self.new_playlist_name.as_str();
+        let compilation_filter = self.compilation_filter;
         let hovered_card = ctx.hovered_card;
 
         let album_art_keys = &self.album_art_keys;
+        let artist_art_keys = &self.artist_art_keys;
         let playlist_art_keys = &self.playlist_art_keys;
         let expanded = &self.expanded;
-        let expanded_tracks = &self.expanded_tracks;
+        let expanded_tracks = self.expanded_tracks.as_deref();
+        let expanded_albums = &self.expanded_albums;
+        let expanded_works = &self.expanded_works;
         let expanded_cover = &self.expanded_cover;
 
         responsive(move |size| {
@@ -192,7 +267,8 @@ impl PaneView for CollectionsPane {
             let panel_px = (panel_height - PANEL_ART_PADDING * 2.0).round() as u32;
             self.panel_art_size.set(panel_px);
 
-            if albums.is_empty() && playlists.is_empty() && !creating_playlist {
+            if albums.is_empty() && artists.is_empty() && playlists.is_empty() && !creating_playlist
+            {
                 return container(text("No albums or playlists").size(18).style(
                     |theme: &Theme| text::Style {
                         color: Some(theme.extended_palette().background.base.text),
@@ -209,9 +285,34 @@ impl PaneView for CollectionsPane {
             let mut content = column![].spacing(GAP * 2.0).padding(EDGE_PADDING);
 
             if !albums.is_empty() {
-                let mut albums_section = column![section_header("Albums")].spacing(GAP);
+                let albums_header = row![
+                    section_header("Albums"),
+                    space::Space::new().width(Length::Fill),
+                    button(text(compilation_filter.label()).size(12))
+                        .padding([2, 8])
+                        .on_press(Message::Collections(
+                            CollectionsMessage::CycleCompilationFilter,
+                        )),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(8);
+
+                let mut albums_section = column![albums_header].spacing(GAP);
+                let visible: Vec<&Album> = albums
+                    .iter()
+                    .filter(|album| compilation_filter.allows(album))
+                    .collect();
+
+                if visible.is_empty() {
+                    albums_section =
+                        albums_section.push(text("No matching albums").size(13).style(
+                            |theme: &Theme| text::Style {
+                                color: Some(theme.extended_palette().background.strong.text),
+                            },
+                        ));
+                }
 
-                for chunk in albums.chunks(cols) {
+                for chunk in visible.chunks(cols) {
                     let mut album_row = row![].spacing(GAP);
 
                     for album in chunk {
@@ -222,47 +323,34 @@ impl PaneView for CollectionsPane {
                                 .map(|e| e.colors)
                         });
                         let art_el = art_card(art, track_id, thumb_px, card_size);
-                        let album_name = album.name.clone();
-                        let artist = album.artist.clone();
-                        let is_hovered = hovered_card == Some((true, album.id));
+                        let aid = album.id;
+                        let is_hovered = hovered_card == Some((true, aid));
 
                         let card = card_with_overlay(
                             art_el,
                             card_size,
                             is_hovered,
                             colors,
-                            Message::PlayAlbum(album_name.clone(), artist.clone()),
-                            Message::QueueAlbumBack(album_name.clone(), artist.clone()),
-                            Message::Collections(CollectionsMessage::ToggleAlbum(
-                                album_name.clone(),
-                                artist.clone(),
-                            )),
-                            Message::CardHovered(true, album.id),
+                            Message::PlayAlbum(aid),
+                            Message::QueueAlbumBack(aid),
+                            Message::Collections(CollectionsMessage::ToggleAlbum(aid)),
+                            Message::CardHovered(true, aid),
                         );
 
                         album_row = album_row.push(context_menu(
                             card,
                             vec![
-                                MenuElement::button(
-                                    "Play",
-                                    Message::PlayAlbum(album_name.clone(), artist.clone()),
-                                ),
-                                MenuElement::button(
-                                    "Queue next",
-                                    Message::QueueAlbumNext(album_name.clone(), artist.clone()),
-                                ),
-                                MenuElement::button(
-                                    "Add to queue",
-                                    Message::QueueAlbumBack(album_name, artist),
-                                ),
+                                MenuElement::button("Play", Message::PlayAlbum(aid)),
+                                MenuElement::button("Queue next", Message::QueueAlbumNext(aid)),
+                                MenuElement::button("Add to queue", Message::QueueAlbumBack(aid)),
                             ],
                         ));
                     }
 
                     albums_section = albums_section.push(album_row);
 
-                    if let Some(ExpandedItem::Album(ref name, ref artist)) = *expanded {
-                        if chunk.iter().any(|a| &a.name == name) {
+                    if let Some(ExpandedItem::Album(aid)) = *expanded {
+                        if let Some(album) = chunk.iter().find(|a| a.id == aid) {
                             let cover_tid = expanded_cover.as_ref().map(|(tid, _)| *tid);
                             albums_section = albums_section.push(expanded_panel(
                                 expanded_tracks,
@@ -270,9 +358,9 @@ impl PaneView for CollectionsPane {
                                 art,
                                 cover_tid,
                                 panel_px,
-                                name.clone(),
-                                artist.clone(),
-                                Message::PlayAlbum(name.clone(), artist.clone()),
+                                album.name.clone(),
+                                album.artist.clone(),
+                                Message::PlayAlbum(aid),
                             ));
                         }
                     }
@@ -281,6 +369,225 @@ impl PaneView for CollectionsPane {
                 content = content.push(albums_section);
             }
 
+            if !artists.is_empty() {
+                let mut artists_section = column![section_header("Artists")].spacing(GAP);
+
+                for chunk in artists.chunks(cols) {
+                    let mut artist_row = row![].spacing(GAP);
+
+                    for artist in chunk {
+                        let track_id = artist_art_keys.get(&artist.id).map(|(tid, _)| *tid);
+                        let art_el = art_card(art, track_id, thumb_px, card_size);
+                        let arid = artist.id;
+
+                        let card = artist_card(
+                            artist,
+                            art_el,
+                            card_size,
+                            Message::Collections(CollectionsMessage::ToggleArtist(arid)),
+                        );
+
+                        artist_row = artist_row.push(context_menu(
+                            card,
+                            vec![
+                                MenuElement::button("Play", Message::PlayArtist(arid)),
+                                MenuElement::button("Queue next", Message::QueueArtistNext(arid)),
+                                MenuElement::button("Add to queue", Message::QueueArtistBack(arid)),
+                            ],
+                        ));
+                    }
+
+                    artists_section = artists_section.push(artist_row);
+
+                    if let Some(ExpandedItem::Artist(arid)) = *expanded {
+                        if let Some(artist) = chunk.iter().find(|a| a.id == arid) {
+                            let cover_tid = expanded_cover.as_ref().map(|(tid, _)| *tid);
+                            artists_section = artists_section.push(albums_panel(
+                                PanelHeader {
+                                    title: artist.name.as_str(),
+                                    summary: artist_summary(artist),
+                                    play: Message::PlayArtist(artist.id),
+                                },
+                                expanded_albums,
+                                album_art_keys,
+                                panel_height,
+                                art,
+                                cover_tid,
+                                panel_px,
+                            ));
+                        }
+                    }
+                }
+
+                content = content.push(artists_section);
+            }
+
+            if !composers.is_empty() {
+                let mut composers_section = column![section_header("Composers")].spacing(GAP);
+
+                for chunk in composers.chunks(cols) {
+                    let mut composer_row = row![].spacing(GAP);
+
+                    for composer in chunk {
+                        let track_id = artist_art_keys.get(&composer.id).map(|(tid, _)| *tid);
+                        let art_el = art_card(art, track_id, thumb_px, card_size);
+                        let cid = composer.id;
+
+                        let card = artist_card(
+                            composer,
+                            art_el,
+                            card_size,
+                            Message::Collections(CollectionsMessage::ToggleComposer(cid)),
+                        );
+
+                        composer_row = composer_row.push(context_menu(
+                            card,
+                            vec![
+                                MenuElement::button("Play", Message::PlayArtist(cid)),
+                                MenuElement::button("Queue next", Message::QueueArtistNext(cid)),
+                                MenuElement::button("Add to queue", Message::QueueArtistBack(cid)),
+                            ],
+                        ));
+                    }
+
+                    composers_section = composers_section.push(composer_row);
+
+                    if let Some(ExpandedItem::Composer(cid)) = *expanded {
+                        if let Some(composer) = chunk.iter().find(|c| c.id == cid) {
+                            let cover_tid = expanded_cover.as_ref().map(|(tid, _)| *tid);
+                            composers_section = composers_section.push(composer_panel(
+                                composer,
+                                expanded_works,
+                                panel_height,
+                                art,
+                                cover_tid,
+                                panel_px,
+                            ));
+                        }
+                    }
+                }
+
+                content = content.push(composers_section);
+            }
+
+            if !genres.is_empty() {
+                let mut genres_section = column![section_header("Genres")].spacing(GAP);
+                let max_tracks = genres.iter().map(|g| g.track_count).max().unwrap_or(1);
+
+                let cloud = row(genres.iter().map(|genre| {
+                    let gid = genre.id;
+                    let name = genre.name.clone();
+                    let selected = *expanded == Some(ExpandedItem::Genre(gid));
+                    context_menu(
+                        button(
+                            text(genre.name.as_str()).size(tag_size(genre.track_count, max_tracks)),
+                        )
+                        .padding([4, 10])
+                        .style(move |theme: &Theme, status| tag_style(theme, status, selected))
+                        .on_press(Message::Collections(CollectionsMessage::ToggleGenre(gid))),
+                        vec![
+                            MenuElement::button("Play", Message::PlayGenre(name.clone())),
+                            MenuElement::button(
+                                "Queue next",
+                                Message::QueueGenreNext(name.clone()),
+                            ),
+                            MenuElement::button("Add to queue", Message::QueueGenreBack(name)),
+                        ],
+                    )
+                }))
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .wrap()
+                .vertical_spacing(8);
+
+                genres_section = genres_section.push(cloud);
+
+                if let Some(ExpandedItem::Genre(gid)) = *expanded {
+                    if let Some(genre) = genres.iter().find(|g| g.id == gid) {
+                        genres_section = genres_section.push(albums_panel(
+                            PanelHeader {
+                                title: genre.name.as_str(),
+                                summary: browse_summary(
+                                    genre.album_count,
+                                    genre.track_count,
+                                    &genre.top_artists,
+                                ),
+                                play: Message::PlayGenre(genre.name.clone()),
+                            },
+                            expanded_albums,
+                            album_art_keys,
+                            panel_height,
+                            art,
+                            expanded_cover.as_ref().map(|(tid, _)| *tid),
+                            panel_px,
+                        ));
+                    }
+                }
+
+                content = content.push(genres_section);
+            }
+
+            if !decades.is_empty() {
+                let mut decades_section = column![section_header("Decades")].spacing(GAP);
+
+                let grid = row(decades.iter().map(|decade| {
+                    let start = decade.start;
+                    let selected = *expanded == Some(ExpandedItem::Decade(start));
+                    context_menu(
+                        button(
+                            column![
+                                text(decade.label()).size(18).font(Font {
+                                    weight: FontWeight::Bold,
+                                    ..Font::DEFAULT
+                                }),
+                                text(decade_summary(decade)).size(11),
+                            ]
+                            .spacing(2),
+                        )
+                        .padding([8, 12])
+                        .width(Length::Fixed(MIN_CARD_WIDTH))
+                        .style(move |theme: &Theme, status| tag_style(theme, status, selected))
+                        .on_press(Message::Collections(
+                            CollectionsMessage::ToggleDecade(start),
+                        )),
+                        vec![
+                            MenuElement::button("Play", Message::PlayDecade(start)),
+                            MenuElement::button("Queue next", Message::QueueDecadeNext(start)),
+                            MenuElement::button("Add to queue", Message::QueueDecadeBack(start)),
+                        ],
+                    )
+                }))
+                .spacing(GAP)
+                .wrap()
+                .vertical_spacing(GAP);
+
+                decades_section = decades_section.push(grid);
+
+                if let Some(ExpandedItem::Decade(start)) = *expanded {
+                    if let Some(decade) = decades.iter().find(|d| d.start == start) {
+                        decades_section = decades_section.push(albums_panel(
+                            PanelHeader {
+                                title: &decade.label(),
+                                summary: browse_summary(
+                                    decade.album_count,
+                                    decade.track_count,
+                                    &decade.top_artists,
+                                ),
+                                play: Message::PlayDecade(start),
+                            },
+                            expanded_albums,
+                            album_art_keys,
+                            panel_height,
+                            art,
+                            expanded_cover.as_ref().map(|(tid, _)| *tid),
+                            panel_px,
+                        ));
+                    }
+                }
+
+                content = content.push(decades_section);
+            }
+
             let playlists_header = row![
                 section_header("Playlists"),
                 space::Space::new().width(Length::Fill),
@@ -426,31 +733,29 @@ fn art_card<'a>(
     card_size: f32,
 ) -> Element<'a, Message> {
     match track_id.and_then(|id| art.get(id, thumb_px, thumb_px).or_else(|| art.get_any(id))) {
-<<<<<<< HEAD
-        Some(entry) => container(
-            image(entry.handle.clone())
-                .width(Length::Fixed(card_size - 1.0))
-                .height(Length::Fixed(card_size))
-                .content_fit(iced::ContentFit::Cover),
-        )
-        .padding(iced::Padding {
-            left: 1.0,
-            ..Default::default()
-        })
-        .width(Length::Fixed(card_size))
-        .height(Length::Fixed(card_size))
-        .into(),
-=======
         Some(entry) => image(entry.handle.clone())
             .width(Length::Fixed(card_size))
             .height(Length::Fixed(card_size))
             .content_fit(ContentFit::Cover)
             .into(),
->>>>>>> 823ddd72eaa64cde5db469f3bfd0239bc38f69db
         None => placeholder_artwork(card_size),
     }
 }
 
+fn artist_summary(artist: &Artist) -> String {
+    let albums = if artist.album_count == 1 {
+        "1 album".to_string()
+    } else {
+        format!("{} albums", artist.album_count)
+    };
+    let tracks = if artist.track_count == 1 {
+        "1 track".to_string()
+    } else {
+        format!("{} tracks", artist.track_count)
+    };
+    format!("{albums} · {tracks}")
+}
+
 fn section_header<'a, Message: 'a>(label: &'a str) -> Element<'a, Message> {
     text(label)
         .size(13)
@@ -460,8 +765,72 @@ fn section_header<'a, Message: 'a>(label: &'a str) -> Element<'a, Message> {
         .into()
 }
 
+fn browse_summary(album_count: i64, track_count: i64, top_artists: &[String]) -> String {
+    let albums = if album_count == 1 {
+        "1 album".to_string()
+    } else {
+        format!("{album_count} albums")
+    };
+    let tracks = if track_count == 1 {
+        "1 track".to_string()
+    } else {
+        format!("{track_count} tracks")
+    };
+    if top_artists.is_empty() {
+        format!("{albums} · {tracks}")
+    } else {
+        format!("{albums} · {tracks} · {}", top_artists.join(", "))
+    }
+}
+
+fn decade_summary(decade: &Decade) -> String {
+    let tracks = if decade.track_count == 1 {
+        "1 track".to_string()
+    } else {
+        format!("{} tracks", decade.track_count)
+    };
+    format!(
+        "{tracks} · {}",
+        formatters::format_duration(decade.duration)
+    )
+}
+
+fn tag_size(track_count: i64, max_tracks: i64) -> f32 {
+    const MIN_SIZE: f32 = 12.0;
+    const MAX_SIZE: f32 = 24.0;
+    if max_tracks <= 1 {
+        return MIN_SIZE;
+    }
+    let weight = (track_count.max(1) as f32).ln() / (max_tracks as f32).ln();
+    (MIN_SIZE + (MAX_SIZE - MIN_SIZE) * weight).round()
+}
+
+fn tag_style(theme: &Theme, status: button::Status, selected: bool) -> button::Style {
+    let palette = theme.extended_palette();
+    let background = if selected {
+        palette.primary.base.color
+    } else if matches!(status, button::Status::Hovered | button::Status::Pressed) {
+        palette.background.strong.color
+    } else {
+        palette.background.weak.color
+    };
+    button::Style {
+        background: Some(background.into()),
+        text_color: if selected {
+            palette.primary.base.text
+        } else {
+            palette.background.base.text
+        },
+        border: Border {
+            radius: 4.0.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
 fn expanded_panel<'a>(
-    tracks: &'a [Track],
+    tracks: Option<&'a [Track]>,
     panel_height: f32,
     art: &'a ArtCache,
     cover_track_id: Option<i64>,
@@ -470,66 +839,20 @@ fn expanded_panel<'a>(
     artist: Option<String>,
     play_msg: Message,
 ) -> Element<'a, Message> {
-    let art_display_size = panel_height - PANEL_ART_PADDING * 2.0;
+    let (cover, cover_color) = panel_cover(art, cover_track_id, panel_height, panel_px);
+    let panel_style = panel_style(cover_color);
 
-    let entry =
-        cover_track_id.and_then(|id| art.get(id, panel_px, panel_px).or_else(|| art.get_any(id)));
-
-    let cover_color: Option<[u8; 3]> = entry.map(|e| e.colors.background);
-
-    let panel_style = move |theme: &Theme| {
-        let palette = theme.extended_palette();
-        let bg_color = palette.background.weak.color;
-        let background = match cover_color {
-            Some([r, g, b]) => {
-                let left = Color::from_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
-                Background::Gradient(Gradient::Linear(
-                    gradient::Linear::new(Radians(std::f32::consts::FRAC_PI_2))
-                        .add_stop(0.0, left)
-                        .add_stop(1.0, bg_color),
-                ))
-            }
-            None => Background::Color(bg_color),
+    let Some(tracks) = tracks.filter(|tracks| !tracks.is_empty()) else {
+        let status = if tracks.is_some() {
+            "No tracks"
+        } else {
+            "Loading tracks…"
         };
-        container::Style {
-            background: Some(background),
-            border: Border {
-                color: palette.background.strong.color,
-                width: 1.0,
-                ..Default::default()
-            },
-            ..Default::default()
-        }
-    };
-
-    let cover: Element<'a, Message> = match entry {
-        Some(entry) => container(
-            image(entry.handle.clone())
-                .width(Length::Fixed(art_display_size))
-                .height(Length::Fixed(art_display_size))
-                .content_fit(ContentFit::Cover),
-        )
-        .padding(PANEL_ART_PADDING)
-        .width(Length::Fixed(panel_height))
-        .height(Length::Fixed(panel_height))
-        .into(),
-        None => container(placeholder_artwork(art_display_size))
-            .padding(PANEL_ART_PADDING)
-            .width(Length::Fixed(panel_height))
-            .height(Length::Fixed(panel_height))
-            .into(),
-    };
-
-    if tracks.is_empty() {
         return container(row![
             cover,
-            container(
-                text("No tracks")
-                    .size(12)
-                    .style(|theme: &Theme| text::Style {
-                        color: Some(theme.extended_palette().background.strong.text),
-                    })
-            )
+            container(text(status).size(12).style(|theme: &Theme| text::Style {
+                color: Some(theme.extended_palette().background.strong.text),
+            }))
             .center_x(Length::Fill)
             .center_y(Length::Fill)
             .width(Length::Fill),
@@ -538,22 +861,12 @@ fn expanded_panel<'a>(
         .height(Length::Fixed(panel_height))
         .style(panel_style)
         .into();
-    }
+    };
 
     let muted = |theme: &Theme| text::Style {
         color: Some(theme.extended_palette().background.strong.text),
     };
 
-    let separator = || {
-        container(space::Space::new())
-            .height(Length::Fixed(2.0))
-            .width(Length::Fill)
-            .style(|theme: &Theme| container::Style {
-                background: Some(Color::from_rgba(1.0, 1.0, 1.0, 0.05).into()),
-                ..Default::default()
-            })
-    };
-
     let total_secs: u32 = tracks.iter().map(|t| t.duration() as u32).sum();
     let total_duration = {
         let h = total_secs / 3600;
@@ -603,7 +916,7 @@ fn expanded_panel<'a>(
     for (i, track) in tracks.iter().enumerate() {
         if let Some(tid) = track.id() {
             if !first {
-                track_col = track_col.push(separator());
+                track_col = track_col.push(panel_separator());
             }
             first = false;
 
@@ -634,23 +947,7 @@ fn expanded_panel<'a>(
                 )
                 .padding([7, 12])
                 .width(Length::Fill)
-                .style(|theme: &Theme, status| {
-                    let palette = theme.extended_palette();
-                    button::Style {
-                        background: match status {
-                            button::Status::Hovered | button::Status::Pressed => Some(
-                                Color {
-                                    a: 0.15,
-                                    ..palette.primary.weak.color
-                                }
-                                .into(),
-                            ),
-                            _ => None,
-                        },
-                        text_color: palette.background.base.text,
-                        ..Default::default()
-                    }
-                })
+                .style(row_button_style)
                 .on_press(Message::PlayTrack(tid)),
                 vec![
                     MenuElement::button("Play", Message::PlayTrack(tid)),
@@ -670,7 +967,7 @@ fn expanded_panel<'a>(
             scrollable::Scrollbar::new().width(0).scroller_width(0),
         ));
 
-    let right = column![play_all, separator(), track_list].spacing(0);
+    let right = column![play_all, panel_separator(), track_list].spacing(0);
 
     container(row![cover, right])
         .width(Length::Fill)
@@ -679,6 +976,335 @@ fn expanded_panel<'a>(
         .into()
 }
 
+fn panel_cover<'a>(
+    art: &'a ArtCache,
+    cover_track_id: Option<i64>,
+    panel_height: f32,
+    panel_px: u32,
+) -> (Element<'a, Message>, Option<[u8; 3]>) {
+    let art_display_size = panel_height - PANEL_ART_PADDING * 2.0;
+
+    let entry =
+        cover_track_id.and_then(|id| art.get(id, panel_px, panel_px).or_else(|| art.get_any(id)));
+
+    let cover_color: Option<[u8; 3]> = entry.map(|e| e.colors.background);
+
+    let cover: Element<'a, Message> = match entry {
+        Some(entry) => container(
+            image(entry.handle.clone())
+                .width(Length::Fixed(art_display_size))
+                .height(Length::Fixed(art_display_size))
+                .content_fit(ContentFit::Cover),
+        )
+        .padding(PANEL_ART_PADDING)
+        .width(Length::Fixed(panel_height))
+        .height(Length::Fixed(panel_height))
+        .into(),
+        None => container(placeholder_artwork(art_display_size))
+            .padding(PANEL_ART_PADDING)
+            .width(Length::Fixed(panel_height))
+            .height(Length::Fixed(panel_height))
+            .into(),
+    };
+
+    (cover, cover_color)
+}
+
+fn panel_style(cover_color: Option<[u8; 3]>) -> impl Fn(&Theme) -> container::Style + Copy {
+    move |theme: &Theme| {
+        let palette = theme.extended_palette();
+        let bg_color = palette.background.weak.color;
+        let background = match cover_color {
+            Some([r, g, b]) => {
+                let left = Color::from_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+                Background::Gradient(Gradient::Linear(
+                    gradient::Linear::new(Radians(std::f32::consts::FRAC_PI_2))
+                        .add_stop(0.0, left)
+                        .add_stop(1.0, bg_color),
+                ))
+            }
+            None => Background::Color(bg_color),
+        };
+        container::Style {
+            background: Some(background),
+            border: Border {
+                color: palette.background.strong.color,
+                width: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+fn panel_separator<'a>() -> container::Container<'a, Message> {
+    container(space::Space::new())
+        .height(Length::Fixed(2.0))
+        .width(Length::Fill)
+        .style(|_: &Theme| container::Style {
+            background: Some(Color::from_rgba(1.0, 1.0, 1.0, 0.05).into()),
+            ..Default::default()
+        })
+}
+
+struct PanelHeader<'a> {
+    title: &'a str,
+    summary: String,
+    play: Message,
+}
+
+fn albums_panel<'a>(
+    PanelHeader {
+        title,
+        summary,
+        play,
+    }: PanelHeader<'_>,
+    albums: &'a [Album],
+    album_art_keys: &'a ArtKeys,
+    panel_height: f32,
+    art: &'a ArtCache,
+    cover_track_id: Option<i64>,
+    panel_px: u32,
+) -> Element<'a, Message> {
+    let (cover, cover_color) = panel_cover(art, cover_track_id, panel_height, panel_px);
+
+    let muted = |theme: &Theme| text::Style {
+        color: Some(theme.extended_palette().background.strong.text),
+    };
+
+    let header = container(
+        row![
+            crate::widgets::canvas_button::canvas_button(
+                svg(SvgHandle::from_memory(include_bytes!(
+                    "../../../assets/icons/play.svg"
+                )))
+                .style(svg_style),
+            )
+            .width(28)
+            .height(28)
+            .on_press(play),
+            text(title.to_string()).size(14).font(Font {
+                weight: FontWeight::Bold,
+                ..Font::DEFAULT
+            }),
+            space::Space::new().width(Length::Fill),
+            text(summary)
+                .size(11)
+                .style(muted)
+                .align_x(Horizontal::Right),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+    )
+    .padding([6, 10]);
+
+    let mut album_col = column![].spacing(0);
+
+    for (i, album) in albums.iter().enumerate() {
+        if i > 0 {
+            album_col = album_col.push(panel_separator());
+        }
+
+        let aid = album.id;
+        let thumb: Element<'a, Message> = match album_art_keys
+            .get(&aid)
+            .and_then(|(tid, _)| art.get_any(*tid))
+        {
+            Some(entry) => image(entry.handle.clone())
+                .width(Length::Fixed(28.0))
+                .height(Length::Fixed(28.0))
+                .content_fit(ContentFit::Cover)
+                .into(),
+            None => placeholder_artwork(28.0),
+        };
+        let year = album.year.map(|y| y.to_string()).unwrap_or_default();
+        let duration = formatters::format_duration(album.duration);
+
+        album_col = album_col.push(context_menu(
+            button(
+                row![
+                    thumb,
+                    text(album.name.as_str()).size(13).width(Length::Fill),
+                    text(year)
+                        .size(11)
+                        .align_x(Horizontal::Right)
+                        .style(muted)
+                        .width(Length::Fixed(40.0)),
+                    text(duration)
+                        .size(11)
+                        .align_x(Horizontal::Right)
+                        .style(muted)
+                        .width(Length::Fixed(46.0)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            )
+            .padding([4, 12])
+            .width(Length::Fill)
+            .style(row_button_style)
+            .on_press(Message::PlayAlbum(aid)),
+            vec![
+                MenuElement::button("Play", Message::PlayAlbum(aid)),
+                MenuElement::button("Queue next", Message::QueueAlbumNext(aid)),
+                MenuElement::button("Add to queue", Message::QueueAlbumBack(aid)),
+            ],
+        ));
+    }
+
+    let album_list = scrollable(album_col)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .direction(scrollable::Direction::Vertical(
+            scrollable::Scrollbar::new().width(0).scroller_width(0),
+        ));
+
+    container(row![
+        cover,
+        column![header, panel_separator(), album_list].spacing(0)
+    ])
+    .width(Length::Fill)
+    .height(Length::Fixed(panel_height))
+    .style(panel_style(cover_color))
+    .into()
+}
+
+fn artist_card<'a>(
+    artist: &'a Artist,
+    art: Element<'a, Message>,
+    card_size: f32,
+    toggle_msg: Message,
+) -> Element<'a, Message> {
+    column![
+        container(
+            button(art)
+                .padding(0)
+                .width(Length::Fixed(card_size))
+                .height(Length::Fixed(card_size))
+                .style(|_, _| button::Style::default())
+                .on_press(toggle_msg),
+        )
+        .width(Length::Fixed(card_size))
+        .height(Length::Fixed(card_size))
+        .clip(true),
+        text(artist.name.as_str()).size(13),
+        text(artist_summary(artist))
+            .size(11)
+            .style(|theme: &Theme| text::Style {
+                color: Some(theme.extended_palette().background.strong.text),
+            }),
+    ]
+    .spacing(4)
+    .width(Length::Fixed(card_size))
+    .into()
+}
+
+fn composer_panel<'a>(
+    composer: &'a Artist,
+    works: &'a [Work],
+    panel_height: f32,
+    art: &'a ArtCache,
+    cover_track_id: Option<i64>,
+    panel_px: u32,
+) -> Element<'a, Message> {
+    let (cover, cover_color) = panel_cover(art, cover_track_id, panel_height, panel_px);
+
+    let muted = |theme: &Theme| text::Style {
+        color: Some(theme.extended_palette().background.strong.text),
+    };
+
+    let header = container(
+        row![
+            crate::widgets::canvas_button::canvas_button(
+                svg(SvgHandle::from_memory(include_bytes!(
+                    "../../../assets/icons/play.svg"
+                )))
+                .style(svg_style),
+            )
+            .width(28)
+            .height(28)
+            .on_press(Message::PlayArtist(composer.id)),
+            text(composer.name.as_str()).size(14).font(Font {
+                weight: FontWeight::Bold,
+                ..Font::DEFAULT
+            }),
+            space::Space::new().width(Length::Fill),
+            text(if works.len() == 1 {
+                "1 work".to_string()
+            } else {
+                format!("{} works", works.len())
+            })
+            .size(11)
+            .style(muted)
+            .align_x(Horizontal::Right),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+    )
+    .padding([6, 10]);
+
+    let mut work_col = column![].spacing(0);
+
+    for (i, work) in works.iter().enumerate() {
+        if i > 0 {
+            work_col = work_col.push(panel_separator());
+        }
+
+        let cid = work.composer_id;
+        let name = work.name.clone();
+        let movements = if work.track_count == 1 {
+            "1 track".to_string()
+        } else {
+            format!("{} tracks", work.track_count)
+        };
+        let duration = formatters::format_duration(work.duration);
+
+        work_col = work_col.push(context_menu(
+            button(
+                row![
+                    text(work.name.as_str()).size(13).width(Length::Fill),
+                    text(movements)
+                        .size(11)
+                        .align_x(Horizontal::Right)
+                        .style(muted)
+                        .width(Length::Fixed(60.0)),
+                    text(duration)
+                        .size(11)
+                        .align_x(Horizontal::Right)
+                        .style(muted)
+                        .width(Length::Fixed(46.0)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            )
+            .padding([6, 12])
+            .width(Length::Fill)
+            .style(row_button_style)
+            .on_press(Message::PlayWork(cid, name.clone())),
+            vec![
+                MenuElement::button("Play", Message::PlayWork(cid, name.clone())),
+                MenuElement::button("Queue next", Message::QueueWorkNext(cid, name.clone())),
+                MenuElement::button("Add to queue", Message::QueueWorkBack(cid, name)),
+            ],
+        ));
+    }
+
+    let work_list = scrollable(work_col)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .direction(scrollable::Direction::Vertical(
+            scrollable::Scrollbar::new().width(0).scroller_width(0),
+        ));
+
+    container(row![
+        cover,
+        column![header, panel_separator(), work_list].spacing(0)
+    ])
+    .width(Length::Fill)
+    .height(Length::Fixed(panel_height))
+    .style(panel_style(cover_color))
+    .into()
+}
+
 fn card_with_overlay<'a>(
     art: Element<'a, Message>,
     card_size: f32,
@@ -706,44 +1332,6 @@ fn card_with_overlay<'a>(
             color: Some(Color::WHITE),
         };
 
-<<<<<<< HEAD
-        container(
-            row![
-                crate::widgets::canvas_button::canvas_button(
-                    svg(SvgHandle::from_memory(include_bytes!(
-                        "../../../assets/icons/play.svg"
-                    )))
-                    .style(icon_style),
-                )
-                .width(20)
-                .height(20)
-                .on_press(play_msg),
-                crate::widgets::canvas_button::canvas_button(
-                    svg(SvgHandle::from_memory(include_bytes!(
-                        "../../../assets/icons/queue_add.svg"
-                    )))
-                    .style(icon_style),
-                )
-                .width(20)
-                .height(20)
-                .on_press(queue_msg),
-            ]
-            .spacing(6),
-        )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .padding(5)
-        .align_x(iced::alignment::Horizontal::Center)
-        .align_y(Vertical::Bottom)
-        .style(move |_: &Theme| container::Style {
-            background: Some(iced::Background::Gradient(iced::Gradient::Linear(
-                iced::gradient::Linear::new(Radians(std::f32::consts::PI))
-                    .add_stop(0.0, Color::TRANSPARENT)
-                    .add_stop(1.0, Color::BLACK),
-            ))),
-            ..Default::default()
-        })
-=======
         column![
             space::Space::new().height(Length::Fill),
             container(
@@ -784,7 +1372,6 @@ fn card_with_overlay<'a>(
         ]
         .width(Length::Fixed(card_size))
         .height(Length::Fixed(card_size))
->>>>>>> 823ddd72eaa64cde5db469f3bfd0239bc38f69db
         .into()
     } else {
         space::Space::new()
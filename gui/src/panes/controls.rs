@@ -1,9 +1,9 @@
 use iced::alignment::Vertical;
 use iced::font::Weight;
 use iced::widget::svg::Handle as SvgHandle;
-use iced::widget::{column, container, responsive, row, space, svg, text, tooltip};
+use iced::widget::{column, container, responsive, row, space, stack, svg, text, tooltip};
 use iced::{Element, Font, Length, Theme};
-use verse_core::Player;
+use verse_core::{MAX_SPEED, MIN_SPEED, Player, StopAfter, Tempo, TempoScope};
 
 use crate::app::Message;
 use crate::art_cache::ArtCache;
@@ -11,7 +11,13 @@ use crate::formatters::format_duration;
 use crate::pane_view::{PaneView, ViewContext};
 use crate::styles::{TOOLTIP_DELAY, svg_style};
 use crate::widgets::canvas_button::canvas_button;
+use crate::widgets::chapter_ticks::chapter_ticks;
 use crate::widgets::hover_slider::hover_slider;
+use crate::widgets::menu::{menu_item, menu_separator, styled_menu};
+use crate::widgets::menu_button::MenuButton;
+
+const STOP_AFTER_TRACKS: [u32; 3] = [2, 5, 10];
+const SLEEP_MINUTES: [u64; 4] = [15, 30, 60, 90];
 
 #[derive(Debug, Clone)]
 pub struct ControlsPane;
@@ -35,6 +41,8 @@ impl PaneView for ControlsPane {
         let actual_position = player.position() as f32;
         let position = seeking_position.unwrap_or(actual_position);
 
+        let chapter_starts: Vec<f32> = player.chapters().iter().map(|c| c.start).collect();
+
         let (title, artist, duration) = if let Some(track) = current_track {
             (
                 track.title().unwrap_or("-").to_string(),
@@ -143,6 +151,67 @@ impl PaneView for ControlsPane {
         .step(0.01)
         .width(Length::Fixed(100.0));
 
+        let tempo = player.tempo();
+
+        let speed_slider = hover_slider(
+            MIN_SPEED as f32..=MAX_SPEED as f32,
+            tempo.speed as f32,
+            |v| Message::Controls(ControlsMessage::SpeedChanged(v)),
+        )
+        .step(0.05)
+        .width(Length::Fixed(60.0));
+
+        let speed_menu = column![
+            menu_item(
+                if tempo.preserve_pitch {
+                    "Change pitch with speed"
+                } else {
+                    "Preserve pitch"
+                },
+                Message::Controls(ControlsMessage::TogglePreservePitch),
+            ),
+            menu_item(
+                "Transpose up",
+                Message::Controls(ControlsMessage::Transpose(1.0)),
+            ),
+            menu_item(
+                "Transpose down",
+                Message::Controls(ControlsMessage::Transpose(-1.0)),
+            ),
+            menu_item(
+                "Reset speed and pitch",
+                Message::Controls(ControlsMessage::ResetTempo),
+            ),
+            menu_separator(),
+            menu_item(
+                "Remember for track",
+                Message::Controls(ControlsMessage::RememberTempo(TempoScope::Track)),
+            ),
+            menu_item(
+                "Remember for genre",
+                Message::Controls(ControlsMessage::RememberTempo(TempoScope::Genre)),
+            ),
+            menu_item(
+                "Forget for track",
+                Message::Controls(ControlsMessage::ForgetTempo(TempoScope::Track)),
+            ),
+            menu_item(
+                "Forget for genre",
+                Message::Controls(ControlsMessage::ForgetTempo(TempoScope::Genre)),
+            ),
+        ];
+
+        let speed_button = MenuButton::new(
+            include_bytes!("../../../assets/icons/speed.svg"),
+            styled_menu(speed_menu),
+        );
+
+        let speed_label = text(format_tempo(tempo))
+            .size(12)
+            .style(|theme: &Theme| text::Style {
+                color: Some(theme.extended_palette().background.base.text),
+            });
+
         let shuffle_button = tooltip(
             canvas_button(
                 svg(SvgHandle::from_memory(include_bytes!(
@@ -185,6 +254,42 @@ impl PaneView for ControlsPane {
         .delay(TOOLTIP_DELAY)
         .snap_within_viewport(true);
 
+        let mut stop_menu = column![menu_item(
+            "Stop after this track",
+            Message::Controls(ControlsMessage::SetStopAfter(StopAfter::Track)),
+        )];
+        for n in STOP_AFTER_TRACKS {
+            stop_menu = stop_menu.push(menu_item(
+                format!("Stop after {n} tracks"),
+                Message::Controls(ControlsMessage::SetStopAfter(StopAfter::Tracks(n))),
+            ));
+        }
+        stop_menu = stop_menu
+            .push(menu_item(
+                "Stop after this album",
+                Message::Controls(ControlsMessage::SetStopAfter(StopAfter::Album)),
+            ))
+            .push(menu_separator());
+        for minutes in SLEEP_MINUTES {
+            stop_menu = stop_menu.push(menu_item(
+                format!("Sleep in {minutes} minutes"),
+                Message::Controls(ControlsMessage::StartSleepTimer(minutes)),
+            ));
+        }
+        if player.has_pending_stop() {
+            stop_menu = stop_menu.push(menu_separator()).push(menu_item(
+                "Cancel",
+                Message::Controls(ControlsMessage::CancelPendingStop),
+            ));
+        }
+
+        let sleep_button = MenuButton::new(
+            include_bytes!("../../../assets/icons/moon.svg"),
+            styled_menu(stop_menu),
+        );
+
+        let pending_stop = pending_stop_label(player);
+
         let time_text = format!(
             "{} / {}",
             format_duration(position),
@@ -205,6 +310,8 @@ impl PaneView for ControlsPane {
             })
             .on_release(Message::Controls(ControlsMessage::SeekReleased))
             .width(Length::Fill);
+            let seek_bar =
+                stack![timeline_slider].push_under(chapter_ticks(chapter_starts.clone(), duration));
 
             container(
                 column![
@@ -226,16 +333,27 @@ impl PaneView for ControlsPane {
                                 color: Some(theme.extended_palette().background.base.text),
                             }
                         }),
-                        container(text(time_text.clone()).size(14).style(|theme: &Theme| {
-                            text::Style {
-                                color: Some(theme.extended_palette().background.base.text),
-                            }
-                        }))
+                        container(
+                            row![
+                                text(pending_stop.clone().unwrap_or_default())
+                                    .size(12)
+                                    .style(|theme: &Theme| text::Style {
+                                        color: Some(theme.extended_palette().primary.base.color),
+                                    }),
+                                text(time_text.clone()).size(14).style(|theme: &Theme| {
+                                    text::Style {
+                                        color: Some(theme.extended_palette().background.base.text),
+                                    }
+                                }),
+                            ]
+                            .spacing(10)
+                            .align_y(Vertical::Center)
+                        )
                         .width(Length::Fill)
                         .align_right(Length::Fill),
                     ]
                     .align_y(Vertical::Center),
-                    timeline_slider,
+                    seek_bar,
                 ]
                 .spacing(0)
                 .width(Length::Fill),
@@ -252,10 +370,15 @@ impl PaneView for ControlsPane {
             vol_button,
             volume_slider,
             space().width(Length::Fixed(20.0)),
+            speed_button,
+            speed_slider,
+            speed_label,
+            space().width(Length::Fixed(20.0)),
             track_info,
             space().width(Length::Fixed(20.0)),
             shuffle_button,
             cycle_button,
+            sleep_button,
         ]
         .spacing(10)
         .padding(15)
@@ -288,6 +411,38 @@ pub enum ControlsMessage {
     SeekReleased,
     ShuffleQueue,
     CycleLoopMode,
+    SetStopAfter(StopAfter),
+    StartSleepTimer(u64),
+    CancelPendingStop,
+    SpeedChanged(f32),
+    Transpose(f64),
+    TogglePreservePitch,
+    ResetTempo,
+    RememberTempo(TempoScope),
+    ForgetTempo(TempoScope),
+}
+
+fn format_tempo(tempo: Tempo) -> String {
+    if tempo.semitones == 0.0 {
+        format!("{:.2}x", tempo.speed)
+    } else {
+        format!("{:.2}x {:+}st", tempo.speed, tempo.semitones)
+    }
+}
+
+fn pending_stop_label(player: &Player) -> Option<String> {
+    if let Some(remaining) = player.sleep_remaining() {
+        return Some(format!(
+            "Sleep in {}",
+            format_duration(remaining.as_secs_f32())
+        ));
+    }
+
+    player.stop_after().map(|stop_after| match stop_after {
+        StopAfter::Track => "Stops after track".to_string(),
+        StopAfter::Tracks(n) => format!("Stops after {n} tracks"),
+        StopAfter::Album => "Stops after album".to_string(),
+    })
 }
 
 fn truncate_text(text: &str, max_chars: usize) -> String {
@@ -0,0 +1,306 @@
+use iced::alignment::Horizontal;
+use iced::widget::svg::Handle as SvgHandle;
+use iced::widget::{button, column, container, row, scrollable, space, svg, text, text_input};
+use iced::{Alignment, Element, Length, Theme};
+use verse_core::{
+    EqBand, EqPreset, FilterKind, GRAPHIC_FREQUENCIES, MAX_GAIN_DB, MAX_PARAMETRIC_BANDS, Player,
+};
+
+use crate::app::Message;
+use crate::art_cache::ArtCache;
+use crate::pane_view::{PaneView, ViewContext};
+use crate::styles::svg_style;
+use crate::widgets::canvas_button::canvas_button;
+use crate::widgets::hover_slider::{hover_slider, hover_vertical_slider};
+use crate::widgets::menu::{menu_item, menu_label, menu_separator, styled_menu};
+use crate::widgets::menu_button::MenuButton;
+
+const MIN_FREQ: f32 = 20.0;
+const MAX_FREQ: f32 = 20000.0;
+const MIN_Q: f32 = 0.1;
+const MAX_Q: f32 = 10.0;
+
+#[derive(Debug, Clone)]
+pub struct EqualizerPane {
+    pub preset_name: String,
+}
+
+impl EqualizerPane {
+    pub fn new() -> Self {
+        Self {
+            preset_name: String::new(),
+        }
+    }
+}
+
+impl PaneView for EqualizerPane {
+    fn update(&mut self, _player: &Player, _art: &mut ArtCache) {}
+
+    fn view<'a>(&'a self, ctx: ViewContext<'a>) -> Element<'a, Message> {
+        let player = ctx.player;
+        let presets = player.eq_presets();
+        let current = player.equalizer().cloned();
+        let flat = EqPreset::flat("Custom");
+        let editing = current.as_ref().unwrap_or(&flat);
+
+        let label_style = |theme: &Theme| text::Style {
+            color: Some(theme.extended_palette().background.base.text),
+        };
+
+        let current_label = match &current {
+            Some(preset) if presets.contains(preset) => preset.name.clone(),
+            Some(preset) => format!("{} (modified)", preset.name),
+            None => "Off".to_string(),
+        };
+
+        let mut preset_menu = column![menu_item(
+            "Off",
+            Message::Equalizer(EqualizerMessage::SelectPreset(None))
+        )];
+        for preset in &presets {
+            preset_menu = preset_menu.push(menu_item(
+                preset.name.clone(),
+                Message::Equalizer(EqualizerMessage::SelectPreset(Some(preset.name.clone()))),
+            ));
+        }
+        if let Some(preset) = current.as_ref().filter(|p| !p.is_builtin()) {
+            preset_menu = preset_menu.push(menu_separator()).push(menu_item(
+                format!("Delete \"{}\"", preset.name),
+                Message::Equalizer(EqualizerMessage::DeletePreset(preset.name.clone())),
+            ));
+        }
+        if let Some(device) = player.output_device() {
+            preset_menu = preset_menu
+                .push(menu_separator())
+                .push(menu_label(format!("Remembered for {}", device)));
+        }
+
+        let preset_button = MenuButton::new(
+            include_bytes!("../../../assets/icons/equalizer.svg"),
+            styled_menu(preset_menu),
+        );
+
+        let name = self.preset_name.trim().to_string();
+        let header = row![
+            preset_button,
+            text(current_label).size(13).style(label_style),
+            space().width(Length::Fill),
+            text_input("Preset name…", &self.preset_name)
+                .on_input(|s| Message::Equalizer(EqualizerMessage::PresetNameChanged(s)))
+                .on_submit(Message::Equalizer(EqualizerMessage::SavePreset(
+                    name.clone()
+                )))
+                .padding(6)
+                .width(Length::Fixed(160.0)),
+            button(text("Save").size(13))
+                .padding([6, 12])
+                .on_press_maybe(
+                    (!name.is_empty())
+                        .then(|| Message::Equalizer(EqualizerMessage::SavePreset(name.clone()))),
+                ),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let mut graphic = row![gain_slider(
+            "Pre".to_string(),
+            editing.preamp_db,
+            EqualizerMessage::PreampChanged,
+        )]
+        .spacing(6)
+        .height(Length::Fixed(180.0));
+
+        for (i, (&frequency, &gain)) in GRAPHIC_FREQUENCIES
+            .iter()
+            .zip(editing.graphic.iter())
+            .enumerate()
+        {
+            graphic = graphic.push(gain_slider(format_frequency(frequency), gain, move |v| {
+                EqualizerMessage::GraphicChanged(i, v)
+            }));
+        }
+
+        let mut bands = column![].spacing(8);
+        for (i, band) in editing.bands.iter().enumerate() {
+            bands = bands.push(band_row(i, *band));
+        }
+        if editing.bands.len() < MAX_PARAMETRIC_BANDS {
+            bands = bands.push(
+                canvas_button(
+                    svg(SvgHandle::from_memory(include_bytes!(
+                        "../../../assets/icons/plus.svg"
+                    )))
+                    .width(18)
+                    .height(18)
+                    .style(svg_style),
+                )
+                .padding(4.0)
+                .on_press(Message::Equalizer(EqualizerMessage::AddBand)),
+            );
+        }
+
+        container(
+            scrollable(
+                column![
+                    header,
+                    graphic,
+                    text("Parametric bands").size(12).style(label_style),
+                    bands,
+                ]
+                .spacing(14)
+                .padding(10),
+            )
+            .height(Length::Fill),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn PaneView> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EqualizerMessage {
+    SelectPreset(Option<String>),
+    PreampChanged(f32),
+    GraphicChanged(usize, f32),
+    BandChanged(usize, EqBand),
+    AddBand,
+    RemoveBand(usize),
+    PresetNameChanged(String),
+    SavePreset(String),
+    DeletePreset(String),
+}
+
+fn gain_slider<'a>(
+    label: String,
+    gain: f32,
+    on_change: impl Fn(f32) -> EqualizerMessage + 'a,
+) -> Element<'a, Message> {
+    let label_style = |theme: &Theme| text::Style {
+        color: Some(theme.extended_palette().background.base.text),
+    };
+
+    column![
+        text(format!("{:+.0}", gain)).size(11).style(label_style),
+        hover_vertical_slider(-MAX_GAIN_DB..=MAX_GAIN_DB, gain, move |v| {
+            Message::Equalizer(on_change(v))
+        })
+        .step(0.5)
+        .height(Length::Fill),
+        text(label).size(11).style(label_style),
+    ]
+    .spacing(4)
+    .width(Length::Fixed(36.0))
+    .align_x(Horizontal::Center)
+    .into()
+}
+
+fn band_row<'a>(index: usize, band: EqBand) -> Element<'a, Message> {
+    let label_style = |theme: &Theme| text::Style {
+        color: Some(theme.extended_palette().background.base.text),
+    };
+
+    let mut kind_menu = column![];
+    for kind in FilterKind::ALL {
+        kind_menu = kind_menu.push(menu_item(
+            kind.name(),
+            Message::Equalizer(EqualizerMessage::BandChanged(
+                index,
+                EqBand { kind, ..band },
+            )),
+        ));
+    }
+    kind_menu = kind_menu.push(menu_separator()).push(menu_item(
+        "Remove",
+        Message::Equalizer(EqualizerMessage::RemoveBand(index)),
+    ));
+
+    let frequency = hover_slider(0.0..=1.0, frequency_to_slider(band.frequency), move |v| {
+        Message::Equalizer(EqualizerMessage::BandChanged(
+            index,
+            EqBand {
+                frequency: slider_to_frequency(v),
+                ..band
+            },
+        ))
+    })
+    .step(0.001);
+
+    let gain = hover_slider(-MAX_GAIN_DB..=MAX_GAIN_DB, band.gain_db, move |v| {
+        Message::Equalizer(EqualizerMessage::BandChanged(
+            index,
+            EqBand { gain_db: v, ..band },
+        ))
+    })
+    .step(0.5);
+
+    let q = hover_slider(MIN_Q..=MAX_Q, band.q, move |v| {
+        Message::Equalizer(EqualizerMessage::BandChanged(
+            index,
+            EqBand { q: v, ..band },
+        ))
+    })
+    .step(0.01);
+
+    let mut controls = row![
+        MenuButton::new(
+            include_bytes!("../../../assets/icons/kebab.svg"),
+            styled_menu(kind_menu),
+        ),
+        text(band.kind.name())
+            .size(12)
+            .width(Length::Fixed(70.0))
+            .style(label_style),
+        text(format_frequency(band.frequency))
+            .size(12)
+            .width(Length::Fixed(44.0))
+            .style(label_style),
+        frequency,
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    if band.kind.has_gain() {
+        controls = controls.push(
+            text(format!("{:+.1} dB", band.gain_db))
+                .size(12)
+                .width(Length::Fixed(56.0))
+                .style(label_style),
+        );
+        controls = controls.push(gain);
+    }
+
+    controls
+        .push(
+            text(format!("Q {:.2}", band.q))
+                .size(12)
+                .width(Length::Fixed(48.0))
+                .style(label_style),
+        )
+        .push(q)
+        .into()
+}
+
+fn frequency_to_slider(frequency: f32) -> f32 {
+    (frequency / MIN_FREQ).ln() / (MAX_FREQ / MIN_FREQ).ln()
+}
+
+fn slider_to_frequency(value: f32) -> f32 {
+    MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(value)
+}
+
+fn format_frequency(frequency: f32) -> String {
+    if frequency >= 1000.0 {
+        format!("{}k", (frequency / 100.0).round() / 10.0)
+    } else {
+        format!("{}", frequency.round())
+    }
+}
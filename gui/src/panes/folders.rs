@@ -0,0 +1,267 @@
+use iced::alignment::Horizontal;
+use iced::widget::{button, column, container, mouse_area, row, scrollable, text};
+use iced::{Alignment, Element, Length, Theme};
+use verse_core::{Album, Artist, Folder, Player, Playlist, Track};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::app::Message;
+use crate::art_cache::ArtCache;
+use crate::components::context_menu::{MenuElement, context_menu};
+use crate::components::track_menu::track_actions;
+use crate::formatters;
+use crate::pane_view::{PaneView, ViewContext};
+use crate::styles::row_button_style;
+
+#[derive(Debug, Clone)]
+pub enum FoldersMessage {
+    Open(PathBuf),
+    Up,
+}
+
+#[derive(Debug, Clone)]
+pub struct FoldersPane {
+    current: Option<PathBuf>,
+    track_index: HashMap<i64, usize>,
+}
+
+impl FoldersPane {
+    pub fn new() -> Self {
+        Self {
+            current: None,
+            track_index: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn open(&mut self, path: PathBuf) {
+        self.current = Some(path);
+    }
+
+    pub(crate) fn up(&mut self, tree: &[Folder]) {
+        let Some(current) = &self.current else {
+            return;
+        };
+        let is_root = tree.iter().any(|root| &root.path == current);
+        self.current = if is_root {
+            None
+        } else {
+            current.parent().map(PathBuf::from)
+        };
+    }
+
+    pub(crate) fn tree_loaded(&mut self, tree: &[Folder]) {
+        if self.current_folder(tree).is_none() {
+            self.current = None;
+        }
+    }
+
+    fn current_folder<'a>(&self, tree: &'a [Folder]) -> Option<&'a Folder> {
+        let current = self.current.as_ref()?;
+        tree.iter().find_map(|root| root.find(current))
+    }
+}
+
+fn folder_summary(folder: &Folder) -> String {
+    let tracks = if folder.track_count == 1 {
+        "1 track".to_string()
+    } else {
+        format!("{} tracks", folder.track_count)
+    };
+    format!(
+        "{tracks} · {}",
+        formatters::format_duration(folder.duration)
+    )
+}
+
+fn muted(theme: &Theme) -> text::Style {
+    text::Style {
+        color: Some(theme.extended_palette().background.strong.text),
+    }
+}
+
+fn folder_row<'a>(folder: &'a Folder, label: String) -> Element<'a, Message> {
+    let path = folder.path.clone();
+
+    context_menu(
+        button(
+            row![
+                text(label).size(13).width(Length::Fill),
+                text(folder_summary(folder))
+                    .size(11)
+                    .style(muted)
+                    .align_x(Horizontal::Right),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        )
+        .padding([8, 12])
+        .width(Length::Fill)
+        .style(row_button_style)
+        .on_press(Message::Folders(FoldersMessage::Open(path.clone()))),
+        vec![
+            MenuElement::button("Play", Message::PlayFolder(path.clone())),
+            MenuElement::button("Queue next", Message::QueueFolderNext(path.clone())),
+            MenuElement::button("Add to queue", Message::QueueFolderBack(path)),
+        ],
+    )
+}
+
+impl PaneView for FoldersPane {
+    fn update(&mut self, _player: &Player, _art: &mut ArtCache) {}
+
+    fn view<'a>(&'a self, ctx: ViewContext<'a>) -> Element<'a, Message> {
+        let hovered_track = ctx.hovered_track;
+        let playlists = ctx.cached_playlists.unwrap_or_default();
+        let tracks = ctx.cached_tracks.unwrap_or_default();
+
+        let Some(tree) = ctx.cached_folders else {
+            return container(text("Loading folders…").size(18))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into();
+        };
+
+        if tree.is_empty() {
+            return container(text("No folders").size(14).style(muted))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into();
+        }
+
+        let mut list = column![].spacing(0);
+
+        let Some(folder) = self.current_folder(tree) else {
+            for root in tree {
+                list = list.push(folder_row(root, root.path.display().to_string()));
+            }
+            return scrollable(list)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into();
+        };
+
+        let path = folder.path.clone();
+        let header = container(
+            row![
+                button(text("↑").size(14))
+                    .padding([2, 8])
+                    .on_press(Message::Folders(FoldersMessage::Up)),
+                text(folder.name.as_str()).size(14).width(Length::Fill),
+                text(folder_summary(folder)).size(11).style(muted),
+                button(text("Play").size(12))
+                    .padding([2, 8])
+                    .on_press(Message::PlayFolder(path.clone())),
+                button(text("Queue").size(12))
+                    .padding([2, 8])
+                    .on_press(Message::QueueFolderBack(path)),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        )
+        .padding([6, 10])
+        .width(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            text_color: Some(theme.extended_palette().background.strong.text),
+            background: Some(theme.extended_palette().background.strong.color.into()),
+            ..Default::default()
+        });
+
+        for child in &folder.folders {
+            list = list.push(folder_row(child, format!("{}/", child.name)));
+        }
+
+        for &track_id in &folder.track_ids {
+            let Some(track) = self.track_index.get(&track_id).and_then(|&i| tracks.get(i)) else {
+                continue;
+            };
+            let is_hovered = hovered_track.as_ref() == Some(&track_id);
+            let file_name = track
+                .path()
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let track_inner = container(
+                row![
+                    text(file_name).size(13).width(Length::Fill),
+                    text(track.title().unwrap_or("-").to_string())
+                        .size(11)
+                        .style(muted)
+                        .width(Length::Fill),
+                    text(formatters::format_duration(track.duration()))
+                        .size(11)
+                        .style(muted)
+                        .align_x(Horizontal::Right)
+                        .width(Length::Fixed(46.0)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            )
+            .padding([8, 12])
+            .width(Length::Fill)
+            .style(move |theme: &Theme| {
+                let palette = theme.extended_palette();
+                container::Style {
+                    text_color: Some(palette.background.base.text),
+                    background: is_hovered.then(|| palette.primary.weak.color.into()),
+                    ..Default::default()
+                }
+            });
+
+            let track_content = mouse_area(track_inner)
+                .on_move(move |_| Message::TrackHovered(track_id))
+                .on_double_click(Message::PlayTrack(track_id));
+
+            list = list.push(context_menu(
+                track_content,
+                track_actions(&[track_id], playlists),
+            ));
+        }
+
+        mouse_area(
+            column![
+                header,
+                scrollable(list)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .direction(scrollable::Direction::Vertical(
+                        scrollable::Scrollbar::new().width(0).scroller_width(0),
+                    )),
+            ]
+            .width(Length::Fill)
+            .height(Length::Fill),
+        )
+        .on_exit(Message::TrackUnhovered)
+        .into()
+    }
+
+    fn invalidate_cache(&mut self) {
+        self.track_index.clear();
+    }
+
+    fn library_loaded(
+        &mut self,
+        tracks: &[Track],
+        _albums: &[Album],
+        _artists: &[Artist],
+        _playlists: &[Playlist],
+    ) {
+        self.track_index = tracks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| Some((t.id()?, i)))
+            .collect();
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn PaneView> {
+        Box::new(self.clone())
+    }
+}
@@ -1,21 +1,283 @@
-use iced::alignment::Horizontal;
-use iced::widget::{button, column, container, mouse_area, row, scrollable, text};
-use iced::{Element, Length, Theme};
-use verse_core::Player;
+use iced::alignment::{Horizontal, Vertical};
+use iced::keyboard::{Modifiers, key};
+use iced::widget::{button, column, container, mouse_area, responsive, row, space, text};
+use iced::{Element, Length, Theme, mouse};
+use std::cell::Cell;
+use std::collections::HashSet;
+use verse_core::{Album, Artist, Player, Playlist, Track};
 
 use crate::app::Message;
 use crate::art_cache::ArtCache;
 use crate::components::context_menu::{MenuElement, context_menu};
+use crate::components::track_menu::track_actions;
+use crate::config::{ColumnConfig, MIN_COLUMN_WIDTH, TrackColumn};
 use crate::formatters;
 use crate::pane_view::{PaneView, ViewContext};
 
+const ROW_HEIGHT: f32 = 32.0;
+const EDGE_PADDING: f32 = 10.0;
+const COLUMN_GAP: f32 = 10.0;
+const LINES_PER_NOTCH: f32 = 3.0;
+
+#[derive(Debug, Clone)]
+pub enum LibraryMessage {
+    Sort(TrackColumn),
+    Scrolled(mouse::ScrollDelta),
+    Select(usize),
+    SelectAll,
+    Navigate(Navigation),
+    ResizeStart(TrackColumn),
+    HeaderMoved(f32),
+    ResizeEnd,
+    ToggleColumn(TrackColumn),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Navigation {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+impl Navigation {
+    pub fn from_key(named: &key::Named) -> Option<Self> {
+        match named {
+            key::Named::ArrowUp => Some(Navigation::Up),
+            key::Named::ArrowDown => Some(Navigation::Down),
+            key::Named::PageUp => Some(Navigation::PageUp),
+            key::Named::PageDown => Some(Navigation::PageDown),
+            key::Named::Home => Some(Navigation::Home),
+            key::Named::End => Some(Navigation::End),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct LibraryPane;
+pub struct LibraryPane {
+    sort: Option<(TrackColumn, bool)>,
+    order: Vec<usize>,
+    first_row: usize,
+    scroll_remainder: f32,
+    cursor: Option<usize>,
+    selected: HashSet<i64>,
+    anchor: Option<i64>,
+    visible_rows: Cell<usize>,
+    pub(crate) resizing: Option<TrackColumn>,
+}
 
 impl LibraryPane {
     pub fn new() -> Self {
-        Self
+        Self {
+            sort: None,
+            order: Vec::new(),
+            first_row: 0,
+            scroll_remainder: 0.0,
+            cursor: None,
+            selected: HashSet::new(),
+            anchor: None,
+            visible_rows: Cell::new(1),
+            resizing: None,
+        }
+    }
+
+    pub(crate) fn sort_by(&mut self, column: TrackColumn, tracks: &[Track]) {
+        self.sort = match self.sort {
+            Some((current, ascending)) if current == column => Some((column, !ascending)),
+            _ => Some((column, true)),
+        };
+        let selected = self.cursor.and_then(|pos| self.order.get(pos).copied());
+        self.rebuild_order(tracks);
+        self.cursor = selected.and_then(|index| self.order.iter().position(|&i| i == index));
+        if let Some(cursor) = self.cursor {
+            self.reveal(cursor);
+        }
+    }
+
+    pub(crate) fn scroll(&mut self, delta: mouse::ScrollDelta) {
+        let rows = match delta {
+            mouse::ScrollDelta::Lines { y, .. } => -y * LINES_PER_NOTCH,
+            mouse::ScrollDelta::Pixels { y, .. } => -y / ROW_HEIGHT,
+        } + self.scroll_remainder;
+        let whole = rows.trunc();
+        self.scroll_remainder = rows - whole;
+        self.first_row = (self.first_row as isize + whole as isize).max(0) as usize;
+        self.clamp_scroll();
+    }
+
+    pub(crate) fn select(&mut self, position: usize, modifiers: Modifiers, tracks: &[Track]) {
+        let Some(track_id) = self.track_at(position, tracks) else {
+            return;
+        };
+
+        if modifiers.shift() {
+            self.select_range(position, !modifiers.command(), tracks);
+        } else if modifiers.command() {
+            if !self.selected.remove(&track_id) {
+                self.selected.insert(track_id);
+            }
+            self.anchor = Some(track_id);
+        } else {
+            self.selected.clear();
+            self.selected.insert(track_id);
+            self.anchor = Some(track_id);
+        }
+        self.cursor = Some(position);
+    }
+
+    pub(crate) fn select_all(&mut self, tracks: &[Track]) {
+        self.selected = tracks.iter().filter_map(Track::id).collect();
     }
+
+    pub(crate) fn selected_tracks(&self, tracks: &[Track]) -> Vec<i64> {
+        self.order
+            .iter()
+            .filter_map(|&i| tracks.get(i)?.id())
+            .filter(|id| self.selected.contains(id))
+            .collect()
+    }
+
+    fn select_range(&mut self, position: usize, replace: bool, tracks: &[Track]) {
+        let anchor = self
+            .anchor
+            .and_then(|id| {
+                self.order
+                    .iter()
+                    .position(|&i| tracks.get(i).and_then(Track::id) == Some(id))
+            })
+            .unwrap_or(position);
+        let (start, end) = (anchor.min(position), anchor.max(position));
+
+        if replace {
+            self.selected.clear();
+        }
+        for &index in &self.order[start..=end] {
+            if let Some(id) = tracks.get(index).and_then(Track::id) {
+                self.selected.insert(id);
+            }
+        }
+        if self.anchor.is_none() {
+            self.anchor = self.track_at(position, tracks);
+        }
+    }
+
+    fn track_at(&self, position: usize, tracks: &[Track]) -> Option<i64> {
+        tracks.get(*self.order.get(position)?)?.id()
+    }
+
+    pub(crate) fn navigate(&mut self, navigation: Navigation, extend: bool, tracks: &[Track]) {
+        let Some(last) = self.order.len().checked_sub(1) else {
+            return;
+        };
+        let page = self.visible_rows.get().saturating_sub(1).max(1);
+        let current = self.cursor.unwrap_or(self.first_row);
+        let next = match (navigation, self.cursor) {
+            (Navigation::Up | Navigation::Down, None) => current,
+            (Navigation::Up, _) => current.saturating_sub(1),
+            (Navigation::Down, _) => current + 1,
+            (Navigation::PageUp, _) => current.saturating_sub(page),
+            (Navigation::PageDown, _) => current + page,
+            (Navigation::Home, _) => 0,
+            (Navigation::End, _) => last,
+        }
+        .min(last);
+        if extend {
+            self.select_range(next, true, tracks);
+            self.cursor = Some(next);
+        } else {
+            self.select(next, Modifiers::empty(), tracks);
+        }
+        self.reveal(next);
+    }
+
+    pub(crate) fn cursor_track(&self, tracks: &[Track]) -> Option<i64> {
+        self.track_at(self.cursor?, tracks)
+    }
+
+    fn reveal(&mut self, position: usize) {
+        let rows = self.visible_rows.get().max(1);
+        if position < self.first_row {
+            self.first_row = position;
+        } else if position >= self.first_row + rows {
+            self.first_row = position + 1 - rows;
+        }
+        self.clamp_scroll();
+    }
+
+    fn clamp_scroll(&mut self) {
+        let max_first = self
+            .order
+            .len()
+            .saturating_sub(self.visible_rows.get().max(1));
+        self.first_row = self.first_row.min(max_first);
+    }
+
+    fn rebuild_order(&mut self, tracks: &[Track]) {
+        self.order = (0..tracks.len()).collect();
+        if let Some((column, ascending)) = self.sort {
+            sort_order(&mut self.order, tracks, column, ascending);
+        }
+    }
+}
+
+pub(crate) fn resize_width(columns: &[ColumnConfig], column: TrackColumn, x: f32) -> f32 {
+    let start: f32 = EDGE_PADDING
+        + columns
+            .iter()
+            .filter(|c| c.visible)
+            .take_while(|c| c.column != column)
+            .map(|c| c.width + COLUMN_GAP)
+            .sum::<f32>();
+    (x - start).max(MIN_COLUMN_WIDTH)
+}
+
+fn sort_order(order: &mut [usize], tracks: &[Track], column: TrackColumn, ascending: bool) {
+    let folded = |value: Option<&str>| value.map(str::to_lowercase);
+    match column {
+        TrackColumn::Title => order.sort_by_cached_key(|&i| folded(tracks[i].title())),
+        TrackColumn::Artist => order.sort_by_cached_key(|&i| folded(tracks[i].track_artist())),
+        TrackColumn::Album => order.sort_by_cached_key(|&i| folded(tracks[i].album())),
+        TrackColumn::Duration => {
+            order.sort_by(|&a, &b| tracks[a].duration().total_cmp(&tracks[b].duration()))
+        }
+        TrackColumn::Year => order.sort_by_key(|&i| tracks[i].year()),
+        TrackColumn::DateAdded => order.sort_by_key(|&i| tracks[i].added_at()),
+    }
+    if !ascending {
+        order.reverse();
+    }
+}
+
+fn cell_text(track: &Track, column: TrackColumn, work_titles: bool) -> String {
+    match column {
+        TrackColumn::Title => work_titles
+            .then(|| track.work_title())
+            .flatten()
+            .unwrap_or_else(|| track.title().unwrap_or("-").to_string()),
+        TrackColumn::Artist => track.track_artist().unwrap_or("-").to_string(),
+        TrackColumn::Album => track.album().unwrap_or("-").to_string(),
+        TrackColumn::Duration => formatters::format_duration(track.duration()),
+        TrackColumn::Year => track
+            .year()
+            .map(|y| y.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        TrackColumn::DateAdded => formatters::format_date(track.added_at()),
+    }
+}
+
+fn status(label: &str) -> Element<'_, Message> {
+    container(text(label).size(18).style(|theme: &Theme| text::Style {
+        color: Some(theme.extended_palette().background.base.text),
+    }))
+    .padding(20)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x(Length::Fill)
+    .center_y(Length::Fill)
+    .into()
 }
 
 impl PaneView for LibraryPane {
@@ -23,7 +285,12 @@ impl PaneView for LibraryPane {
 
     fn view<'a>(&'a self, ctx: ViewContext<'a>) -> Element<'a, Message> {
         let hovered_track = ctx.hovered_track;
-        let cached_tracks = ctx.cached_tracks.borrow().clone().unwrap_or_default();
+        let columns = ctx.library_columns;
+        let work_titles = ctx.work_titles;
+
+        let Some(cached_tracks) = ctx.cached_tracks else {
+            return status("Loading library…");
+        };
 
         if cached_tracks.is_empty() {
             return container(
@@ -48,123 +315,186 @@ impl PaneView for LibraryPane {
             .into();
         }
 
-        let header = container(
-            row![
-                container(text("Title").size(12).style(|theme: &Theme| text::Style {
-                    color: Some(theme.extended_palette().background.strong.text),
-                }))
-                .width(Length::FillPortion(3)),
-                container(text("Artist").size(12).style(|theme: &Theme| text::Style {
-                    color: Some(theme.extended_palette().background.strong.text),
-                }))
-                .width(Length::FillPortion(2)),
-                container(text("Album").size(12).style(|theme: &Theme| text::Style {
-                    color: Some(theme.extended_palette().background.strong.text),
-                }))
-                .width(Length::FillPortion(2)),
-                container(
-                    text("Duration")
-                        .size(12)
-                        .style(|theme: &Theme| text::Style {
-                            color: Some(theme.extended_palette().background.strong.text),
-                        })
+        let visible_columns: Vec<ColumnConfig> =
+            columns.iter().filter(|c| c.visible).copied().collect();
+
+        let mut header_row = row![].height(Length::Fill);
+        for config in &visible_columns {
+            let arrow = match self.sort {
+                Some((column, true)) if column == config.column => " ▲",
+                Some((column, false)) if column == config.column => " ▼",
+                _ => "",
+            };
+            header_row = header_row
+                .push(
+                    mouse_area(
+                        container(text(format!("{}{}", config.column.title(), arrow)).size(12))
+                            .width(Length::Fixed(config.width))
+                            .height(Length::Fill)
+                            .align_y(Vertical::Center)
+                            .clip(true),
+                    )
+                    .on_press(Message::Library(LibraryMessage::Sort(config.column))),
                 )
-                .width(Length::Fixed(80.0)),
-            ]
-            .spacing(10),
-        )
-        .padding(10)
-        .width(Length::Fill)
-        .style(|theme: &Theme| container::Style {
-            text_color: Some(theme.extended_palette().background.strong.text),
-            background: Some(theme.extended_palette().background.strong.color.into()),
-            ..Default::default()
-        });
+                .push(
+                    mouse_area(
+                        container(space())
+                            .width(Length::Fixed(COLUMN_GAP))
+                            .height(Length::Fill),
+                    )
+                    .interaction(mouse::Interaction::ResizingHorizontally)
+                    .on_press(Message::Library(LibraryMessage::ResizeStart(config.column))),
+                );
+        }
 
-        let mut track_list = column![].spacing(0);
+        let header = container(header_row)
+            .padding([0.0, EDGE_PADDING])
+            .height(Length::Fixed(ROW_HEIGHT))
+            .width(Length::Fill)
+            .style(|theme: &Theme| container::Style {
+                text_color: Some(theme.extended_palette().background.strong.text),
+                background: Some(theme.extended_palette().background.strong.color.into()),
+                ..Default::default()
+            });
 
-        for track in cached_tracks.iter() {
-            if let Some(track_id) = track.id() {
-                let duration_text = formatters::format_duration(track.duration());
-                let is_hovered = hovered_track.as_ref() == Some(&track_id);
+        let header = mouse_area(header)
+            .on_move(|point| Message::Library(LibraryMessage::HeaderMoved(point.x)))
+            .on_release(Message::Library(LibraryMessage::ResizeEnd))
+            .on_exit(Message::Library(LibraryMessage::ResizeEnd));
 
-                let track_inner = container(
-                    row![
-                        container(text(track.title().unwrap_or("-").to_string()).size(12))
-                            .width(Length::FillPortion(3)),
-                        container(text(track.track_artist().unwrap_or("-").to_string()).size(12))
-                            .width(Length::FillPortion(2)),
-                        container(text(track.album().unwrap_or("-").to_string()).size(12))
-                            .width(Length::FillPortion(2)),
-                        container(text(duration_text).size(12)).width(Length::Fixed(80.0)),
-                    ]
-                    .spacing(10),
+        let column_items = columns
+            .iter()
+            .map(|c| {
+                let mark = if c.visible { "✓ " } else { "   " };
+                MenuElement::button(
+                    format!("{}{}", mark, c.column.title()),
+                    Message::Library(LibraryMessage::ToggleColumn(c.column)),
                 )
-                .padding(10)
-                .width(Length::Fill)
-                .style(move |theme: &Theme| {
-                    let palette = theme.extended_palette();
-                    container::Style {
-                        text_color: Some(palette.background.base.text),
-                        background: if is_hovered {
-                            Some(palette.primary.weak.color.into())
-                        } else {
-                            Some(palette.background.base.color.into())
-                        },
-                        ..Default::default()
-                    }
-                });
+            })
+            .collect();
+        let header = context_menu(header, column_items);
 
-                let track_content =
-                    mouse_area(track_inner).on_move(move |_| Message::TrackHovered(track_id));
+        let playlists = ctx.cached_playlists.unwrap_or_default();
+        let selected_ids = self.selected_tracks(cached_tracks);
 
-                let playlists = ctx.cached_playlists.borrow().clone().unwrap_or_default();
+        let body = responsive(move |size| {
+            let rows = ((size.height / ROW_HEIGHT).ceil() as usize).max(1);
+            self.visible_rows.set(rows);
 
-                let mut menu_items: Vec<MenuElement<Message>> = Vec::new();
+            let first = self.first_row.min(self.order.len().saturating_sub(1));
+            let mut track_list = column![].spacing(0);
 
-                if playlists.is_empty() {
-                    menu_items.push(MenuElement::label("No playlists"));
-                } else {
-                    for playlist in &playlists {
-                        menu_items.push(MenuElement::button(
-                            format!("Add to \"{}\"", playlist.name),
-                            Message::AddTrackToPlaylist(track_id, playlist.id),
-                        ));
-                    }
+            for (position, &index) in self.order.iter().enumerate().skip(first).take(rows) {
+                let Some(track) = cached_tracks.get(index) else {
+                    continue;
+                };
+                let Some(track_id) = track.id() else {
+                    continue;
+                };
+                let is_hovered = hovered_track.as_ref() == Some(&track_id);
+                let is_cursor = self.cursor == Some(position);
+                let is_selected = self.selected.contains(&track_id);
+
+                let mut cells = row![].spacing(COLUMN_GAP).height(Length::Fill);
+                for config in &visible_columns {
+                    cells = cells.push(
+                        container(text(cell_text(track, config.column, work_titles)).size(12))
+                            .width(Length::Fixed(config.width))
+                            .height(Length::Fill)
+                            .align_y(Vertical::Center)
+                            .clip(true),
+                    );
                 }
 
+                let track_inner = container(cells)
+                    .padding([0.0, EDGE_PADDING])
+                    .height(Length::Fixed(ROW_HEIGHT))
+                    .width(Length::Fill)
+                    .style(move |theme: &Theme| {
+                        let palette = theme.extended_palette();
+                        let (background, text_color) = if is_selected {
+                            (palette.primary.strong.color, palette.primary.strong.text)
+                        } else if is_cursor {
+                            (
+                                palette.background.strong.color,
+                                palette.background.strong.text,
+                            )
+                        } else if is_hovered {
+                            (palette.primary.weak.color, palette.primary.weak.text)
+                        } else {
+                            (palette.background.base.color, palette.background.base.text)
+                        };
+                        container::Style {
+                            text_color: Some(text_color),
+                            background: Some(background.into()),
+                            ..Default::default()
+                        }
+                    });
+
+                let track_content = mouse_area(track_inner)
+                    .on_move(move |_| Message::TrackHovered(track_id))
+                    .on_press(Message::Library(LibraryMessage::Select(position)))
+                    .on_double_click(Message::PlayTrack(track_id));
+
+                let targets: &[i64] = if is_selected {
+                    &selected_ids
+                } else {
+                    &[track_id]
+                };
+                let mut menu_items = track_actions(targets, playlists);
                 menu_items.push(MenuElement::Separator);
-                menu_items.push(MenuElement::button("Play", Message::PlayTrack(track_id)));
                 menu_items.push(MenuElement::button(
-                    "Queue next",
-                    Message::QueueFront(track_id),
+                    "Select all",
+                    Message::Library(LibraryMessage::SelectAll),
                 ));
-                menu_items.push(MenuElement::button("Queue", Message::QueueBack(track_id)));
-                menu_items.push(MenuElement::Separator);
                 menu_items.push(MenuElement::button("Queue library", Message::QueueLibrary));
 
-                let track_row = context_menu(track_content, menu_items);
-
-                track_list = track_list.push(track_row);
+                track_list = track_list.push(context_menu(track_content, menu_items));
             }
-        }
+
+            mouse_area(
+                container(track_list)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .clip(true),
+            )
+            .on_scroll(|delta| Message::Library(LibraryMessage::Scrolled(delta)))
+            .into()
+        });
 
         mouse_area(
-            column![
-                header,
-                scrollable(track_list).height(Length::Fill).direction(
-                    scrollable::Direction::Vertical(
-                        scrollable::Scrollbar::new().width(0).scroller_width(0),
-                    )
-                )
-            ]
-            .width(Length::Fill)
-            .height(Length::Fill),
+            column![header, body]
+                .width(Length::Fill)
+                .height(Length::Fill),
         )
         .on_exit(Message::TrackUnhovered)
         .into()
     }
 
+    fn library_loaded(
+        &mut self,
+        tracks: &[Track],
+        _albums: &[Album],
+        _artists: &[Artist],
+        _playlists: &[Playlist],
+    ) {
+        if self.order.len() != tracks.len() {
+            self.cursor = None;
+        }
+        let existing: HashSet<i64> = tracks.iter().filter_map(Track::id).collect();
+        self.selected.retain(|id| existing.contains(id));
+        self.rebuild_order(tracks);
+        self.clamp_scroll();
+    }
+
+    fn invalidate_cache(&mut self) {
+        self.order.clear();
+        self.cursor = None;
+        self.selected.clear();
+        self.anchor = None;
+        self.first_row = 0;
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
@@ -2,7 +2,9 @@ mod artwork;
 pub mod collections;
 pub mod controls;
 mod empty;
-mod library;
+pub mod equalizer;
+pub mod folders;
+pub mod library;
 mod queue;
 mod spectrum;
 mod timeline;
@@ -13,9 +15,11 @@ pub use artwork::ArtworkPane;
 pub use collections::{CollectionsMessage, CollectionsPane};
 pub use controls::{ControlsMessage, ControlsPane};
 pub use empty::EmptyPane;
-pub use library::LibraryPane;
-pub use queue::QueuePane;
+pub use equalizer::{EqualizerMessage, EqualizerPane};
+pub use folders::{FoldersMessage, FoldersPane};
+pub use library::{LibraryMessage, LibraryPane, Navigation};
+pub use queue::{QueueMessage, QueuePane};
 pub use spectrum::SpectrumPane;
-pub use timeline::TimelinePane;
-pub use track_info::TrackInfoPane;
+pub use timeline::{TimelineMessage, TimelinePane};
+pub use track_info::{TrackInfoMessage, TrackInfoPane};
 pub use vu_meters::VUMetersPane;
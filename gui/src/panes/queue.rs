@@ -1,31 +1,93 @@
 use iced::font::Weight;
+use iced::keyboard::Modifiers;
 use iced::widget::{column, container, mouse_area, rule, scrollable, text};
 use iced::{Element, Font, Length, Theme};
-use verse_core::Player;
+use std::collections::{BTreeSet, HashMap};
+use verse_core::{Album, Artist, Player, Playlist, Track};
 
 use crate::app::Message;
 use crate::art_cache::ArtCache;
 use crate::components::context_menu::{MenuElement, context_menu};
+use crate::components::track_menu::track_actions;
 use crate::pane_view::{PaneView, ViewContext};
 use crate::panes::ControlsMessage;
 
 const MAX_DISPLAY: usize = 100;
 
+#[derive(Debug, Clone, Copy)]
+pub enum QueueMessage {
+    Select(usize),
+    SelectAll,
+}
+
 #[derive(Debug, Clone)]
-pub struct QueuePane;
+pub struct QueuePane {
+    selected: BTreeSet<usize>,
+    anchor: Option<usize>,
+    snapshot: (Option<i64>, Vec<i64>),
+    track_index: HashMap<i64, usize>,
+}
 
 impl QueuePane {
     pub fn new() -> Self {
-        Self
+        Self {
+            selected: BTreeSet::new(),
+            anchor: None,
+            snapshot: (None, Vec::new()),
+            track_index: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn select(&mut self, index: usize, modifiers: Modifiers) {
+        if index >= self.snapshot.1.len() {
+            return;
+        }
+
+        if modifiers.shift() {
+            let anchor = self.anchor.unwrap_or(index);
+            if !modifiers.command() {
+                self.selected.clear();
+            }
+            self.selected.extend(anchor.min(index)..=anchor.max(index));
+            self.anchor.get_or_insert(index);
+        } else if modifiers.command() {
+            if !self.selected.remove(&index) {
+                self.selected.insert(index);
+            }
+            self.anchor = Some(index);
+        } else {
+            self.selected.clear();
+            self.selected.insert(index);
+            self.anchor = Some(index);
+        }
+    }
+
+    pub(crate) fn select_all(&mut self) {
+        self.selected = (0..self.snapshot.1.len()).collect();
     }
 }
 
 impl PaneView for QueuePane {
-    fn update(&mut self, _player: &Player, _art: &mut ArtCache) {}
+    fn update(&mut self, player: &Player, _art: &mut ArtCache) {
+        let queue = player.queue();
+        let changed =
+            self.snapshot.0 != queue.current_id() || !queue.upcoming().iter().eq(&self.snapshot.1);
+        if changed {
+            self.snapshot = (
+                queue.current_id(),
+                queue.upcoming().iter().copied().collect(),
+            );
+            self.selected.clear();
+            self.anchor = None;
+        }
+    }
 
     fn view<'a>(&'a self, ctx: ViewContext<'a>) -> Element<'a, Message> {
         let player = ctx.player;
         let hovered_track = ctx.hovered_track;
+        let playlists = ctx.cached_playlists.unwrap_or_default();
+        let tracks = ctx.cached_tracks.unwrap_or_default();
+        let track = |id: i64| self.track_index.get(&id).and_then(|&i| tracks.get(i));
 
         let queue = player.queue();
         let current_id = queue.current_id();
@@ -33,7 +95,7 @@ impl PaneView for QueuePane {
         let mut track_list = column![].spacing(0);
 
         if let Some(current_id) = current_id {
-            if let Ok(Some(track)) = player.query_track_from_id(current_id) {
+            if let Some(track) = track(current_id) {
                 let is_hovered = hovered_track.as_ref() == Some(&current_id);
 
                 let track_inner = container(
@@ -90,6 +152,11 @@ impl PaneView for QueuePane {
 
         let upcoming = queue.upcoming();
         let total_upcoming = upcoming.len();
+        let selected_ids: Vec<i64> = self
+            .selected
+            .iter()
+            .filter_map(|&i| upcoming.get(i).copied())
+            .collect();
 
         for (idx, track_id) in upcoming.iter().enumerate() {
             if idx >= MAX_DISPLAY {
@@ -110,8 +177,9 @@ impl PaneView for QueuePane {
                 break;
             }
 
-            if let Ok(Some(track)) = player.query_track_from_id(*track_id) {
+            if let Some(track) = track(*track_id) {
                 let is_hovered = hovered_track.as_ref() == Some(track_id);
+                let is_selected = self.selected.contains(&idx);
 
                 let track_inner = container(
                     column![
@@ -136,28 +204,52 @@ impl PaneView for QueuePane {
                 .width(Length::Fill)
                 .style(move |theme: &Theme| {
                     let palette = theme.extended_palette();
+                    let (background, text_color) = if is_selected {
+                        (palette.primary.strong.color, palette.primary.strong.text)
+                    } else if is_hovered {
+                        (palette.primary.weak.color, palette.primary.weak.text)
+                    } else {
+                        (palette.background.base.color, palette.background.base.text)
+                    };
                     container::Style {
-                        text_color: Some(palette.background.base.text),
-                        background: if is_hovered {
-                            Some(palette.primary.weak.color.into())
-                        } else {
-                            Some(palette.background.base.color.into())
-                        },
+                        text_color: Some(text_color),
+                        background: Some(background.into()),
                         ..Default::default()
                     }
                 });
 
-                let track_content =
-                    mouse_area(track_inner).on_move(move |_| Message::TrackHovered(*track_id));
+                let track_content = mouse_area(track_inner)
+                    .on_move(move |_| Message::TrackHovered(*track_id))
+                    .on_press(Message::Queue(QueueMessage::Select(idx)));
+
+                let (indices, targets): (Vec<usize>, &[i64]) = if is_selected {
+                    (self.selected.iter().copied().collect(), &selected_ids)
+                } else {
+                    (vec![idx], std::slice::from_ref(track_id))
+                };
 
                 let mut items = vec![];
-                if idx > 0 {
+                if idx > 0 && indices.len() == 1 {
                     items.push(MenuElement::button(
                         "Move to Top",
                         Message::MoveToQueueFront(idx),
                     ));
                 }
-                items.push(MenuElement::button("Remove", Message::RemoveFromQueue(idx)));
+                items.push(MenuElement::button(
+                    if indices.len() > 1 {
+                        format!("Remove ({} tracks)", indices.len())
+                    } else {
+                        "Remove".to_string()
+                    },
+                    Message::RemoveFromQueue(indices),
+                ));
+                items.push(MenuElement::Separator);
+                items.extend(track_actions(targets, playlists));
+                items.push(MenuElement::Separator);
+                items.push(MenuElement::button(
+                    "Select all",
+                    Message::Queue(QueueMessage::SelectAll),
+                ));
                 items.push(MenuElement::Separator);
                 items.push(MenuElement::button(
                     "Shuffle Queue",
@@ -194,6 +286,24 @@ impl PaneView for QueuePane {
         .into()
     }
 
+    fn invalidate_cache(&mut self) {
+        self.track_index.clear();
+    }
+
+    fn library_loaded(
+        &mut self,
+        tracks: &[Track],
+        _albums: &[Album],
+        _artists: &[Artist],
+        _playlists: &[Playlist],
+    ) {
+        self.track_index = tracks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| Some((t.id()?, i)))
+            .collect();
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
@@ -1,10 +1,19 @@
-use iced::widget::{column, text};
-use iced::{Element, Theme};
-use verse_core::Player;
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use iced::widget::{column, container, row, space, text};
+use iced::{Color, Element, Length, Point, Rectangle, Size, Theme, mouse};
+use verse_core::{LoopRegion, MIN_LOOP_LENGTH, Player};
 
 use crate::app::Message;
 use crate::art_cache::ArtCache;
+use crate::formatters::format_duration;
 use crate::pane_view::{PaneView, ViewContext};
+use crate::widgets::chapter_ticks::draw_ticks;
+use crate::widgets::menu::{menu_item, menu_label, menu_separator, styled_menu};
+use crate::widgets::menu_button::MenuButton;
+
+const RAIL_HEIGHT: f32 = 4.0;
+const MARKER_GRAB: f32 = 8.0;
+const SPEED_STEPS: [f64; 3] = [0.01, 0.02, 0.05];
 
 #[derive(Debug, Clone)]
 pub struct TimelinePane;
@@ -18,21 +27,116 @@ impl TimelinePane {
 impl PaneView for TimelinePane {
     fn update(&mut self, _player: &Player, _art: &mut ArtCache) {}
 
-    fn view<'a>(&'a self, _ctx: ViewContext<'a>) -> Element<'a, Message> {
-        column![
-            text("Timeline / Seek Bar")
-                .size(16)
-                .style(|theme: &Theme| text::Style {
-                    color: Some(theme.extended_palette().background.base.text),
-                }),
-            text("0:00 ━━━━━━━━━━ 3:45")
-                .size(14)
-                .style(|theme: &Theme| text::Style {
-                    color: Some(theme.extended_palette().background.base.text),
-                }),
+    fn view<'a>(&'a self, ctx: ViewContext<'a>) -> Element<'a, Message> {
+        let player = ctx.player;
+        let track = player.clone_current_track();
+        let duration = track.as_ref().map(|t| t.duration() as f64).unwrap_or(0.0);
+        let chapters = player.chapters().iter().map(|c| c.start).collect();
+        let position = player.position();
+        let region = player.loop_region();
+
+        let timeline = Canvas::new(Timeline {
+            position,
+            duration,
+            region,
+            chapters,
+        })
+        .width(Length::Fill)
+        .height(Length::Fixed(36.0));
+
+        let label_style = |theme: &Theme| text::Style {
+            color: Some(theme.extended_palette().background.base.text),
+        };
+
+        let loop_label = match region {
+            Some(r) => format!(
+                "A {}  B {}",
+                format_duration(r.start as f32),
+                format_duration(r.end as f32)
+            ),
+            None => "No loop".to_string(),
+        };
+
+        let set_a = LoopRegion::new(
+            position,
+            region
+                .map(|r| r.end)
+                .unwrap_or(duration)
+                .max(position + MIN_LOOP_LENGTH),
+        );
+        let set_b = LoopRegion::new(region.map(|r| r.start).unwrap_or(0.0), position);
+        let speed_step = region.map(|r| r.speed_step).unwrap_or(0.0);
+
+        let mut loop_menu = column![
+            menu_item(
+                "Set A here",
+                Message::Timeline(TimelineMessage::SetLoop(set_a.with_speed_step(speed_step))),
+            ),
+            menu_item(
+                "Set B here",
+                Message::Timeline(TimelineMessage::SetLoop(set_b.with_speed_step(speed_step))),
+            ),
+        ];
+
+        if let Some(r) = region {
+            loop_menu = loop_menu
+                .push(menu_separator())
+                .push(menu_label("Speed up each loop"));
+            loop_menu = loop_menu.push(menu_item(
+                if r.speed_step == 0.0 {
+                    "Off (current)"
+                } else {
+                    "Off"
+                },
+                Message::Timeline(TimelineMessage::SetLoop(r.with_speed_step(0.0))),
+            ));
+            for step in SPEED_STEPS {
+                let label = if r.speed_step == step {
+                    format!("+{}% (current)", (step * 100.0) as u32)
+                } else {
+                    format!("+{}%", (step * 100.0) as u32)
+                };
+                loop_menu = loop_menu.push(menu_item(
+                    label,
+                    Message::Timeline(TimelineMessage::SetLoop(r.with_speed_step(step))),
+                ));
+            }
+            loop_menu = loop_menu.push(menu_separator()).push(menu_item(
+                "Clear loop",
+                Message::Timeline(TimelineMessage::ClearLoop),
+            ));
+        }
+
+        let loop_button = MenuButton::new(
+            include_bytes!("../../../assets/icons/ab_loop.svg"),
+            styled_menu(loop_menu),
+        );
+
+        let info = row![
+            text(format_duration(position as f32))
+                .size(12)
+                .style(label_style),
+            space().width(Length::Fill),
+            text(loop_label).size(12).style(label_style),
+            space().width(Length::Fill),
+            text(format_duration(duration as f32))
+                .size(12)
+                .style(label_style),
         ]
-        .spacing(10)
-        .padding(20)
+        .align_y(iced::Alignment::Center);
+
+        container(
+            row![
+                loop_button,
+                column![timeline, info].spacing(4).width(Length::Fill)
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(10)
+        .center_y(Length::Fill)
         .into()
     }
 
@@ -44,3 +148,201 @@ impl PaneView for TimelinePane {
         Box::new(self.clone())
     }
 }
+
+#[derive(Debug, Clone)]
+pub enum TimelineMessage {
+    Seek(f64),
+    SetLoop(LoopRegion),
+    ClearLoop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    Playhead(f64),
+    Start(f64),
+    End(f64),
+}
+
+struct Timeline {
+    position: f64,
+    duration: f64,
+    region: Option<LoopRegion>,
+    chapters: Vec<f32>,
+}
+
+impl Timeline {
+    fn to_x(&self, time: f64, bounds: Rectangle) -> f32 {
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        (time / self.duration).clamp(0.0, 1.0) as f32 * bounds.width
+    }
+
+    fn to_time(&self, x: f32, bounds: Rectangle) -> f64 {
+        (x / bounds.width).clamp(0.0, 1.0) as f64 * self.duration
+    }
+
+    fn grab(&self, x: f32, bounds: Rectangle) -> Drag {
+        let time = self.to_time(x, bounds);
+
+        if let Some(r) = self.region {
+            let to_start = (self.to_x(r.start, bounds) - x).abs();
+            let to_end = (self.to_x(r.end, bounds) - x).abs();
+
+            if to_start <= MARKER_GRAB && to_start <= to_end {
+                return Drag::Start(r.start);
+            }
+            if to_end <= MARKER_GRAB {
+                return Drag::End(r.end);
+            }
+        }
+
+        Drag::Playhead(time)
+    }
+
+    fn dragged(&self, drag: Drag, x: f32, bounds: Rectangle) -> Drag {
+        let time = self.to_time(x, bounds);
+
+        match (drag, self.region) {
+            (Drag::Start(_), Some(r)) => Drag::Start(time.min(r.end - MIN_LOOP_LENGTH)),
+            (Drag::End(_), Some(r)) => Drag::End(time.max(r.start + MIN_LOOP_LENGTH)),
+            _ => Drag::Playhead(time),
+        }
+    }
+
+    fn released(&self, drag: Drag) -> TimelineMessage {
+        match (drag, self.region) {
+            (Drag::Start(start), Some(r)) => TimelineMessage::SetLoop(
+                LoopRegion::new(start, r.end).with_speed_step(r.speed_step),
+            ),
+            (Drag::End(end), Some(r)) => TimelineMessage::SetLoop(
+                LoopRegion::new(r.start, end).with_speed_step(r.speed_step),
+            ),
+            (Drag::Playhead(t) | Drag::Start(t) | Drag::End(t), _) => TimelineMessage::Seek(t),
+        }
+    }
+}
+
+impl canvas::Program<Message> for Timeline {
+    type State = Option<Drag>;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        if self.duration <= 0.0 {
+            *state = None;
+            return None;
+        }
+
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let cursor = cursor.position_in(bounds)?;
+                *state = Some(self.grab(cursor.x, bounds));
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            canvas::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let drag = (*state)?;
+                let x = cursor.position()?.x - bounds.x;
+                *state = Some(self.dragged(drag, x, bounds));
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let drag = state.take()?;
+                Some(canvas::Action::publish(Message::Timeline(self.released(drag))).and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &iced::Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+
+        let mut position = self.position;
+        let mut region = self.region;
+
+        match (*state, region.as_mut()) {
+            (Some(Drag::Playhead(t)), _) => position = t,
+            (Some(Drag::Start(t)), Some(r)) => r.start = t,
+            (Some(Drag::End(t)), Some(r)) => r.end = t,
+            _ => {}
+        }
+
+        let mid = bounds.height / 2.0;
+        let rail_top = mid - RAIL_HEIGHT / 2.0;
+
+        frame.fill_rectangle(
+            Point::new(0.0, rail_top),
+            Size::new(bounds.width, RAIL_HEIGHT),
+            palette.background.strong.color,
+        );
+
+        if let Some(r) = region {
+            let start_x = self.to_x(r.start, bounds);
+            let end_x = self.to_x(r.end, bounds);
+            let accent = palette.primary.weak.color;
+
+            frame.fill_rectangle(
+                Point::new(start_x, 0.0),
+                Size::new(end_x - start_x, bounds.height),
+                Color { a: 0.25, ..accent },
+            );
+
+            for x in [start_x, end_x] {
+                frame.stroke(
+                    &Path::line(Point::new(x, 0.0), Point::new(x, bounds.height)),
+                    Stroke::default()
+                        .with_width(2.0)
+                        .with_color(palette.primary.base.color),
+                );
+            }
+        }
+
+        draw_ticks(&mut frame, &self.chapters, self.duration as f32, theme);
+
+        let played_x = self.to_x(position, bounds);
+
+        frame.fill_rectangle(
+            Point::new(0.0, rail_top),
+            Size::new(played_x, RAIL_HEIGHT),
+            palette.primary.strong.color,
+        );
+
+        frame.fill(
+            &Path::circle(Point::new(played_x, mid), 5.0),
+            palette.primary.strong.color,
+        );
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match state {
+            Some(Drag::Start(_) | Drag::End(_)) => mouse::Interaction::ResizingHorizontally,
+            Some(Drag::Playhead(_)) => mouse::Interaction::Grabbing,
+            None => match cursor.position_in(bounds) {
+                Some(p) if self.duration > 0.0 => match self.grab(p.x, bounds) {
+                    Drag::Start(_) | Drag::End(_) => mouse::Interaction::ResizingHorizontally,
+                    Drag::Playhead(_) => mouse::Interaction::Pointer,
+                },
+                _ => mouse::Interaction::default(),
+            },
+        }
+    }
+}
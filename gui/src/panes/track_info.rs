@@ -1,12 +1,14 @@
+use iced::alignment::Horizontal;
 use iced::font::Weight;
-use iced::widget::{column, container, row, text};
-use iced::{Element, Font, Length, Theme};
-use verse_core::Player;
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Font, Length, Theme};
+use verse_core::{Chapter, Player};
 
 use crate::app::Message;
 use crate::art_cache::ArtCache;
 use crate::formatters;
 use crate::pane_view::{PaneView, ViewContext};
+use crate::styles::row_button_style;
 
 #[derive(Debug, Clone)]
 pub struct TrackInfoPane;
@@ -28,12 +30,16 @@ impl PaneView for TrackInfoPane {
                 .into();
         };
 
-        let title_text = text(track.title().unwrap_or("-").to_string())
-            .size(18)
-            .font(Font {
-                weight: Weight::Bold,
-                ..Default::default()
-            });
+        let title = ctx
+            .work_titles
+            .then(|| track.work_title())
+            .flatten()
+            .unwrap_or_else(|| track.title().unwrap_or("-").to_string());
+
+        let title_text = text(title).size(18).font(Font {
+            weight: Weight::Bold,
+            ..Default::default()
+        });
 
         let artist_text = text(track.track_artist().unwrap_or("-").to_string()).size(15);
 
@@ -51,6 +57,13 @@ impl PaneView for TrackInfoPane {
             text("").size(13)
         };
 
+        let conductor = track.conductor().map(|c| format!("cond. {c}"));
+        let classical_parts: Vec<_> = [track.composer(), conductor.as_deref(), track.performer()]
+            .into_iter()
+            .flatten()
+            .map(|s| s.to_string())
+            .collect();
+
         let duration_text = text(formatters::format_duration(track.duration()))
             .size(13)
             .style(secondary_style);
@@ -76,7 +89,14 @@ impl PaneView for TrackInfoPane {
             .size(13)
             .style(secondary_style);
 
-        let main_info = column![title_text, artist_text, album_genre].spacing(6);
+        let mut main_info = column![title_text, artist_text, album_genre].spacing(6);
+        if !classical_parts.is_empty() {
+            main_info = main_info.push(
+                text(classical_parts.join(" • "))
+                    .size(13)
+                    .style(secondary_style),
+            );
+        }
 
         let technical_info = column![
             row![
@@ -89,12 +109,48 @@ impl PaneView for TrackInfoPane {
         ]
         .spacing(6);
 
-        container(column![main_info, technical_info].spacing(10).padding(10))
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x(Length::Fill)
-            .center_y(Length::Fill)
-            .into()
+        let info = column![main_info, technical_info].spacing(10).padding(10);
+
+        let chapters = ctx.player.chapters();
+        if chapters.is_empty() {
+            return container(info)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into();
+        }
+
+        let current = ctx.player.current_chapter();
+        let header = row![
+            text(format!("Chapters ({})", chapters.len()))
+                .size(13)
+                .width(Length::Fill),
+            button(text("‹").size(14))
+                .padding([2, 8])
+                .on_press(Message::TrackInfo(TrackInfoMessage::Previous)),
+            button(text("›").size(14))
+                .padding([2, 8])
+                .on_press(Message::TrackInfo(TrackInfoMessage::Next)),
+        ]
+        .spacing(6)
+        .padding([0, 10])
+        .align_y(Alignment::Center);
+
+        let mut list = column![].spacing(0);
+        for (index, chapter) in chapters.iter().enumerate() {
+            list = list.push(chapter_row(index, chapter, current == Some(index)));
+        }
+
+        column![
+            info,
+            header,
+            scrollable(list).width(Length::Fill).height(Length::Fill)
+        ]
+        .spacing(6)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
     }
 
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
@@ -106,6 +162,48 @@ impl PaneView for TrackInfoPane {
     }
 }
 
+#[derive(Debug, Clone)]
+pub enum TrackInfoMessage {
+    Previous,
+    Next,
+    JumpTo(usize),
+}
+
+fn chapter_row<'a>(index: usize, chapter: &Chapter, current: bool) -> Element<'a, Message> {
+    let weight = if current {
+        Weight::Bold
+    } else {
+        Weight::Normal
+    };
+
+    button(
+        row![
+            text(formatters::format_duration(chapter.start))
+                .size(11)
+                .style(secondary_style)
+                .width(Length::Fixed(46.0)),
+            text(chapter.title.clone())
+                .size(13)
+                .font(Font {
+                    weight,
+                    ..Default::default()
+                })
+                .width(Length::Fill),
+            text(formatters::format_duration(chapter.end - chapter.start))
+                .size(11)
+                .style(secondary_style)
+                .align_x(Horizontal::Right),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+    )
+    .padding([6, 12])
+    .width(Length::Fill)
+    .style(row_button_style)
+    .on_press(Message::TrackInfo(TrackInfoMessage::JumpTo(index)))
+    .into()
+}
+
 fn secondary_style(theme: &Theme) -> text::Style {
     text::Style {
         color: Some(theme.extended_palette().background.weak.text),
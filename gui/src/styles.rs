@@ -63,6 +63,24 @@ pub fn icon_button_style(theme: &Theme, status: button::Status) -> button::Style
     }
 }
 
+pub fn row_button_style(theme: &Theme, status: button::Status) -> button::Style {
+    let palette = theme.extended_palette();
+    button::Style {
+        background: match status {
+            button::Status::Hovered | button::Status::Pressed => Some(
+                Color {
+                    a: 0.15,
+                    ..palette.primary.weak.color
+                }
+                .into(),
+            ),
+            _ => None,
+        },
+        text_color: palette.background.base.text,
+        ..Default::default()
+    }
+}
+
 pub fn menu_container_style(theme: &Theme) -> container::Style {
     let palette = theme.extended_palette();
     container::Style {
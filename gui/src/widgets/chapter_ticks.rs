@@ -0,0 +1,53 @@
+use iced::widget::canvas::{self, Canvas, Frame, Geometry};
+use iced::{Element, Length, Point, Rectangle, Size, Theme, mouse};
+
+const TICK_WIDTH: f32 = 2.0;
+const TICK_HEIGHT: f32 = 10.0;
+
+pub fn chapter_ticks<'a, Message: 'a>(starts: Vec<f32>, duration: f32) -> Element<'a, Message> {
+    Canvas::new(ChapterTicks { starts, duration })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+pub fn draw_ticks(frame: &mut Frame, starts: &[f32], duration: f32, theme: &Theme) {
+    if duration <= 0.0 {
+        return;
+    }
+
+    let size = frame.size();
+    let top = (size.height - TICK_HEIGHT) / 2.0;
+    let color = theme.extended_palette().background.base.text;
+
+    for &start in starts.iter().filter(|&&s| s > 0.0 && s < duration) {
+        let x = start / duration * size.width;
+        frame.fill_rectangle(
+            Point::new(x - TICK_WIDTH / 2.0, top),
+            Size::new(TICK_WIDTH, TICK_HEIGHT),
+            color,
+        );
+    }
+}
+
+struct ChapterTicks {
+    starts: Vec<f32>,
+    duration: f32,
+}
+
+impl<Message> canvas::Program<Message> for ChapterTicks {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        draw_ticks(&mut frame, &self.starts, self.duration, theme);
+        vec![frame.into_geometry()]
+    }
+}
@@ -1,4 +1,5 @@
 use iced::widget::slider::{self, Handle, HandleShape, Rail, Slider, Status};
+use iced::widget::vertical_slider::VerticalSlider;
 use iced::{Border, Color, Theme};
 
 pub fn hover_slider<'a, Message: Clone + 'a>(
@@ -6,10 +7,19 @@ pub fn hover_slider<'a, Message: Clone + 'a>(
     value: f32,
     on_change: impl Fn(f32) -> Message + 'a,
 ) -> Slider<'a, f32, Message> {
-    Slider::new(range, value, on_change).style(move |theme, status| style(theme, status, value))
+    Slider::new(range, value, on_change)
+        .style(move |theme, status| style(theme, status, value == 0.0))
 }
 
-fn style(theme: &Theme, status: Status, value: f32) -> slider::Style {
+pub fn hover_vertical_slider<'a, Message: Clone + 'a>(
+    range: std::ops::RangeInclusive<f32>,
+    value: f32,
+    on_change: impl Fn(f32) -> Message + 'a,
+) -> VerticalSlider<'a, f32, Message> {
+    VerticalSlider::new(range, value, on_change).style(|theme, status| style(theme, status, false))
+}
+
+fn style(theme: &Theme, status: Status, hide_idle_handle: bool) -> slider::Style {
     let palette = theme.extended_palette();
 
     let handle = match status {
@@ -21,7 +31,7 @@ fn style(theme: &Theme, status: Status, value: f32) -> slider::Style {
         },
         Status::Active => Handle {
             shape: HandleShape::Circle {
-                radius: if value == 0.0 { 0.0 } else { 4.0 },
+                radius: if hide_idle_handle { 0.0 } else { 4.0 },
             },
             background: palette.primary.strong.color.into(),
             border_width: 0.0,
@@ -1,4 +1,5 @@
 pub mod canvas_button;
+pub mod chapter_ticks;
 pub mod hover_slider;
 pub mod menu;
 pub mod menu_button;
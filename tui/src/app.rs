@@ -1,6 +1,6 @@
 use color_eyre::eyre::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
-use verse_core::Player;
+use verse_core::{Paths, Player};
 use ratatui::{DefaultTerminal, Frame};
 use std::time::Duration;
 
@@ -10,10 +10,10 @@ pub struct App {
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
+    pub fn new(paths: Paths) -> Result<Self> {
         Ok(Self {
             exit: false,
-            player: Player::new()?,
+            player: Player::builder().paths(paths).build()?,
         })
     }
 
@@ -1,11 +1,13 @@
 mod app;
 
 use color_eyre::eyre::Result;
+use verse_core::LaunchOptions;
 
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let paths = LaunchOptions::from_args(std::env::args().skip(1))?.paths()?;
     let terminal = ratatui::init();
-    let result = app::App::new()?.run(terminal);
+    let result = app::App::new(paths)?.run(terminal);
     ratatui::restore();
     result
 }